@@ -0,0 +1,163 @@
+use rodio::{OutputStream, Sink, Source};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const SAMPLE_RATE: u32 = 48000;
+const CHANNELS: u16 = 2;
+
+// Enough queued audio to ride out a slow frame, in interleaved samples
+const TARGET_BUFFERED: usize = (SAMPLE_RATE as usize * CHANNELS as usize) / 20;
+const CAPACITY: usize = TARGET_BUFFERED * 4;
+
+// How far the emulated sample rate is nudged either way to hold the target
+const MAX_RATE_ADJUSTMENT: f64 = 0.005;
+
+// Don't wait forever on a device that has stopped pulling samples
+const MAX_WAIT: Duration = Duration::from_millis(100);
+
+// How quickly the output falls silent when the buffer runs dry
+const UNDERRUN_DECAY: f32 = 0.995;
+
+pub const DEFAULT_VOLUME: f32 = 1.0;
+const VOLUME_STEP: f32 = 0.1;
+
+type RingBuffer = Arc<Mutex<VecDeque<f32>>>;
+
+/// Plays samples from the ring buffer as they arrive. When the buffer runs
+/// dry it fades out the last sample rather than dropping straight to silence.
+struct BufferSource {
+    buffer: RingBuffer,
+    channel: usize,
+    last: [f32; CHANNELS as usize],
+}
+
+impl Iterator for BufferSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let channel = self.channel;
+
+        self.channel = (self.channel + 1) % CHANNELS as usize;
+
+        self.last[channel] = match self.buffer.lock().unwrap().pop_front() {
+            Some(sample) => sample,
+            None => self.last[channel] * UNDERRUN_DECAY,
+        };
+
+        Some(self.last[channel])
+    }
+}
+
+impl Source for BufferSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        CHANNELS
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Streams emulated audio to the default output device.
+///
+/// Emulation follows the device: the main loop waits whenever more than the
+/// target amount of audio is queued, and the rate the emulator generates
+/// samples at is nudged to keep the buffer near that target, so small drifts
+/// between the two clocks never under- or overrun it.
+pub struct Audio {
+    buffer: RingBuffer,
+    muted: bool,
+    sink: Sink,
+    volume: f32,
+    _stream: OutputStream,
+}
+
+impl Audio {
+    pub fn open(volume: f32, muted: bool) -> Result<Self, String> {
+        let (stream, handle) = OutputStream::try_default().map_err(|e| e.to_string())?;
+        let sink = Sink::try_new(&handle).map_err(|e| e.to_string())?;
+
+        let buffer = Arc::new(Mutex::new(VecDeque::with_capacity(CAPACITY)));
+
+        sink.append(BufferSource {
+            buffer: buffer.clone(),
+            channel: 0,
+            last: [0.0; CHANNELS as usize],
+        });
+
+        let mut audio = Audio {
+            buffer,
+            muted,
+            sink,
+            volume: volume.clamp(0.0, 1.0),
+            _stream: stream,
+        };
+
+        audio.update_volume();
+
+        Ok(audio)
+    }
+
+    fn buffered(&self) -> usize {
+        self.buffer.lock().unwrap().len()
+    }
+
+    /// Queues samples, dropping any that don't fit.
+    pub fn push(&self, samples: &[f32]) {
+        let mut buffer = self.buffer.lock().unwrap();
+
+        let room = CAPACITY.saturating_sub(buffer.len());
+
+        // Keep whole left and right pairs
+        buffer.extend(&samples[..room.min(samples.len()) & !1]);
+    }
+
+    /// The rate the emulator should generate samples at: a little faster
+    /// while the buffer is short, a little slower while it's long.
+    pub fn sample_rate(&self) -> u32 {
+        let error = (TARGET_BUFFERED as f64 - self.buffered() as f64) / TARGET_BUFFERED as f64;
+
+        (SAMPLE_RATE as f64 * (1.0 + error.clamp(-1.0, 1.0) * MAX_RATE_ADJUSTMENT)).round() as u32
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+
+        self.update_volume();
+    }
+
+    fn update_volume(&mut self) {
+        self.sink.set_volume(if self.muted { 0.0 } else { self.volume });
+    }
+
+    pub fn volume_down(&mut self) {
+        self.volume = (self.volume - VOLUME_STEP).max(0.0);
+
+        self.update_volume();
+    }
+
+    pub fn volume_up(&mut self) {
+        self.volume = (self.volume + VOLUME_STEP).min(1.0);
+
+        self.update_volume();
+    }
+
+    /// Waits until the device has played the buffer down to its target.
+    pub fn wait(&self) {
+        let start = Instant::now();
+
+        while self.buffered() > TARGET_BUFFERED && start.elapsed() < MAX_WAIT {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -1,42 +1,488 @@
-extern crate sdl2; 
+extern crate sdl2;
 
-use sdl2::pixels::Color;
+mod audio;
+
+use audio::{Audio, DEFAULT_VOLUME, SAMPLE_RATE};
+use emulation::{
+    apu::WavRecorder,
+    boot::BootRomComponent,
+    cartridge::RumbleEvent,
+    display::{DmgPalette, RgbConverter},
+    joypad::Button,
+    ppu::{SCREEN_HEIGHT, SCREEN_WIDTH},
+    serial::{LinkCable, SocketLinkCable},
+    sgb::{SGB_SCREEN_HEIGHT, SGB_SCREEN_WIDTH},
+    Cartridge,
+    Emulator,
+    Model,
+};
+use sdl2::controller::{Button as ControllerButton, GameController};
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::Texture;
+use std::env;
+use std::io;
+use std::process;
+use std::sync::mpsc::Receiver;
+use std::thread;
 use std::time::Duration;
- 
+
+const WINDOW_SCALE: u32 = 4;
+
+// Long enough to outlast a frame; the motor is stopped explicitly
+const RUMBLE_DURATION_MS: u32 = 1000;
+
+// Pacing when there's no audio to follow
+const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+const UNIX_SOCKET_PREFIX: &str = "unix:";
+
+const USAGE: &str = "Usage: desktop [ROM] [--model NAME] [--boot-rom PATH] [--palette NAME] [--color-correction] [--link-listen ADDRESS | --link-connect ADDRESS] [--volume PERCENT] [--mute] [--record-wav PATH] [--frames N]
+
+Models are dmg (the default), mgb, sgb, cgb and agb. Without a boot ROM, the
+cartridge starts in the state the model's boot ROM leaves behind. The sgb
+shows games inside its border, in the colors they ask for.
+Palettes for DMG games are green (the default), greyscale and pocket, or four
+RRGGBB colors separated by commas, lightest first. Color correction makes CGB
+games look as they do on the CGB's screen.
+Link cable addresses are host:port for TCP, or unix:PATH for a Unix socket.
+Recordings are made at a fixed sample rate, so the same input always gives the
+same file. With --frames the ROM runs for that many frames as fast as it can,
+with no window or sound, and then exits.
+While running, M toggles mute and - and = change the volume.";
+
+enum LinkMode {
+    Connect(String),
+    Listen(String),
+}
+
+#[derive(Default)]
+struct Options {
+    boot_rom_path: Option<String>,
+    color_correction: bool,
+    frames: Option<usize>,
+    link: Option<LinkMode>,
+    model: Model,
+    muted: bool,
+    palette: DmgPalette,
+    record_path: Option<String>,
+    rom_path: Option<String>,
+    volume: Option<f32>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--boot-rom" => options.boot_rom_path = Some(args.next().ok_or("--boot-rom needs a path")?),
+            "--color-correction" => options.color_correction = true,
+            "--frames" => {
+                let frames = args.next()
+                    .and_then(|frames| frames.parse::<usize>().ok())
+                    .ok_or("--frames needs a number of frames")?;
+
+                options.frames = Some(frames);
+            },
+            "--link-connect" | "--link-listen" => {
+                let address = args.next().ok_or(format!("{} needs an address", arg))?;
+
+                options.link = Some(if arg == "--link-listen" {
+                    LinkMode::Listen(address)
+                } else {
+                    LinkMode::Connect(address)
+                });
+            },
+            "--model" => {
+                let name = args.next().ok_or("--model needs a name")?;
+
+                options.model = parse_model(&name).ok_or(format!("Unknown model {}\n\n{}", name, USAGE))?;
+            },
+            "--mute" => options.muted = true,
+            "--palette" => {
+                let name = args.next().ok_or("--palette needs a name")?;
+
+                options.palette = parse_palette(&name).ok_or(format!("Unknown palette {}\n\n{}", name, USAGE))?;
+            },
+            "--record-wav" => options.record_path = Some(args.next().ok_or("--record-wav needs a path")?),
+            "--volume" => {
+                let volume = args.next()
+                    .and_then(|percent| percent.parse::<f32>().ok())
+                    .ok_or("--volume needs a percentage")?;
+
+                options.volume = Some(volume / 100.0);
+            },
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {}\n\n{}", arg, USAGE)),
+            _ => options.rom_path = Some(arg),
+        };
+    }
+
+    if options.frames.is_some() && options.rom_path.is_none() {
+        return Err(format!("--frames needs a ROM\n\n{}", USAGE));
+    }
+
+    Ok(options)
+}
+
+fn parse_model(name: &str) -> Option<Model> {
+    match name.to_ascii_lowercase().as_str() {
+        "dmg" => Some(Model::Dmg),
+        "mgb" => Some(Model::Mgb),
+        "sgb" => Some(Model::Sgb),
+        "cgb" => Some(Model::Cgb),
+        "agb" => Some(Model::Agb),
+        _ => None,
+    }
+}
+
+fn parse_palette(name: &str) -> Option<DmgPalette> {
+    match name.to_ascii_lowercase().as_str() {
+        "green" => Some(DmgPalette::Green),
+        "greyscale" | "grayscale" => Some(DmgPalette::Greyscale),
+        "pocket" => Some(DmgPalette::Pocket),
+        _ => {
+            let colors = name.split(',')
+                .map(|color| {
+                    let color = color.trim_start_matches('#');
+
+                    if color.len() != 6 {
+                        return None;
+                    }
+
+                    u32::from_str_radix(color, 16).ok()
+                })
+                .collect::<Option<Vec<u32>>>()?;
+
+            let shades: [u32; 4] = colors.try_into().ok()?;
+
+            Some(DmgPalette::Custom(shades.map(|color| {
+                let [_, red, green, blue] = color.to_be_bytes();
+
+                [red, green, blue]
+            })))
+        },
+    }
+}
+
+fn open_link_cable(mode: &LinkMode) -> io::Result<Box<dyn LinkCable>> {
+    let link_cable: Box<dyn LinkCable> = match mode {
+        LinkMode::Connect(address) => match address.strip_prefix(UNIX_SOCKET_PREFIX) {
+            Some(path) => Box::new(SocketLinkCable::connect_unix(path)?),
+            None => Box::new(SocketLinkCable::connect_tcp(address.as_str())?),
+        },
+        LinkMode::Listen(address) => {
+            println!("Waiting for the other end of the link cable on {}", address);
+
+            match address.strip_prefix(UNIX_SOCKET_PREFIX) {
+                Some(path) => Box::new(SocketLinkCable::listen_unix(path)?),
+                None => Box::new(SocketLinkCable::listen_tcp(address.as_str())?),
+            }
+        },
+    };
+
+    Ok(link_cable)
+}
+
+/// Loads the cartridge and either maps the boot ROM to run first or starts
+/// from where the model's boot ROM would have left off. Exits if the cartridge
+/// can't be loaded.
+fn load_cartridge(emulator: &mut Emulator, path: &str, boot_rom_path: Option<&str>) -> Receiver<RumbleEvent> {
+    let mut cartridge = match Cartridge::from_file(path) {
+        Ok(cartridge) => cartridge,
+        Err(e) => {
+            eprintln!("Failed to load cartridge: {}", e);
+
+            process::exit(1);
+        },
+    };
+
+    let rumble_events = cartridge.subscribe_rumble();
+
+    emulator.load_cartridge(cartridge);
+
+    match boot_rom_path.map(BootRomComponent::from_file) {
+        Some(Ok(boot_rom)) => emulator.load_boot_rom(boot_rom),
+        Some(Err(e)) => {
+            eprintln!("Failed to load boot ROM: {}", e);
+
+            emulator.skip_boot_rom();
+        },
+        None => emulator.skip_boot_rom(),
+    };
+
+    rumble_events
+}
+
+fn keyboard_button(keycode: Keycode) -> Option<Button> {
+    match keycode {
+        Keycode::Right => Some(Button::Right),
+        Keycode::Left => Some(Button::Left),
+        Keycode::Up => Some(Button::Up),
+        Keycode::Down => Some(Button::Down),
+        Keycode::Z => Some(Button::A),
+        Keycode::X => Some(Button::B),
+        Keycode::Backspace => Some(Button::Select),
+        Keycode::Return => Some(Button::Start),
+        _ => None,
+    }
+}
+
+fn controller_button(button: ControllerButton) -> Option<Button> {
+    match button {
+        ControllerButton::DPadRight => Some(Button::Right),
+        ControllerButton::DPadLeft => Some(Button::Left),
+        ControllerButton::DPadUp => Some(Button::Up),
+        ControllerButton::DPadDown => Some(Button::Down),
+        ControllerButton::A => Some(Button::A),
+        ControllerButton::B => Some(Button::B),
+        ControllerButton::Back => Some(Button::Select),
+        ControllerButton::Start => Some(Button::Start),
+        _ => None,
+    }
+}
+
+fn run_frame(emulator: &mut Emulator) -> bool {
+    if let Err(e) = emulator.run_frame() {
+        eprintln!("Emulation stopped at {:#06x}: {}", emulator.program_counter(), e);
+
+        return false;
+    }
+
+    true
+}
+
+/// Takes the audio from the last frame, writing it to the recording if there
+/// is one.
+fn record_audio(emulator: &mut Emulator, recorder: &mut Option<WavRecorder>) -> Vec<f32> {
+    let samples = emulator.take_audio_samples();
+
+    if let Some(writer) = recorder {
+        if let Err(e) = writer.write_samples(&samples) {
+            eprintln!("Stopped recording audio: {}", e);
+
+            *recorder = None;
+        }
+    }
+
+    samples
+}
+
+/// Runs the given number of frames as fast as possible, with no window or
+/// audio device, returning whether they all ran.
+fn run_headless(emulator: &mut Emulator, recorder: &mut Option<WavRecorder>, frames: usize) -> bool {
+    for _ in 0..frames {
+        if !run_frame(emulator) {
+            return false;
+        }
+
+        record_audio(emulator, recorder);
+    }
+
+    true
+}
+
+/// Writes out the battery save and finishes the recording before exiting.
+fn shut_down(emulator: &mut Emulator, recorder: Option<WavRecorder>) {
+    if let Some(cartridge) = emulator.cartridge_mut() {
+        if let Err(e) = cartridge.flush_battery_save() {
+            eprintln!("Failed to write battery save: {}", e);
+        }
+    }
+
+    if let Some(recorder) = recorder {
+        if let Err(e) = recorder.finish() {
+            eprintln!("Failed to finish recording audio: {}", e);
+        }
+    }
+}
+
+/// The size of the frames the model shows, the SGB's taking in its border.
+fn screen_size(model: Model) -> (usize, usize) {
+    match model {
+        Model::Sgb => (SGB_SCREEN_WIDTH, SGB_SCREEN_HEIGHT),
+        _ => (SCREEN_WIDTH, SCREEN_HEIGHT),
+    }
+}
+
+fn draw_frame(emulator: &Emulator, converter: &RgbConverter, texture: &mut Texture, width: usize) {
+    if let Some(frame) = converter.frame_rgb(emulator) {
+        texture.update(None, &frame, width * 3).unwrap();
+    }
+}
+
+fn apply_rumble(controller: &mut Option<GameController>, rumble_events: &Receiver<RumbleEvent>) {
+    for event in rumble_events.try_iter() {
+        if let Some(controller) = controller {
+            let strength = match event {
+                RumbleEvent::Started => u16::MAX,
+                RumbleEvent::Stopped => 0u16,
+            };
+
+            // Not every controller has motors
+            let _ = controller.set_rumble(strength, strength, RUMBLE_DURATION_MS);
+        }
+    }
+}
+
 pub fn main() {
+    let options = parse_options().unwrap_or_else(|message| {
+        eprintln!("{}", message);
+
+        process::exit(2);
+    });
+
+    let mut emulator = Emulator::builder().model(options.model).build();
+    let mut converter = RgbConverter::with_palette(options.palette);
+
+    converter.set_color_correction(options.color_correction);
+
+    let mut running = false;
+    let mut rumble_events = None;
+
+    if let Some(path) = &options.rom_path {
+        rumble_events = Some(load_cartridge(&mut emulator, path, options.boot_rom_path.as_deref()));
+        running = true;
+    }
+
+    if let Some(mode) = &options.link {
+        match open_link_cable(mode) {
+            Ok(link_cable) => emulator.connect_link_cable(link_cable),
+            Err(e) => eprintln!("Failed to open link cable: {}", e),
+        };
+    }
+
+    emulator.set_audio_sample_rate(SAMPLE_RATE);
+
+    let mut recorder = options.record_path.as_ref().and_then(|path| {
+        WavRecorder::create(path, SAMPLE_RATE)
+            .map_err(|e| eprintln!("Failed to create {}: {}", path, e))
+            .ok()
+    });
+
+    if let Some(frames) = options.frames {
+        let completed = run_headless(&mut emulator, &mut recorder, frames);
+
+        shut_down(&mut emulator, recorder);
+
+        process::exit(if completed { 0 } else { 1 });
+    }
+
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
- 
-    let window = video_subsystem.window("rust-sdl2 demo", 800, 600)
+    let game_controller_subsystem = sdl_context.game_controller().unwrap();
+
+    let mut audio = Audio::open(options.volume.unwrap_or(DEFAULT_VOLUME), options.muted)
+        .map_err(|e| eprintln!("No audio: {}", e))
+        .ok();
+
+    let mut controller = (0..game_controller_subsystem.num_joysticks().unwrap_or(0))
+        .find(|i| game_controller_subsystem.is_game_controller(*i))
+        .and_then(|i| game_controller_subsystem.open(i).ok());
+
+    let (width, height) = screen_size(options.model);
+
+    let window = video_subsystem.window("gameboy_rust", width as u32 * WINDOW_SCALE, height as u32 * WINDOW_SCALE)
         .position_centered()
         .build()
         .unwrap();
- 
+
     let mut canvas = window.into_canvas().build().unwrap();
- 
-    canvas.set_draw_color(Color::RGB(0, 255, 255));
-    canvas.clear();
-    canvas.present();
+    let texture_creator = canvas.texture_creator();
+
+    let mut texture = texture_creator
+        .create_texture_streaming(PixelFormatEnum::RGB24, width as u32, height as u32)
+        .unwrap();
+
     let mut event_pump = sdl_context.event_pump().unwrap();
-    let mut i = 0;
+    let mut link_error_reported = false;
+
     'running: loop {
-        i = (i + 1) % 255;
-        canvas.set_draw_color(Color::RGB(i, 64, 255 - i));
-        canvas.clear();
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit {..} |
                 Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
                     break 'running
                 },
+                Event::KeyDown { keycode: Some(keycode @ (Keycode::M | Keycode::Minus | Keycode::Equals)), repeat: false, .. } => {
+                    if let Some(audio) = &mut audio {
+                        match keycode {
+                            Keycode::M => audio.toggle_mute(),
+                            Keycode::Minus => audio.volume_down(),
+                            _ => audio.volume_up(),
+                        };
+                    }
+                },
+                Event::KeyDown { keycode: Some(keycode), repeat: false, .. } => {
+                    if let Some(button) = keyboard_button(keycode) {
+                        emulator.press_button(button);
+                    }
+                },
+                Event::KeyUp { keycode: Some(keycode), .. } => {
+                    if let Some(button) = keyboard_button(keycode) {
+                        emulator.release_button(button);
+                    }
+                },
+                Event::ControllerButtonDown { button, .. } => {
+                    if let Some(button) = controller_button(button) {
+                        emulator.press_button(button);
+                    }
+                },
+                Event::ControllerButtonUp { button, .. } => {
+                    if let Some(button) = controller_button(button) {
+                        emulator.release_button(button);
+                    }
+                },
                 _ => {}
             }
         }
         // The rest of the game loop goes here...
+        if running {
+            running = run_frame(&mut emulator);
+        }
+
+        if let Some(cartridge) = emulator.cartridge_mut() {
+            if let Err(e) = cartridge.autosave() {
+                eprintln!("Failed to write battery save: {}", e);
+            }
+        }
+
+        if !link_error_reported {
+            if let Some(e) = emulator.link_cable().and_then(|link_cable| link_cable.last_error()) {
+                eprintln!("Link cable disconnected: {}", e);
+
+                link_error_reported = true;
+            }
+        }
+
+        if let Some(rumble_events) = &rumble_events {
+            apply_rumble(&mut controller, rumble_events);
+        }
 
+        draw_frame(&emulator, &converter, &mut texture, width);
+
+        canvas.clear();
+        canvas.copy(&texture, None, None).unwrap();
         canvas.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+
+        let samples = record_audio(&mut emulator, &mut recorder);
+
+        match &audio {
+            Some(audio) if running => {
+                audio.push(&samples);
+
+                // A recording stays at the rate its header was written with
+                if recorder.is_none() {
+                    emulator.set_audio_sample_rate(audio.sample_rate());
+                }
+
+                audio.wait();
+            },
+            _ => thread::sleep(FRAME_DURATION),
+        };
     }
-}
\ No newline at end of file
+
+    shut_down(&mut emulator, recorder);
+}
@@ -1,2 +1,3 @@
 pub const PORT_REGISTER_START: u16 = 0xff00u16;
-pub const PROGRAM_COUNTER_START: u16 = 0x0000u16;
\ No newline at end of file
+pub const PROGRAM_COUNTER_START: u16 = 0x0000u16;
+pub const STACK_POINTER_START: u16 = 0xfffeu16;
\ No newline at end of file
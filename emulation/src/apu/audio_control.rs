@@ -0,0 +1,11 @@
+/// The bits of NR52 (0xFF26): the master switch, and a read-only status bit
+/// per channel.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum AudioMasterControlFlag {
+    Channel1Switch = 0b00000001,
+    Channel2Switch = 0b00000010,
+    Channel3Switch = 0b00000100,
+    Channel4Switch = 0b00001000,
+    MasterSwitch = 0b10000000,
+}
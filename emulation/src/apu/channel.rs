@@ -0,0 +1,27 @@
+/// What the mixer and frame sequencer need from each of the four channels.
+pub trait Channel {
+    /// Counts down the length, disabling the channel once it runs out.
+    fn clock_length(&mut self);
+
+    /// Whether the channel's DAC is powered. A channel can't play without it.
+    fn dac_enabled(&self) -> bool;
+
+    /// Whether the channel is playing, as reported in NR52.
+    fn enabled(&self) -> bool;
+
+    /// The digital output, from 0 to 15.
+    fn output(&self) -> u8;
+
+    /// Advances the channel by a number of clocks at 4.19 MHz.
+    fn step(&mut self, clocks: u32);
+
+    /// The channel's output through its DAC, from -1.0 to 1.0. A powered-off
+    /// DAC outputs nothing.
+    fn analog_output(&self) -> f32 {
+        if !self.dac_enabled() {
+            return 0.0f32;
+        }
+
+        1.0f32 - self.output() as f32 / 7.5f32
+    }
+}
@@ -0,0 +1,79 @@
+const MAX_VOLUME: u8 = 0x0fu8;
+
+const INITIAL_VOLUME_SHIFT: u8 = 4u8;
+const INCREASING_BIT: u8 = 0b00001000;
+const PERIOD_MASK: u8 = 0b00000111;
+const DAC_MASK: u8 = 0b11111000;
+
+/// The volume envelope of NRx2, stepped at 64 Hz by the frame sequencer.
+#[derive(Default)]
+pub struct Envelope {
+    register: u8,
+    timer: u8,
+    volume: u8,
+}
+
+impl Envelope {
+    pub fn new() -> Self {
+        Envelope {
+            register: 0x00u8,
+            timer: 0x00u8,
+            volume: 0x00u8,
+        }
+    }
+
+    /// Moves the volume one step every period, until it reaches 0 or 15. A
+    /// period of 0 holds it.
+    pub fn clock(&mut self) {
+        let period = self.period();
+
+        if period == 0 {
+            return;
+        }
+
+        self.timer = self.timer.saturating_sub(1);
+
+        if self.timer > 0 {
+            return;
+        }
+
+        self.timer = period;
+
+        if self.increasing() && self.volume < MAX_VOLUME {
+            self.volume += 1;
+        } else if !self.increasing() && self.volume > 0 {
+            self.volume -= 1;
+        }
+    }
+
+    /// The channel's DAC is powered whenever the top 5 bits of NRx2 aren't
+    /// all clear.
+    pub fn dac_enabled(&self) -> bool {
+        self.register & DAC_MASK > 0
+    }
+
+    fn increasing(&self) -> bool {
+        self.register & INCREASING_BIT > 0
+    }
+
+    fn period(&self) -> u8 {
+        self.register & PERIOD_MASK
+    }
+
+    pub fn register(&self) -> u8 {
+        self.register
+    }
+
+    pub fn trigger(&mut self) {
+        self.timer = self.period();
+        self.volume = self.register >> INITIAL_VOLUME_SHIFT;
+    }
+
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    pub fn write(&mut self, value: u8) {
+        self.register = value;
+    }
+}
@@ -0,0 +1,76 @@
+/// Silences a channel once it has played for its length, counting down at
+/// 256 Hz while enabled by bit 6 of NRx4.
+#[derive(Default)]
+pub struct LengthCounter {
+    enabled: bool,
+    maximum: u16,
+    remaining: u16,
+}
+
+impl LengthCounter {
+    /// `maximum` is 64, or 256 for the wave channel.
+    pub fn new(maximum: u16) -> Self {
+        LengthCounter {
+            enabled: false,
+            maximum,
+            remaining: 0u16,
+        }
+    }
+
+    /// Counts down, returning whether the length just ran out.
+    pub fn clock(&mut self) -> bool {
+        if !self.enabled || self.remaining == 0 {
+            return false;
+        }
+
+        self.remaining -= 1;
+
+        self.remaining == 0
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Loads the length from the value written to NRx1.
+    pub fn load(&mut self, value: u8) {
+        self.remaining = self.maximum - (value as u16 & (self.maximum - 1));
+    }
+
+    pub fn remaining(&self) -> u16 {
+        self.remaining
+    }
+
+    /// Sets the enable bit from NRx4. Enabling it when the frame sequencer's
+    /// next step won't clock lengths counts down once straight away,
+    /// returning whether that ran it out.
+    pub fn set_enabled(&mut self, enabled: bool, extra_clock: bool) -> bool {
+        let was_enabled = self.enabled;
+
+        self.enabled = enabled;
+
+        !was_enabled && extra_clock && self.clock()
+    }
+
+    /// Triggering a channel whose length ran out starts it from the maximum,
+    /// less the extra clock if one is due.
+    pub fn trigger(&mut self, extra_clock: bool) {
+        if self.remaining == 0 {
+            self.remaining = self.maximum;
+
+            if self.enabled && extra_clock {
+                self.remaining -= 1;
+            }
+        }
+    }
+
+    /// Powering off the APU clears the enable bit. The DMG keeps the length
+    /// itself, while the CGB clears it too.
+    pub fn power_off(&mut self, keep_length: bool) {
+        self.enabled = false;
+
+        if !keep_length {
+            self.remaining = 0u16;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod audio_control;
+mod channel;
+mod envelope;
+mod length_counter;
+mod noise_channel;
+mod sound_component;
+mod square_channel;
+mod sweep;
+mod wav_recorder;
+mod wave_channel;
+
+pub use audio_control::AudioMasterControlFlag;
+pub use channel::Channel;
+pub use envelope::Envelope;
+pub use length_counter::LengthCounter;
+pub use noise_channel::NoiseChannel;
+pub use sound_component::{SoundComponent, DEFAULT_SAMPLE_RATE};
+pub use square_channel::SquareChannel;
+pub use sweep::Sweep;
+pub use wav_recorder::WavRecorder;
+pub use wave_channel::{WaveChannel, WAVE_RAM_SIZE};
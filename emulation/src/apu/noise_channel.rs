@@ -0,0 +1,149 @@
+use crate::apu::{Channel, Envelope, LengthCounter};
+
+const CLOCK_SHIFT: u8 = 4u8;
+const WIDTH_MODE_BIT: u8 = 0b00001000;
+const DIVISOR_MASK: u8 = 0b00000111;
+const LENGTH_ENABLE_BIT: u8 = 0b01000000;
+const TRIGGER_BIT: u8 = 0b10000000;
+
+const LENGTH: u16 = 64u16;
+
+const DIVISORS: [u32; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
+
+/// Channel 4 (NR41–NR44), playing pseudo-random noise from a 15-bit linear
+/// feedback shift register.
+///
+/// Each shift XORs the low two bits into bit 14, and into bit 6 as well in
+/// 7-bit mode for a shorter, more tonal pattern. The channel plays while bit
+/// 0 is clear. NR43 sets the shift rate as a divisor shifted left by the
+/// clock shift.
+pub struct NoiseChannel {
+    enabled: bool,
+    envelope: Envelope,
+    length: LengthCounter,
+    lfsr: u16,
+    polynomial: u8,
+    timer: u32,
+}
+
+impl NoiseChannel {
+    pub fn new() -> Self {
+        NoiseChannel {
+            enabled: false,
+            envelope: Envelope::new(),
+            length: LengthCounter::new(LENGTH),
+            lfsr: 0x7fffu16,
+            polynomial: 0x00u8,
+            timer: 0u32,
+        }
+    }
+
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    fn period(&self) -> u32 {
+        DIVISORS[(self.polynomial & DIVISOR_MASK) as usize] << (self.polynomial >> CLOCK_SHIFT)
+    }
+
+    /// Clears everything but, on the DMG, the length, as powering off the
+    /// APU does.
+    pub fn power_off(&mut self, keep_length: bool) {
+        let mut length = std::mem::take(&mut self.length);
+
+        length.power_off(keep_length);
+
+        *self = NoiseChannel {
+            length,
+            ..NoiseChannel::new()
+        };
+    }
+
+    fn shift(&mut self) {
+        let feedback = (self.lfsr ^ (self.lfsr >> 1)) & 0x0001u16;
+
+        self.lfsr = (self.lfsr >> 1) | (feedback << 14);
+
+        if self.polynomial & WIDTH_MODE_BIT > 0 {
+            self.lfsr = (self.lfsr & !0x0040u16) | (feedback << 6);
+        }
+    }
+
+    fn trigger(&mut self, extra_length_clock: bool) {
+        self.enabled = self.envelope.dac_enabled();
+        self.lfsr = 0x7fffu16;
+        self.timer = self.period();
+
+        self.envelope.trigger();
+        self.length.trigger(extra_length_clock);
+    }
+
+    /// Writes NR41 to NR44, by their offset from NR40, which doesn't exist.
+    pub fn write(&mut self, register: u16, value: u8, extra_length_clock: bool) {
+        match register {
+            1 => self.length.load(value),
+            2 => {
+                self.envelope.write(value);
+
+                if !self.envelope.dac_enabled() {
+                    self.enabled = false;
+                }
+            },
+            3 => self.polynomial = value,
+            4 => {
+                let expired = self.length.set_enabled(value & LENGTH_ENABLE_BIT > 0, extra_length_clock);
+
+                if value & TRIGGER_BIT > 0 {
+                    self.trigger(extra_length_clock);
+                } else if expired {
+                    self.enabled = false;
+                }
+            },
+            _ => {},
+        };
+    }
+}
+
+impl Default for NoiseChannel {
+    fn default() -> Self {
+        NoiseChannel::new()
+    }
+}
+
+impl Channel for NoiseChannel {
+    fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    fn dac_enabled(&self) -> bool {
+        self.envelope.dac_enabled()
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn output(&self) -> u8 {
+        if !self.enabled || self.lfsr & 0x0001u16 > 0 {
+            return 0x00u8;
+        }
+
+        self.envelope.volume()
+    }
+
+    fn step(&mut self, clocks: u32) {
+        let mut clocks = clocks;
+
+        while clocks >= self.timer {
+            clocks -= self.timer;
+
+            self.timer = self.period();
+
+            self.shift();
+        }
+
+        self.timer -= clocks;
+    }
+}
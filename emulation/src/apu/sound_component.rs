@@ -0,0 +1,413 @@
+use std::collections::VecDeque;
+
+use crate::{
+    apu::{AudioMasterControlFlag, Channel, NoiseChannel, SquareChannel, WaveChannel},
+    memory_component::{MemoryComponent, MemoryError},
+    model::Model,
+};
+
+const NR_10_ADDRESS: u16 = 0xff10u16;
+const NR_11_ADDRESS: u16 = 0xff11u16;
+const NR_12_ADDRESS: u16 = 0xff12u16;
+const NR_13_ADDRESS: u16 = 0xff13u16;
+const NR_14_ADDRESS: u16 = 0xff14u16;
+const NR_21_ADDRESS: u16 = 0xff16u16;
+const NR_22_ADDRESS: u16 = 0xff17u16;
+const NR_23_ADDRESS: u16 = 0xff18u16;
+const NR_24_ADDRESS: u16 = 0xff19u16;
+const NR_30_ADDRESS: u16 = 0xff1au16;
+const NR_31_ADDRESS: u16 = 0xff1bu16;
+const NR_32_ADDRESS: u16 = 0xff1cu16;
+const NR_33_ADDRESS: u16 = 0xff1du16;
+const NR_34_ADDRESS: u16 = 0xff1eu16;
+const NR_41_ADDRESS: u16 = 0xff20u16;
+const NR_42_ADDRESS: u16 = 0xff21u16;
+const NR_43_ADDRESS: u16 = 0xff22u16;
+const NR_44_ADDRESS: u16 = 0xff23u16;
+const NR_50_ADDRESS: u16 = 0xff24u16;
+const NR_51_ADDRESS: u16 = 0xff25u16;
+const NR_52_ADDRESS: u16 = 0xff26u16;
+const WAVE_PATTERN_RAM_START_ADDRESS: u16 = 0xff30u16;
+const WAVE_PATTERN_RAM_END_ADDRESS: u16 = 0xff3fu16;
+
+const NR_20_ADDRESS: u16 = 0xff15u16;
+const NR_40_ADDRESS: u16 = 0xff1fu16;
+const UNUSED_START_ADDRESS: u16 = 0xff27u16;
+const UNUSED_END_ADDRESS: u16 = 0xff2fu16;
+
+const REGISTER_COUNT: usize = (UNUSED_END_ADDRESS - NR_10_ADDRESS) as usize + 1;
+
+// Bits that always read back as 1, from NR10 to the unused registers before
+// wave RAM. Write-only bits like the frequencies and triggers read as 1 too.
+const READ_MASKS: [u8; REGISTER_COUNT] = [
+    0x80, 0x3f, 0x00, 0xff, 0xbf,
+    0xff, 0x3f, 0x00, 0xff, 0xbf,
+    0x7f, 0xff, 0x9f, 0xff, 0xbf,
+    0xff, 0xff, 0x00, 0x00, 0xbf,
+    0x00, 0x00, 0x70,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
+// While powered off, only the length half of these can be written
+const LENGTH_ADDRESSES: [u16; 4] = [NR_11_ADDRESS, NR_21_ADDRESS, NR_31_ADDRESS, NR_41_ADDRESS];
+const LENGTH_MASK: u8 = 0b00111111;
+
+const CLOCKS_PER_CYCLE: u32 = 4u32;
+const CYCLES_PER_SECOND: u32 = 1048576u32;
+
+// The frame sequencer runs at 512 Hz
+const CYCLES_PER_FRAME_SEQUENCER_STEP: u32 = 2048u32;
+
+const LEFT_VOLUME_SHIFT: u8 = 4u8;
+const VOLUME_MASK: u8 = 0b00000111;
+const LEFT_PANNING_SHIFT: u8 = 4u8;
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100u32;
+
+// How much of the output's DC offset survives each clock, as the capacitor on
+// the real output drains
+const HIGH_PASS_CHARGE_FACTOR: f64 = 0.999958f64;
+
+/// The audio processing unit: the sound registers NR10–NR52 (0xFF10–0xFF26)
+/// and wave RAM (0xFF30–0xFF3F).
+///
+/// A frame sequencer at 512 Hz clocks the channels' length counters at
+/// 256 Hz, channel 1's sweep at 128 Hz, and the volume envelopes at 64 Hz.
+/// The four channels are mixed into left and right outputs as selected by
+/// NR51, scaled by the master volumes in NR50, and averaged down to stereo
+/// samples at the host's sample rate. Samples are buffered, interleaved left
+/// then right, until taken.
+///
+/// Unused and write-only bits read back as 1. Powering off with NR52 clears
+/// every register up to NR51 and ignores writes to them until powered on
+/// again. Wave RAM is left alone. The DMG models also keep the lengths, and
+/// still let them be loaded while powered off, where the CGB clears them and
+/// ignores those writes too.
+///
+/// At CGB double speed the APU only runs every other machine cycle, so
+/// pitches and the frame sequencer stay the same.
+pub struct SoundComponent {
+    capacitors: [f32; 2],
+    channel1: SquareChannel,
+    channel2: SquareChannel,
+    channel3: WaveChannel,
+    channel4: NoiseChannel,
+    double_speed: bool,
+    frame_sequencer_cycles: u32,
+    frame_sequencer_step: u8,
+    high_pass_charge_factor: f32,
+    model: Model,
+    registers: [u8; REGISTER_COUNT],
+    sample_accumulator: [f32; 2],
+    sample_cycles: u32,
+    sample_phase: u32,
+    sample_rate: u32,
+    samples: VecDeque<f32>,
+    skip_cycle: bool,
+}
+
+impl SoundComponent {
+    pub fn new() -> Self {
+        SoundComponent::with_sample_rate(DEFAULT_SAMPLE_RATE)
+    }
+
+    pub fn with_model(model: Model) -> Self {
+        SoundComponent {
+            model,
+            ..SoundComponent::new()
+        }
+    }
+
+    pub fn with_sample_rate(sample_rate: u32) -> Self {
+        let mut sound = SoundComponent {
+            capacitors: [0.0f32; 2],
+            channel1: SquareChannel::with_sweep(),
+            channel2: SquareChannel::new(),
+            channel3: WaveChannel::new(),
+            channel4: NoiseChannel::new(),
+            double_speed: false,
+            frame_sequencer_cycles: 0u32,
+            frame_sequencer_step: 0x00u8,
+            high_pass_charge_factor: 0.0f32,
+            model: Model::Dmg,
+            registers: [0x00u8; REGISTER_COUNT],
+            sample_accumulator: [0.0f32; 2],
+            sample_cycles: 0u32,
+            sample_phase: 0u32,
+            sample_rate: 0u32,
+            samples: VecDeque::new(),
+            skip_cycle: false,
+        };
+
+        sound.set_sample_rate(sample_rate);
+
+        sound
+    }
+
+    fn channels(&self) -> [&dyn Channel; 4] {
+        [&self.channel1, &self.channel2, &self.channel3, &self.channel4]
+    }
+
+    fn channels_mut(&mut self) -> [&mut dyn Channel; 4] {
+        [&mut self.channel1, &mut self.channel2, &mut self.channel3, &mut self.channel4]
+    }
+
+    /// Whether each channel is playing, as in the low bits of NR52.
+    pub fn channels_enabled(&self) -> u8 {
+        let flags = [
+            AudioMasterControlFlag::Channel1Switch,
+            AudioMasterControlFlag::Channel2Switch,
+            AudioMasterControlFlag::Channel3Switch,
+            AudioMasterControlFlag::Channel4Switch,
+        ];
+
+        self.channels()
+            .iter()
+            .zip(flags)
+            .filter(|(channel, _)| channel.enabled())
+            .fold(0x00u8, |enabled, (_, flag)| enabled | flag as u8)
+    }
+
+    fn clock_frame_sequencer(&mut self) {
+        let step = self.frame_sequencer_step;
+
+        if step.is_multiple_of(2) {
+            for channel in self.channels_mut() {
+                channel.clock_length();
+            }
+        }
+
+        if step == 2 || step == 6 {
+            self.channel1.clock_sweep();
+        }
+
+        if step == 7 {
+            self.channel1.clock_envelope();
+            self.channel2.clock_envelope();
+            self.channel4.clock_envelope();
+        }
+
+        self.frame_sequencer_step = (step + 1) % 8;
+    }
+
+    /// The current left and right outputs, each from -1.0 to 1.0.
+    fn mix(&self) -> [f32; 2] {
+        let nr50 = self.register(NR_50_ADDRESS);
+        let nr51 = self.register(NR_51_ADDRESS);
+
+        let mut left = 0.0f32;
+        let mut right = 0.0f32;
+
+        for (i, channel) in self.channels().iter().enumerate() {
+            let output = channel.analog_output();
+
+            if nr51 & (0x01u8 << (i as u8 + LEFT_PANNING_SHIFT)) > 0 {
+                left += output;
+            }
+
+            if nr51 & (0x01u8 << i) > 0 {
+                right += output;
+            }
+        }
+
+        let left_volume = ((nr50 >> LEFT_VOLUME_SHIFT) & VOLUME_MASK) as f32 + 1.0f32;
+        let right_volume = (nr50 & VOLUME_MASK) as f32 + 1.0f32;
+
+        [left * left_volume / 32.0f32, right * right_volume / 32.0f32]
+    }
+
+    fn power_off(&mut self) {
+        let keep_length = !self.model.is_cgb();
+
+        self.channel1.power_off(keep_length);
+        self.channel2.power_off(keep_length);
+        self.channel3.power_off(keep_length);
+        self.channel4.power_off(keep_length);
+
+        for register in self.registers[..(NR_52_ADDRESS - NR_10_ADDRESS) as usize].iter_mut() {
+            *register = 0x00u8;
+        }
+    }
+
+    pub fn powered(&self) -> bool {
+        self.register(NR_52_ADDRESS) & AudioMasterControlFlag::MasterSwitch as u8 > 0
+    }
+
+    /// Averages the output since the last sample into a new one, through the
+    /// high-pass filter.
+    fn push_sample(&mut self) {
+        for (i, accumulated) in self.sample_accumulator.iter_mut().enumerate() {
+            let input = *accumulated / self.sample_cycles as f32;
+            let output = input - self.capacitors[i];
+
+            self.capacitors[i] = input - output * self.high_pass_charge_factor;
+
+            *accumulated = 0.0f32;
+
+            // Half a second of stereo samples at the current rate
+            if self.samples.len() >= self.sample_rate as usize {
+                self.samples.pop_front();
+            }
+
+            self.samples.push_back(output);
+        }
+
+        self.sample_cycles = 0;
+    }
+
+    fn register(&self, location: u16) -> u8 {
+        self.registers[(location - NR_10_ADDRESS) as usize]
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.clamp(1, CYCLES_PER_SECOND);
+
+        let clocks_per_sample = (CYCLES_PER_SECOND * CLOCKS_PER_CYCLE) as f64 / self.sample_rate as f64;
+
+        self.high_pass_charge_factor = HIGH_PASS_CHARGE_FACTOR.powf(clocks_per_sample) as f32;
+    }
+
+    /// Takes the samples generated since the last call, interleaved left then
+    /// right. Only the most recent are kept if nothing takes them.
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+
+    fn write_channel(&mut self, location: u16, value: u8) {
+        // Enabling a length between the frame sequencer's length clocks
+        // counts it down straight away
+        let extra_length_clock = !self.frame_sequencer_step.is_multiple_of(2);
+
+        match location {
+            NR_10_ADDRESS..=NR_14_ADDRESS => self.channel1.write(location - NR_10_ADDRESS, value, extra_length_clock),
+            NR_20_ADDRESS..=NR_24_ADDRESS => self.channel2.write(location - NR_20_ADDRESS, value, extra_length_clock),
+            NR_30_ADDRESS..=NR_34_ADDRESS => self.channel3.write(location - NR_30_ADDRESS, value, extra_length_clock),
+            NR_40_ADDRESS..=NR_44_ADDRESS => self.channel4.write(location - NR_40_ADDRESS, value, extra_length_clock),
+            _ => {},
+        };
+    }
+}
+
+impl Default for SoundComponent {
+    fn default() -> Self {
+        SoundComponent::new()
+    }
+}
+
+impl MemoryComponent for SoundComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        let registers = [
+            NR_10_ADDRESS, NR_11_ADDRESS, NR_12_ADDRESS, NR_13_ADDRESS, NR_14_ADDRESS,
+            NR_21_ADDRESS, NR_22_ADDRESS, NR_23_ADDRESS, NR_24_ADDRESS,
+            NR_30_ADDRESS, NR_31_ADDRESS, NR_32_ADDRESS, NR_33_ADDRESS, NR_34_ADDRESS,
+            NR_41_ADDRESS, NR_42_ADDRESS, NR_43_ADDRESS, NR_44_ADDRESS,
+            NR_50_ADDRESS, NR_51_ADDRESS, NR_52_ADDRESS,
+            NR_20_ADDRESS, NR_40_ADDRESS,
+        ];
+
+        registers
+            .into_iter()
+            .chain(UNUSED_START_ADDRESS..=UNUSED_END_ADDRESS)
+            .chain(WAVE_PATTERN_RAM_START_ADDRESS..=WAVE_PATTERN_RAM_END_ADDRESS)
+            .collect()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            NR_52_ADDRESS => Ok(READ_MASKS[(location - NR_10_ADDRESS) as usize] | self.register(location) | self.channels_enabled()),
+            NR_10_ADDRESS..=UNUSED_END_ADDRESS => Ok(READ_MASKS[(location - NR_10_ADDRESS) as usize] | self.register(location)),
+            WAVE_PATTERN_RAM_START_ADDRESS..=WAVE_PATTERN_RAM_END_ADDRESS => {
+                Ok(self.channel3.wave_ram((location - WAVE_PATTERN_RAM_START_ADDRESS) as usize))
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn set_double_speed(&mut self, double_speed: bool) {
+        self.double_speed = double_speed;
+        self.skip_cycle = false;
+    }
+
+    fn step(&mut self) -> u8 {
+        if self.double_speed {
+            self.skip_cycle = !self.skip_cycle;
+
+            if self.skip_cycle {
+                return 0x00u8;
+            }
+        }
+
+        if self.powered() {
+            self.frame_sequencer_cycles += 1;
+
+            if self.frame_sequencer_cycles >= CYCLES_PER_FRAME_SEQUENCER_STEP {
+                self.frame_sequencer_cycles = 0;
+
+                self.clock_frame_sequencer();
+            }
+
+            for channel in self.channels_mut() {
+                channel.step(CLOCKS_PER_CYCLE);
+            }
+        }
+
+        let [left, right] = if self.powered() { self.mix() } else { [0.0f32; 2] };
+
+        self.sample_accumulator[0] += left;
+        self.sample_accumulator[1] += right;
+        self.sample_cycles += 1;
+
+        self.sample_phase += self.sample_rate;
+
+        if self.sample_phase >= CYCLES_PER_SECOND {
+            self.sample_phase -= CYCLES_PER_SECOND;
+
+            self.push_sample();
+        }
+
+        0x00u8
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            NR_52_ADDRESS => {
+                let master_switch = AudioMasterControlFlag::MasterSwitch as u8;
+
+                match (self.powered(), value & master_switch > 0) {
+                    (true, false) => self.power_off(),
+                    // The frame sequencer starts over when powered on
+                    (false, true) => {
+                        self.frame_sequencer_cycles = 0;
+                        self.frame_sequencer_step = 0x00u8;
+                    },
+                    _ => {},
+                };
+
+                self.registers[(location - NR_10_ADDRESS) as usize] = value & master_switch;
+            },
+            NR_20_ADDRESS | NR_40_ADDRESS | UNUSED_START_ADDRESS..=UNUSED_END_ADDRESS => {},
+            NR_10_ADDRESS..=NR_51_ADDRESS if self.powered() => {
+                self.registers[(location - NR_10_ADDRESS) as usize] = value;
+
+                self.write_channel(location, value);
+            },
+            NR_10_ADDRESS..=NR_51_ADDRESS => {
+                if !self.model.is_cgb() && LENGTH_ADDRESSES.contains(&location) {
+                    let length = if location == NR_31_ADDRESS { value } else { value & LENGTH_MASK };
+
+                    self.write_channel(location, length);
+                }
+            },
+            WAVE_PATTERN_RAM_START_ADDRESS..=WAVE_PATTERN_RAM_END_ADDRESS => {
+                self.channel3.write_wave_ram((location - WAVE_PATTERN_RAM_START_ADDRESS) as usize, value);
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::apu::{Channel, Envelope, LengthCounter, Sweep};
+
+const DUTY_SHIFT: u8 = 6u8;
+const FREQUENCY_HIGH_MASK: u8 = 0b00000111;
+const LENGTH_ENABLE_BIT: u8 = 0b01000000;
+const TRIGGER_BIT: u8 = 0b10000000;
+
+const LENGTH: u16 = 64u16;
+
+// 12.5%, 25%, 50% and 75%, played from the top bit down
+const DUTY_PATTERNS: [u8; 4] = [0b00000001, 0b10000001, 0b10000111, 0b01111110];
+
+/// A square wave channel: channel 1 (NR10–NR14) with a frequency sweep, or
+/// channel 2 (NR21–NR24) without.
+///
+/// The frequency timer steps through one of four duty patterns, each step
+/// lasting `(2048 - frequency) * 4` clocks.
+pub struct SquareChannel {
+    duty: u8,
+    duty_position: u8,
+    enabled: bool,
+    envelope: Envelope,
+    frequency: u16,
+    length: LengthCounter,
+    sweep: Option<Sweep>,
+    timer: u32,
+}
+
+impl SquareChannel {
+    pub fn new() -> Self {
+        SquareChannel {
+            duty: 0x00u8,
+            duty_position: 0x00u8,
+            enabled: false,
+            envelope: Envelope::new(),
+            frequency: 0x0000u16,
+            length: LengthCounter::new(LENGTH),
+            sweep: None,
+            timer: 0u32,
+        }
+    }
+
+    pub fn with_sweep() -> Self {
+        SquareChannel {
+            sweep: Some(Sweep::new()),
+            ..SquareChannel::new()
+        }
+    }
+
+    pub fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub fn clock_sweep(&mut self) {
+        let Some(sweep) = self.sweep.as_mut() else {
+            return;
+        };
+
+        if let Some(frequency) = sweep.clock() {
+            self.frequency = frequency;
+        }
+
+        if sweep.overflowed() {
+            self.enabled = false;
+        }
+    }
+
+    fn period(&self) -> u32 {
+        (2048 - self.frequency as u32) * 4
+    }
+
+    /// Clears everything but, on the DMG, the length, as powering off the
+    /// APU does.
+    pub fn power_off(&mut self, keep_length: bool) {
+        let mut length = std::mem::take(&mut self.length);
+
+        length.power_off(keep_length);
+
+        *self = SquareChannel {
+            length,
+            sweep: self.sweep.as_ref().map(|_| Sweep::new()),
+            ..SquareChannel::new()
+        };
+    }
+
+    fn trigger(&mut self, extra_length_clock: bool) {
+        self.enabled = self.envelope.dac_enabled();
+        self.timer = self.period();
+
+        self.envelope.trigger();
+        self.length.trigger(extra_length_clock);
+
+        if let Some(sweep) = self.sweep.as_mut() {
+            sweep.trigger(self.frequency);
+
+            if sweep.overflowed() {
+                self.enabled = false;
+            }
+        }
+    }
+
+    /// Writes NRx0 to NRx4, by their offset from NRx0.
+    pub fn write(&mut self, register: u16, value: u8, extra_length_clock: bool) {
+        match register {
+            0 => {
+                if let Some(sweep) = self.sweep.as_mut() {
+                    sweep.write(value);
+
+                    if sweep.overflowed() {
+                        self.enabled = false;
+                    }
+                }
+            },
+            1 => {
+                self.duty = value >> DUTY_SHIFT;
+
+                self.length.load(value);
+            },
+            2 => {
+                self.envelope.write(value);
+
+                if !self.envelope.dac_enabled() {
+                    self.enabled = false;
+                }
+            },
+            3 => self.frequency = (self.frequency & 0x0700u16) | value as u16,
+            4 => {
+                self.frequency = (self.frequency & 0x00ffu16) | ((value & FREQUENCY_HIGH_MASK) as u16) << 8;
+
+                let expired = self.length.set_enabled(value & LENGTH_ENABLE_BIT > 0, extra_length_clock);
+
+                if value & TRIGGER_BIT > 0 {
+                    self.trigger(extra_length_clock);
+                } else if expired {
+                    self.enabled = false;
+                }
+            },
+            _ => {},
+        };
+    }
+}
+
+impl Default for SquareChannel {
+    fn default() -> Self {
+        SquareChannel::new()
+    }
+}
+
+impl Channel for SquareChannel {
+    fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    fn dac_enabled(&self) -> bool {
+        self.envelope.dac_enabled()
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn output(&self) -> u8 {
+        if !self.enabled {
+            return 0x00u8;
+        }
+
+        let high = DUTY_PATTERNS[self.duty as usize] & (0x80u8 >> self.duty_position) > 0;
+
+        if high {
+            self.envelope.volume()
+        } else {
+            0x00u8
+        }
+    }
+
+    fn step(&mut self, clocks: u32) {
+        let mut clocks = clocks;
+
+        while clocks >= self.timer {
+            clocks -= self.timer;
+
+            self.timer = self.period();
+            self.duty_position = (self.duty_position + 1) % 8;
+        }
+
+        self.timer -= clocks;
+    }
+}
@@ -0,0 +1,133 @@
+const MAX_FREQUENCY: u16 = 0x07ffu16;
+
+const PERIOD_SHIFT: u8 = 4u8;
+const PERIOD_MASK: u8 = 0b00000111;
+const NEGATE_BIT: u8 = 0b00001000;
+const SHIFT_MASK: u8 = 0b00000111;
+
+/// The frequency sweep of channel 1, set by NR10 and stepped at 128 Hz by
+/// the frame sequencer.
+///
+/// Every period the shadow frequency moves by itself shifted right, up or
+/// down. Going past 2047 silences the channel, as does leaving negate mode
+/// after a calculation has used it.
+#[derive(Default)]
+pub struct Sweep {
+    enabled: bool,
+    negated: bool,
+    overflowed: bool,
+    register: u8,
+    shadow_frequency: u16,
+    timer: u8,
+}
+
+impl Sweep {
+    pub fn new() -> Self {
+        Sweep {
+            enabled: false,
+            negated: false,
+            overflowed: false,
+            register: 0x00u8,
+            shadow_frequency: 0x0000u16,
+            timer: 0x00u8,
+        }
+    }
+
+    /// The next frequency, flagging an overflow past 2047.
+    fn calculate(&mut self) -> u16 {
+        let delta = self.shadow_frequency >> self.shift();
+
+        let frequency = if self.negate() {
+            self.negated = true;
+
+            self.shadow_frequency.wrapping_sub(delta)
+        } else {
+            self.shadow_frequency + delta
+        };
+
+        if frequency > MAX_FREQUENCY {
+            self.overflowed = true;
+        }
+
+        frequency
+    }
+
+    /// Steps the sweep, returning the channel's new frequency if it changed.
+    pub fn clock(&mut self) -> Option<u16> {
+        self.timer = self.timer.saturating_sub(1);
+
+        if self.timer > 0 {
+            return None;
+        }
+
+        self.reload_timer();
+
+        if !self.enabled || self.period() == 0 {
+            return None;
+        }
+
+        let frequency = self.calculate();
+
+        if self.overflowed || self.shift() == 0 {
+            return None;
+        }
+
+        self.shadow_frequency = frequency;
+
+        // The new frequency is checked again straight away
+        self.calculate();
+
+        Some(frequency)
+    }
+
+    fn negate(&self) -> bool {
+        self.register & NEGATE_BIT > 0
+    }
+
+    /// Whether the channel has been silenced, usually by a calculation going
+    /// past 2047.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    fn period(&self) -> u8 {
+        (self.register >> PERIOD_SHIFT) & PERIOD_MASK
+    }
+
+    pub fn register(&self) -> u8 {
+        self.register
+    }
+
+    // A period of 0 counts as 8
+    fn reload_timer(&mut self) {
+        self.timer = match self.period() {
+            0 => 8u8,
+            period => period,
+        };
+    }
+
+    fn shift(&self) -> u8 {
+        self.register & SHIFT_MASK
+    }
+
+    pub fn trigger(&mut self, frequency: u16) {
+        self.shadow_frequency = frequency;
+        self.negated = false;
+        self.overflowed = false;
+        self.enabled = self.period() > 0 || self.shift() > 0;
+
+        self.reload_timer();
+
+        if self.shift() > 0 {
+            self.calculate();
+        }
+    }
+
+    pub fn write(&mut self, value: u8) {
+        self.register = value;
+
+        if self.negated && !self.negate() {
+            self.overflowed = true;
+        }
+    }
+}
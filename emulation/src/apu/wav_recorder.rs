@@ -0,0 +1,63 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, Write},
+    path::Path,
+};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+const CHANNELS: u16 = 2u16;
+const BITS_PER_SAMPLE: u16 = 16u16;
+
+/// Writes the APU's interleaved stereo samples to a 16-bit PCM .wav file.
+///
+/// Emulation is deterministic, so recording a fixed number of frames gives
+/// the same file every time, which makes it usable as a golden file.
+pub struct WavRecorder<W: Write + Seek = BufWriter<File>> {
+    writer: WavWriter<W>,
+}
+
+impl WavRecorder {
+    pub fn create<P: AsRef<Path>>(path: P, sample_rate: u32) -> io::Result<Self> {
+        WavRecorder::new(BufWriter::new(File::create(path)?), sample_rate)
+    }
+}
+
+impl<W: Write + Seek> WavRecorder<W> {
+    pub fn new(writer: W, sample_rate: u32) -> io::Result<Self> {
+        let spec = WavSpec {
+            channels: CHANNELS,
+            sample_rate,
+            bits_per_sample: BITS_PER_SAMPLE,
+            sample_format: SampleFormat::Int,
+        };
+
+        let writer = WavWriter::new(writer, spec).map_err(to_io_error)?;
+
+        Ok(WavRecorder { writer })
+    }
+
+    /// Fills in the header's lengths. Dropping the recorder does this too, but
+    /// swallows any error.
+    pub fn finish(self) -> io::Result<()> {
+        self.writer.finalize().map_err(to_io_error)
+    }
+
+    /// Appends samples as returned by `Emulator::take_audio_samples`.
+    pub fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            let sample = (sample.clamp(-1.0f32, 1.0f32) * i16::MAX as f32).round() as i16;
+
+            self.writer.write_sample(sample).map_err(to_io_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn to_io_error(error: hound::Error) -> io::Error {
+    match error {
+        hound::Error::IoError(e) => e,
+        e => io::Error::other(e),
+    }
+}
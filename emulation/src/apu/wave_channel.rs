@@ -0,0 +1,159 @@
+use crate::apu::{Channel, LengthCounter};
+
+const DAC_ENABLE_BIT: u8 = 0b10000000;
+const OUTPUT_LEVEL_SHIFT: u8 = 5u8;
+const OUTPUT_LEVEL_MASK: u8 = 0b00000011;
+const FREQUENCY_HIGH_MASK: u8 = 0b00000111;
+const LENGTH_ENABLE_BIT: u8 = 0b01000000;
+const TRIGGER_BIT: u8 = 0b10000000;
+
+const LENGTH: u16 = 256u16;
+
+pub const WAVE_RAM_SIZE: usize = 16usize;
+const SAMPLE_COUNT: u8 = 32u8;
+
+/// Channel 3 (NR30–NR34), playing the 32 4-bit samples in wave RAM
+/// (0xFF30–0xFF3F), high nibble first.
+///
+/// Each sample lasts `(2048 - frequency) * 2` clocks, and is shifted right
+/// by the output level in NR32 to mute it or play it at 100%, 50% or 25%.
+pub struct WaveChannel {
+    dac_enabled: bool,
+    enabled: bool,
+    frequency: u16,
+    length: LengthCounter,
+    output_level: u8,
+    position: u8,
+    timer: u32,
+    wave_ram: [u8; WAVE_RAM_SIZE],
+}
+
+impl WaveChannel {
+    pub fn new() -> Self {
+        WaveChannel {
+            dac_enabled: false,
+            enabled: false,
+            frequency: 0x0000u16,
+            length: LengthCounter::new(LENGTH),
+            output_level: 0x00u8,
+            position: 0x00u8,
+            timer: 0u32,
+            wave_ram: [0x00u8; WAVE_RAM_SIZE],
+        }
+    }
+
+    fn period(&self) -> u32 {
+        (2048 - self.frequency as u32) * 2
+    }
+
+    /// Clears everything but wave RAM and, on the DMG, the length, as
+    /// powering off the APU does.
+    pub fn power_off(&mut self, keep_length: bool) {
+        let mut length = std::mem::take(&mut self.length);
+
+        length.power_off(keep_length);
+
+        *self = WaveChannel {
+            length,
+            wave_ram: self.wave_ram,
+            ..WaveChannel::new()
+        };
+    }
+
+    fn sample(&self) -> u8 {
+        let byte = self.wave_ram[self.position as usize / 2];
+
+        if self.position.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0x0fu8
+        }
+    }
+
+    fn trigger(&mut self, extra_length_clock: bool) {
+        self.enabled = self.dac_enabled;
+        self.position = 0x00u8;
+        self.timer = self.period();
+
+        self.length.trigger(extra_length_clock);
+    }
+
+    pub fn wave_ram(&self, index: usize) -> u8 {
+        self.wave_ram[index]
+    }
+
+    /// Writes NR30 to NR34, by their offset from NR30.
+    pub fn write(&mut self, register: u16, value: u8, extra_length_clock: bool) {
+        match register {
+            0 => {
+                self.dac_enabled = value & DAC_ENABLE_BIT > 0;
+
+                if !self.dac_enabled {
+                    self.enabled = false;
+                }
+            },
+            1 => self.length.load(value),
+            2 => self.output_level = (value >> OUTPUT_LEVEL_SHIFT) & OUTPUT_LEVEL_MASK,
+            3 => self.frequency = (self.frequency & 0x0700u16) | value as u16,
+            4 => {
+                self.frequency = (self.frequency & 0x00ffu16) | ((value & FREQUENCY_HIGH_MASK) as u16) << 8;
+
+                let expired = self.length.set_enabled(value & LENGTH_ENABLE_BIT > 0, extra_length_clock);
+
+                if value & TRIGGER_BIT > 0 {
+                    self.trigger(extra_length_clock);
+                } else if expired {
+                    self.enabled = false;
+                }
+            },
+            _ => {},
+        };
+    }
+
+    pub fn write_wave_ram(&mut self, index: usize, value: u8) {
+        self.wave_ram[index] = value;
+    }
+}
+
+impl Default for WaveChannel {
+    fn default() -> Self {
+        WaveChannel::new()
+    }
+}
+
+impl Channel for WaveChannel {
+    fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    fn dac_enabled(&self) -> bool {
+        self.dac_enabled
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn output(&self) -> u8 {
+        if !self.enabled || self.output_level == 0 {
+            return 0x00u8;
+        }
+
+        self.sample() >> (self.output_level - 1)
+    }
+
+    fn step(&mut self, clocks: u32) {
+        let mut clocks = clocks;
+
+        while clocks >= self.timer {
+            clocks -= self.timer;
+
+            self.timer = self.period();
+            self.position = (self.position + 1) % SAMPLE_COUNT;
+        }
+
+        self.timer -= clocks;
+    }
+}
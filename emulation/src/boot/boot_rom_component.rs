@@ -0,0 +1,97 @@
+use std::{fs, io, path::Path};
+
+use crate::memory_component::{MemoryComponent, MemoryError};
+
+pub const BOOT_ROM_DISABLE_ADDRESS: u16 = 0xff50u16;
+
+const DMG_BOOT_ROM_SIZE: usize = 0x0100usize;
+const CGB_BOOT_ROM_SIZE: usize = 0x0900usize;
+
+// The CGB boot ROM leaves the cartridge header visible
+const HEADER_START_ADDRESS: u16 = 0x0100u16;
+const HEADER_END_ADDRESS: u16 = 0x01ffu16;
+
+/// A boot ROM image, overlaid on the start of the cartridge until the boot
+/// ROM writes to 0xFF50.
+///
+/// DMG, MGB and SGB images are 256 bytes covering 0x0000-0x00FF. CGB images
+/// are 2304 bytes and also cover 0x0200-0x08FF, leaving the cartridge header
+/// in between. Once unmapped the boot ROM stays unmapped, and 0xFF50 reads
+/// 0xFF.
+pub struct BootRomComponent {
+    data: Vec<u8>,
+    enabled: bool,
+}
+
+impl BootRomComponent {
+    pub fn from_bytes(data: &[u8]) -> io::Result<Self> {
+        if data.len() != DMG_BOOT_ROM_SIZE && data.len() != CGB_BOOT_ROM_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("boot ROM must be {} or {} bytes, not {}", DMG_BOOT_ROM_SIZE, CGB_BOOT_ROM_SIZE, data.len()),
+            ));
+        }
+
+        Ok(BootRomComponent {
+            data: data.to_vec(),
+            enabled: true,
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        BootRomComponent::from_bytes(&fs::read(path)?)
+    }
+
+    /// Whether the boot ROM is still mapped over the cartridge.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn overlays(&self, location: u16) -> bool {
+        self.enabled
+            && (location as usize) < self.data.len()
+            && !(HEADER_START_ADDRESS..=HEADER_END_ADDRESS).contains(&location)
+    }
+}
+
+impl MemoryComponent for BootRomComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        (0x0000u16..self.data.len() as u16)
+            .filter(|location| self.overlays(*location))
+            .chain([BOOT_ROM_DISABLE_ADDRESS])
+            .collect()
+    }
+
+    // Unmapping uncovers the cartridge underneath
+    fn mapping_changed(&self, location: u16) -> bool {
+        location == BOOT_ROM_DISABLE_ADDRESS && !self.enabled
+    }
+
+    fn overlay(&self) -> bool {
+        true
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            BOOT_ROM_DISABLE_ADDRESS => Ok(0xffu8),
+            _ if self.overlays(location) => Ok(self.data[location as usize]),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            BOOT_ROM_DISABLE_ADDRESS => {
+                if value != 0x00u8 {
+                    self.enabled = false;
+                }
+
+                Ok(())
+            },
+            // Read-only. On hardware these writes still reach the cartridge,
+            // but no boot ROM makes any
+            _ if self.overlays(location) => Ok(()),
+            _ => Err(MemoryError::WriteError(location, value, "invalid state")),
+        }
+    }
+}
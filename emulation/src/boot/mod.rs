@@ -0,0 +1,5 @@
+mod boot_rom_component;
+mod post_boot_state;
+
+pub use boot_rom_component::{BootRomComponent, BOOT_ROM_DISABLE_ADDRESS};
+pub use post_boot_state::PostBootState;
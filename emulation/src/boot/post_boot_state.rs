@@ -0,0 +1,118 @@
+use crate::model::Model;
+
+const P1_ADDRESS: u16 = 0xff00u16;
+const SC_ADDRESS: u16 = 0xff02u16;
+const TIMA_ADDRESS: u16 = 0xff05u16;
+const TMA_ADDRESS: u16 = 0xff06u16;
+const TAC_ADDRESS: u16 = 0xff07u16;
+const IF_ADDRESS: u16 = 0xff0fu16;
+const NR10_ADDRESS: u16 = 0xff10u16;
+const NR14_ADDRESS: u16 = 0xff14u16;
+const NR52_ADDRESS: u16 = 0xff26u16;
+const LCDC_ADDRESS: u16 = 0xff40u16;
+const STAT_ADDRESS: u16 = 0xff41u16;
+const SCY_ADDRESS: u16 = 0xff42u16;
+const SCX_ADDRESS: u16 = 0xff43u16;
+const LYC_ADDRESS: u16 = 0xff45u16;
+const BGP_ADDRESS: u16 = 0xff47u16;
+const WY_ADDRESS: u16 = 0xff4au16;
+const WX_ADDRESS: u16 = 0xff4bu16;
+const IE_ADDRESS: u16 = 0xffffu16;
+
+// NR10 to NR51, leaving out the trigger bits, which read back as 1 anyway
+const SOUND_REGISTERS: [u8; 0x16] = [
+    0x80, 0xbf, 0xf3, 0xff, 0x3f, 0xff, 0x3f, 0x00, 0xff, 0x3f, 0x7f, 0xff, 0x9f, 0xff, 0x3f, 0xff, 0xff, 0x00,
+    0x00, 0x3f, 0x77, 0xf3,
+];
+
+const NR14_TRIGGER: u8 = 0xbfu8;
+
+const SOUND_ON: u8 = 0x80u8;
+
+/// The state the boot ROM leaves behind when it hands over to the cartridge
+/// at 0x0100, per model, as documented in Pan Docs.
+///
+/// Registers Pan Docs leaves unknown, like DIV outside the DMG and MGB, are
+/// left at their power-on values.
+pub struct PostBootState {
+    pub a: u8,
+    pub f: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+    pub e: u8,
+    pub h: u8,
+    pub l: u8,
+    pub divider: Option<u16>,
+    /// I/O register writes, in the order they should be made.
+    pub io: Vec<(u16, u8)>,
+}
+
+impl PostBootState {
+    /// The DMG and MGB boot ROMs set the half carry and carry flags unless
+    /// the header checksum is 0.
+    pub fn new(model: Model, header_checksum: u8) -> Self {
+        let checksum_flags = if header_checksum != 0x00u8 { 0x30u8 } else { 0x00u8 };
+
+        let (a, f, b, c, d, e, h, l) = match model {
+            Model::Dmg => (0x01u8, 0x80u8 | checksum_flags, 0x00u8, 0x13u8, 0x00u8, 0xd8u8, 0x01u8, 0x4du8),
+            Model::Mgb => (0xffu8, 0x80u8 | checksum_flags, 0x00u8, 0x13u8, 0x00u8, 0xd8u8, 0x01u8, 0x4du8),
+            Model::Sgb => (0x01u8, 0x00u8, 0x00u8, 0x14u8, 0x00u8, 0x00u8, 0xc0u8, 0x60u8),
+            Model::Cgb => (0x11u8, 0x80u8, 0x00u8, 0x00u8, 0xffu8, 0x56u8, 0x00u8, 0x0du8),
+            Model::Agb => (0x11u8, 0x00u8, 0x01u8, 0x00u8, 0xffu8, 0x56u8, 0x00u8, 0x0du8),
+        };
+
+        let divider = match model {
+            Model::Dmg | Model::Mgb => Some(0xabccu16),
+            _ => None,
+        };
+
+        let sc = match model {
+            Model::Cgb | Model::Agb => 0x7fu8,
+            _ => 0x7eu8,
+        };
+
+        // Sound has to be on before the other sound registers take writes
+        let mut io = vec![
+            (P1_ADDRESS, 0x00u8),
+            (SC_ADDRESS, sc),
+            (TIMA_ADDRESS, 0x00u8),
+            (TMA_ADDRESS, 0x00u8),
+            (TAC_ADDRESS, 0xf8u8),
+            (NR52_ADDRESS, SOUND_ON),
+        ];
+
+        io.extend(SOUND_REGISTERS.iter().enumerate().map(|(i, value)| (NR10_ADDRESS + i as u16, *value)));
+
+        // Every boot ROM but the SGB's leaves channel 1 playing its chime
+        if model != Model::Sgb {
+            io.push((NR14_ADDRESS, NR14_TRIGGER));
+        }
+
+        io.extend([
+            (LCDC_ADDRESS, 0x91u8),
+            (STAT_ADDRESS, 0x85u8),
+            (SCY_ADDRESS, 0x00u8),
+            (SCX_ADDRESS, 0x00u8),
+            (LYC_ADDRESS, 0x00u8),
+            (BGP_ADDRESS, 0xfcu8),
+            (WY_ADDRESS, 0x00u8),
+            (WX_ADDRESS, 0x00u8),
+            (IF_ADDRESS, 0xe1u8),
+            (IE_ADDRESS, 0x00u8),
+        ]);
+
+        PostBootState {
+            a,
+            f,
+            b,
+            c,
+            d,
+            e,
+            h,
+            l,
+            divider,
+            io,
+        }
+    }
+}
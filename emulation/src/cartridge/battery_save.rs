@@ -0,0 +1,78 @@
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Keeps battery-backed cartridge state in a .sav file, rewriting it when the
+/// state changes and the flush interval has passed. What counts as a change is
+/// up to the caller, since a clock trailer's timestamp moves on regardless.
+pub struct BatterySave {
+    flush_interval: Duration,
+    last_flush: Instant,
+    last_saved: Vec<u8>,
+    path: PathBuf,
+}
+
+impl BatterySave {
+    pub fn new(path: PathBuf, flush_interval: Duration) -> Self {
+        BatterySave {
+            flush_interval,
+            last_flush: Instant::now(),
+            last_saved: Vec::new(),
+            path,
+        }
+    }
+
+    /// Reads the .sav file, if there is one yet.
+    pub fn load(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(&self.path) {
+            Ok(data) => {
+                self.last_saved = data.clone();
+
+                Ok(Some(data))
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        self.flush_interval
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_flush_interval(&mut self, flush_interval: Duration) {
+        self.flush_interval = flush_interval;
+    }
+
+    /// Writes the data unless `unchanged` finds the last write still matches
+    /// it. Returns whether the file was written.
+    pub fn flush<F: FnOnce(&[u8]) -> bool>(&mut self, data: &[u8], unchanged: F) -> io::Result<bool> {
+        self.last_flush = Instant::now();
+
+        if unchanged(&self.last_saved) {
+            return Ok(false);
+        }
+
+        // Write then rename so a crash never leaves a truncated save behind
+        let temporary_path = self.path.with_extension("sav.tmp");
+
+        fs::write(&temporary_path, data)?;
+        fs::rename(&temporary_path, &self.path)?;
+
+        self.last_saved = data.to_vec();
+
+        Ok(true)
+    }
+
+    pub fn flush_due(&self) -> bool {
+        self.last_flush.elapsed() >= self.flush_interval
+    }
+}
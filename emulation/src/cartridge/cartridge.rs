@@ -0,0 +1,224 @@
+use std::{
+    fmt::Display,
+    fs,
+    io,
+    path::Path,
+    sync::mpsc::Receiver,
+    time::Duration,
+};
+
+use num::ToPrimitive;
+
+use crate::{
+    cartridge::{
+        BatterySave,
+        CartridgeHeader,
+        CartridgeType,
+        ClockSource,
+        Mbc1Component,
+        Mbc2Component,
+        Mbc3Component,
+        Mbc5Component,
+        MemoryBankController,
+        RomOnlyComponent,
+        RumbleEvent,
+        SystemClockSource,
+        DEFAULT_FLUSH_INTERVAL,
+    },
+    memory_component::{MemoryComponent, MemoryError},
+};
+
+#[derive(Clone, Debug)]
+pub enum CartridgeError {
+    HeaderTooShort(usize),
+    InvalidRamSize(u8),
+    InvalidRomSize(u8),
+    Io(String),
+    NoBattery,
+    UnsupportedCartridgeType(u8),
+}
+
+impl From<io::Error> for CartridgeError {
+    fn from(value: io::Error) -> Self {
+        CartridgeError::Io(value.to_string())
+    }
+}
+
+impl Display for CartridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CartridgeError::HeaderTooShort(l) => write!(f, "image too short to contain a header: {} bytes", l),
+            CartridgeError::InvalidRamSize(s) => write!(f, "invalid RAM size: {:#04x}", s),
+            CartridgeError::InvalidRomSize(s) => write!(f, "invalid ROM size: {:#04x}", s),
+            CartridgeError::Io(e) => write!(f, "i/o error: {}", e),
+            CartridgeError::NoBattery => write!(f, "cartridge has no battery-backed RAM"),
+            CartridgeError::UnsupportedCartridgeType(t) => write!(f, "unsupported cartridge type: {:#04x}", t),
+        }
+    }
+}
+
+/// A cartridge parsed from a .gb/.gbc image.
+///
+/// Maps the program area (0x0000-0x7FFF) and the external RAM area
+/// (0xA000-0xBFFF) through the memory bank controller named by the cartridge
+/// type byte at 0x0147.
+///
+/// Cartridges loaded from a file with "+BATTERY" in their type keep their
+/// battery RAM in a .sav file next to the ROM. It is flushed by `autosave` once
+/// the flush interval has passed, and by `flush_battery_save`, which frontends
+/// call on exit.
+/// Nothing is written when the cartridge is dropped.
+pub struct Cartridge {
+    battery_save: Option<BatterySave>,
+    controller: Box<dyn MemoryBankController>,
+    header: CartridgeHeader,
+}
+
+impl Cartridge {
+    /// Loads a ROM image from disk, restoring its .sav file if there is one.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, CartridgeError> {
+        let data = fs::read(path.as_ref())?;
+
+        let mut cartridge = Cartridge::from_bytes(&data)?;
+
+        if cartridge.has_battery() {
+            let mut battery_save = BatterySave::new(path.as_ref().with_extension("sav"), DEFAULT_FLUSH_INTERVAL);
+
+            if let Some(save_data) = battery_save.load()? {
+                cartridge.import_battery_ram(&save_data)?;
+            }
+
+            cartridge.battery_save = Some(battery_save);
+        }
+
+        Ok(cartridge)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CartridgeError> {
+        Cartridge::with_clock_source(data, Box::new(SystemClockSource::new()))
+    }
+
+    /// Parses a cartridge whose real-time clock, if it has one, reads time
+    /// from the given source instead of the system clock.
+    pub fn with_clock_source(data: &[u8], clock_source: Box<dyn ClockSource>) -> Result<Self, CartridgeError> {
+        let header = CartridgeHeader::parse(data)?;
+
+        let rom = data.to_vec();
+        let ram_size = header.ram_size().bytes();
+
+        let controller: Box<dyn MemoryBankController> = match header.cartridge_type() {
+            CartridgeType::RomOnly | CartridgeType::RomRam | CartridgeType::RomRamBattery => {
+                Box::new(RomOnlyComponent::new(rom, ram_size))
+            },
+            CartridgeType::Mbc1 | CartridgeType::Mbc1Ram | CartridgeType::Mbc1RamBattery => {
+                Box::new(Mbc1Component::new(rom, ram_size))
+            },
+            CartridgeType::Mbc2 | CartridgeType::Mbc2Battery => Box::new(Mbc2Component::new(rom)),
+            CartridgeType::Mbc3TimerBattery | CartridgeType::Mbc3TimerRamBattery => {
+                Box::new(Mbc3Component::new(rom, ram_size, Some(clock_source)))
+            },
+            CartridgeType::Mbc3 | CartridgeType::Mbc3Ram | CartridgeType::Mbc3RamBattery => {
+                Box::new(Mbc3Component::new(rom, ram_size, None))
+            },
+            CartridgeType::Mbc5 | CartridgeType::Mbc5Ram | CartridgeType::Mbc5RamBattery => {
+                Box::new(Mbc5Component::new(rom, ram_size, false))
+            },
+            CartridgeType::Mbc5Rumble | CartridgeType::Mbc5RumbleRam | CartridgeType::Mbc5RumbleRamBattery => {
+                Box::new(Mbc5Component::new(rom, ram_size, true))
+            },
+            cartridge_type => {
+                return Err(CartridgeError::UnsupportedCartridgeType(cartridge_type.to_u8().unwrap()));
+            },
+        };
+
+        Ok(Cartridge {
+            battery_save: None,
+            controller,
+            header,
+        })
+    }
+
+    /// Writes the .sav file if the flush interval has passed and the battery
+    /// RAM or the clock registers changed since the last write. Meant to be
+    /// called once per frame.
+    pub fn autosave(&mut self) -> Result<bool, CartridgeError> {
+        match &self.battery_save {
+            Some(battery_save) if battery_save.flush_due() => self.flush_battery_save(),
+            _ => Ok(false),
+        }
+    }
+
+    /// The battery RAM in .sav layout, including the RTC trailer on MBC3
+    /// timer cartridges. Cartridges without a battery have nothing to export.
+    pub fn export_battery_ram(&self) -> Option<Vec<u8>> {
+        if self.has_battery() {
+            Some(self.controller.save_data())
+        } else {
+            None
+        }
+    }
+
+    /// Writes the .sav file now if the battery RAM or the clock registers
+    /// changed. A running clock alone doesn't count as a change.
+    pub fn flush_battery_save(&mut self) -> Result<bool, CartridgeError> {
+        let save_data = match self.export_battery_ram() {
+            Some(save_data) => save_data,
+            None => return Ok(false),
+        };
+
+        let controller = &self.controller;
+
+        match &mut self.battery_save {
+            Some(battery_save) => Ok(battery_save.flush(&save_data, |saved| controller.save_data_matches(saved))?),
+            None => Ok(false),
+        }
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.header.cartridge_type().has_battery()
+    }
+
+    pub fn header(&self) -> &CartridgeHeader {
+        &self.header
+    }
+
+    pub fn import_battery_ram(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        if !self.has_battery() {
+            return Err(CartridgeError::NoBattery);
+        }
+
+        self.controller.load_save_data(data);
+
+        Ok(())
+    }
+
+    pub fn save_path(&self) -> Option<&Path> {
+        self.battery_save.as_ref().map(|b| b.path())
+    }
+
+    pub fn set_flush_interval(&mut self, flush_interval: Duration) {
+        if let Some(battery_save) = &mut self.battery_save {
+            battery_save.set_flush_interval(flush_interval);
+        }
+    }
+
+    /// Subscribes to the rumble motor. Subscribe before handing the cartridge
+    /// to the emulator; cartridges without a motor never send an event.
+    pub fn subscribe_rumble(&mut self) -> Receiver<RumbleEvent> {
+        self.controller.subscribe_rumble()
+    }
+}
+
+impl MemoryComponent for Cartridge {
+    fn mapped_locations(&self) -> Vec<u16> {
+        self.controller.mapped_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        self.controller.read(location)
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        self.controller.write(location, value)
+    }
+}
@@ -0,0 +1,169 @@
+use num::FromPrimitive;
+
+use crate::{
+    cartridge::{CartridgeError, CartridgeType},
+    rom::{
+        global_checksum,
+        header_checksum,
+        CbgCompatibility,
+        RamSize,
+        RomSize,
+        CARTRIDGE_TYPE_ADDRESS,
+        CGB_COMPATIBILITY_ADDRESS,
+        GAME_TITLE_START_ADDRESS,
+        GLOBAL_CHECKSUM_HIGH_ADDRESS,
+        GLOBAL_CHECKSUM_LOW_ADDRESS,
+        HEADER_CHECKSUM_ADDRESS,
+        HEADER_END_ADDRESS,
+        NEW_MAKER_CODES,
+        NEW_MAKER_CODE_ADDRESS_HIGH,
+        NEW_MAKER_CODE_ADDRESS_LOW,
+        OLD_MAKER_CODES,
+        OLD_MAKER_CODE_ADDRESS,
+        RAM_SIZE_ADDRESS,
+        ROM_SIZE_ADDRESS,
+        SGB_FLAG_ADDRESS,
+        SGB_SUPPORT_VALUE,
+        USE_NEW_MAKER_CODE_VALUE,
+    },
+};
+
+/// The parsed contents of the cartridge header at 0x0100-0x014F.
+#[derive(Clone, Debug)]
+pub struct CartridgeHeader {
+    cartridge_type: CartridgeType,
+    cgb_compatibility: CbgCompatibility,
+    global_checksum: u16,
+    header_checksum: u8,
+    maker_code: String,
+    old_maker_code: u8,
+    ram_size: RamSize,
+    rom_size: RomSize,
+    sgb_support: bool,
+    title: String,
+}
+
+impl CartridgeHeader {
+    pub fn parse(data: &[u8]) -> Result<Self, CartridgeError> {
+        if data.len() <= HEADER_END_ADDRESS as usize {
+            return Err(CartridgeError::HeaderTooShort(data.len()));
+        }
+
+        let cartridge_type_value = data[CARTRIDGE_TYPE_ADDRESS as usize];
+        let cartridge_type = CartridgeType::from_u8(cartridge_type_value)
+            .ok_or(CartridgeError::UnsupportedCartridgeType(cartridge_type_value))?;
+
+        let rom_size_value = data[ROM_SIZE_ADDRESS as usize];
+        let rom_size = RomSize::from_u8(rom_size_value).ok_or(CartridgeError::InvalidRomSize(rom_size_value))?;
+
+        let ram_size_value = data[RAM_SIZE_ADDRESS as usize];
+        let ram_size = RamSize::from_u8(ram_size_value).ok_or(CartridgeError::InvalidRamSize(ram_size_value))?;
+
+        let cgb_compatibility = CbgCompatibility::from_flag(data[CGB_COMPATIBILITY_ADDRESS as usize]);
+
+        // Older cartridges use the CGB flag as the last title character
+        let title_end = if cgb_compatibility == CbgCompatibility::CGBIncompatible {
+            CGB_COMPATIBILITY_ADDRESS
+        } else {
+            CGB_COMPATIBILITY_ADDRESS - 1
+        };
+
+        let title = data[GAME_TITLE_START_ADDRESS as usize..=title_end as usize]
+            .iter()
+            .take_while(|c| **c != 0x00u8)
+            .map(|c| *c as char)
+            .collect();
+
+        // New maker codes are two ASCII characters, old ones a single byte
+        // written here in hex to look them up the same way
+        let old_maker_code = data[OLD_MAKER_CODE_ADDRESS as usize];
+        let maker_code = if old_maker_code == USE_NEW_MAKER_CODE_VALUE {
+            [data[NEW_MAKER_CODE_ADDRESS_HIGH as usize], data[NEW_MAKER_CODE_ADDRESS_LOW as usize]]
+                .iter()
+                .map(|c| *c as char)
+                .collect()
+        } else {
+            format!("{:02X}", old_maker_code)
+        };
+
+        // The SGB ignores the flag unless the old maker code defers to the
+        // new one
+        let sgb_support = data[SGB_FLAG_ADDRESS as usize] == SGB_SUPPORT_VALUE
+            && old_maker_code == USE_NEW_MAKER_CODE_VALUE;
+
+        Ok(CartridgeHeader {
+            cartridge_type,
+            cgb_compatibility,
+            global_checksum: u16::from_be_bytes([
+                data[GLOBAL_CHECKSUM_HIGH_ADDRESS as usize],
+                data[GLOBAL_CHECKSUM_LOW_ADDRESS as usize],
+            ]),
+            header_checksum: data[HEADER_CHECKSUM_ADDRESS as usize],
+            maker_code,
+            old_maker_code,
+            ram_size,
+            rom_size,
+            sgb_support,
+            title,
+        })
+    }
+
+    pub fn cartridge_type(&self) -> CartridgeType {
+        self.cartridge_type
+    }
+
+    pub fn cgb_compatibility(&self) -> CbgCompatibility {
+        self.cgb_compatibility
+    }
+
+    pub fn global_checksum(&self) -> u16 {
+        self.global_checksum
+    }
+
+    /// Whether the stored global checksum matches the ROM. Real hardware never
+    /// checks this, so a mismatch is only informative.
+    pub fn global_checksum_valid(&self, data: &[u8]) -> bool {
+        global_checksum(data) == self.global_checksum
+    }
+
+    pub fn header_checksum(&self) -> u8 {
+        self.header_checksum
+    }
+
+    /// Whether the stored header checksum matches the header. The boot ROM
+    /// locks up on a mismatch.
+    pub fn header_checksum_valid(&self, data: &[u8]) -> bool {
+        header_checksum(data) == self.header_checksum
+    }
+
+    /// The maker's name, from whichever of the two code tables the header
+    /// uses.
+    pub fn maker(&self) -> Option<&'static str> {
+        let maker = match self.old_maker_code {
+            USE_NEW_MAKER_CODE_VALUE => NEW_MAKER_CODES.get(self.maker_code.as_str()),
+            old_maker_code => OLD_MAKER_CODES.get(&old_maker_code),
+        };
+
+        maker.map(|m| m.as_str())
+    }
+
+    pub fn maker_code(&self) -> &str {
+        &self.maker_code
+    }
+
+    pub fn ram_size(&self) -> RamSize {
+        self.ram_size
+    }
+
+    pub fn rom_size(&self) -> RomSize {
+        self.rom_size
+    }
+
+    pub fn sgb_support(&self) -> bool {
+        self.sgb_support
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+}
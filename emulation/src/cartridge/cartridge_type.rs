@@ -0,0 +1,75 @@
+/// An enumeration of the cartridge types encoded in the header at 0x0147.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[repr(u8)]
+pub enum CartridgeType {
+    RomOnly = 0x00u8,
+    Mbc1 = 0x01u8,
+    Mbc1Ram = 0x02u8,
+    Mbc1RamBattery = 0x03u8,
+    Mbc2 = 0x05u8,
+    Mbc2Battery = 0x06u8,
+    RomRam = 0x08u8,
+    RomRamBattery = 0x09u8,
+    Mmm01 = 0x0bu8,
+    Mmm01Ram = 0x0cu8,
+    Mmm01RamBattery = 0x0du8,
+    Mbc3TimerBattery = 0x0fu8,
+    Mbc3TimerRamBattery = 0x10u8,
+    Mbc3 = 0x11u8,
+    Mbc3Ram = 0x12u8,
+    Mbc3RamBattery = 0x13u8,
+    Mbc5 = 0x19u8,
+    Mbc5Ram = 0x1au8,
+    Mbc5RamBattery = 0x1bu8,
+    Mbc5Rumble = 0x1cu8,
+    Mbc5RumbleRam = 0x1du8,
+    Mbc5RumbleRamBattery = 0x1eu8,
+    Mbc6 = 0x20u8,
+    Mbc7SensorRumbleRamBattery = 0x22u8,
+    PocketCamera = 0xfcu8,
+    BandaiTama5 = 0xfdu8,
+    HuC3 = 0xfeu8,
+    HuC1RamBattery = 0xffu8,
+}
+
+impl CartridgeType {
+    /// Whether the header marks the cartridge as "+BATTERY", meaning its
+    /// external RAM (and clock, if any) survives power-off.
+    pub fn has_battery(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc1RamBattery
+                | CartridgeType::Mbc2Battery
+                | CartridgeType::RomRamBattery
+                | CartridgeType::Mmm01RamBattery
+                | CartridgeType::Mbc3TimerBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Mbc3RamBattery
+                | CartridgeType::Mbc5RamBattery
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+                | CartridgeType::HuC1RamBattery
+        )
+    }
+
+    pub fn has_ram(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::Mbc1Ram
+                | CartridgeType::Mbc1RamBattery
+                | CartridgeType::RomRam
+                | CartridgeType::RomRamBattery
+                | CartridgeType::Mmm01Ram
+                | CartridgeType::Mmm01RamBattery
+                | CartridgeType::Mbc3TimerRamBattery
+                | CartridgeType::Mbc3Ram
+                | CartridgeType::Mbc3RamBattery
+                | CartridgeType::Mbc5Ram
+                | CartridgeType::Mbc5RamBattery
+                | CartridgeType::Mbc5RumbleRam
+                | CartridgeType::Mbc5RumbleRamBattery
+                | CartridgeType::Mbc7SensorRumbleRamBattery
+                | CartridgeType::HuC1RamBattery
+        )
+    }
+}
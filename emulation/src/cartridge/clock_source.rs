@@ -0,0 +1,63 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A source of wall-clock time for cartridge real-time clocks.
+pub trait ClockSource {
+    /// Seconds since the UNIX epoch.
+    fn now(&self) -> u64;
+}
+
+/// Reads the host's system clock.
+pub struct SystemClockSource {}
+
+impl SystemClockSource {
+    pub fn new() -> Self {
+        SystemClockSource {}
+    }
+}
+
+impl Default for SystemClockSource {
+    fn default() -> Self {
+        SystemClockSource::new()
+    }
+}
+
+impl ClockSource for SystemClockSource {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0u64, |d| d.as_secs())
+    }
+}
+
+/// A clock that only moves when told to, so tests can advance time
+/// deterministically. Clones share the same time.
+#[derive(Clone, Default)]
+pub struct ManualClockSource {
+    now: Arc<AtomicU64>,
+}
+
+impl ManualClockSource {
+    pub fn new(now: u64) -> Self {
+        ManualClockSource {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    pub fn advance(&self, seconds: u64) {
+        self.now.fetch_add(seconds, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+}
+
+impl ClockSource for ManualClockSource {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
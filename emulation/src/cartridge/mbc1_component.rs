@@ -0,0 +1,176 @@
+use crate::{
+    cartridge::memory_bank_controller::{cartridge_locations, ram_bank_offset, read_rom_bank, MemoryBankController},
+    memory_component::{MemoryComponent, MemoryError},
+    rom::{
+        EXTERNAL_RAM_END_ADDRESS,
+        EXTERNAL_RAM_START_ADDRESS,
+        FIXED_BANK_END_ADDRESS,
+        LOGO_END_ADDRESS,
+        LOGO_START_ADDRESS,
+        NINTENDO_LOGO,
+        PROGRAM_AREA_END_ADDRESS,
+        PROGRAM_AREA_START_ADDRESS,
+        ROM_BANK_SIZE,
+        SWITCHABLE_BANK_START_ADDRESS,
+    },
+};
+
+const RAM_ENABLE_END_ADDRESS: u16 = 0x1fffu16;
+const ROM_BANK_NUMBER_START_ADDRESS: u16 = 0x2000u16;
+const ROM_BANK_NUMBER_END_ADDRESS: u16 = 0x3fffu16;
+const RAM_BANK_NUMBER_START_ADDRESS: u16 = 0x4000u16;
+const RAM_BANK_NUMBER_END_ADDRESS: u16 = 0x5fffu16;
+const BANKING_MODE_START_ADDRESS: u16 = 0x6000u16;
+const BANKING_MODE_END_ADDRESS: u16 = 0x7fffu16;
+
+const RAM_ENABLE_VALUE: u8 = 0x0au8;
+
+// Multicarts are 1MB ROMs made of four 256KB games, each with its own header
+const MULTICART_ROM_SIZE: usize = 0x100000usize;
+const MULTICART_GAME_BANKS: usize = 0x10usize;
+
+/// The MBC1 memory bank controller.
+///
+/// Up to 2MB of ROM and 32KB of RAM. The 5-bit BANK1 register selects the ROM
+/// bank at 0x4000-0x7FFF, and the 2-bit BANK2 register either extends it (mode
+/// 0) or also applies to 0x0000-0x3FFF and the RAM bank (mode 1).
+///
+/// MBC1M multicarts wire only four bits of BANK1, so BANK2 selects the game.
+pub struct Mbc1Component {
+    bank_1: u8,
+    bank_2: u8,
+    banking_mode: bool,
+    multicart: bool,
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom: Vec<u8>,
+}
+
+impl Mbc1Component {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        let multicart = Mbc1Component::is_multicart(&rom);
+
+        Mbc1Component {
+            bank_1: 0x01u8,
+            bank_2: 0x00u8,
+            banking_mode: false,
+            multicart,
+            ram: vec![0x00u8; ram_size],
+            ram_enabled: false,
+            rom,
+        }
+    }
+
+    /// Detects the MBC1M wiring by looking for a boot logo at the start of the
+    /// second game, since the header can't tell the two wirings apart.
+    pub fn is_multicart(rom: &[u8]) -> bool {
+        if rom.len() != MULTICART_ROM_SIZE {
+            return false;
+        }
+
+        let second_game = MULTICART_GAME_BANKS * ROM_BANK_SIZE;
+
+        rom[second_game + LOGO_START_ADDRESS as usize..=second_game + LOGO_END_ADDRESS as usize] == NINTENDO_LOGO
+    }
+
+    pub fn multicart(&self) -> bool {
+        self.multicart
+    }
+
+    fn bank_2_shift(&self) -> usize {
+        if self.multicart {
+            4
+        } else {
+            5
+        }
+    }
+
+    fn high_rom_bank(&self) -> usize {
+        let bank_1 = if self.multicart {
+            self.bank_1 & 0x0fu8
+        } else {
+            self.bank_1
+        };
+
+        ((self.bank_2 as usize) << self.bank_2_shift()) | bank_1 as usize
+    }
+
+    fn low_rom_bank(&self) -> usize {
+        if self.banking_mode {
+            (self.bank_2 as usize) << self.bank_2_shift()
+        } else {
+            0
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        if self.banking_mode {
+            self.bank_2 as usize
+        } else {
+            0
+        }
+    }
+}
+
+impl MemoryBankController for Mbc1Component {
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+impl MemoryComponent for Mbc1Component {
+    fn mapped_locations(&self) -> Vec<u16> {
+        cartridge_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=FIXED_BANK_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, self.low_rom_bank(), location))
+            },
+            SWITCHABLE_BANK_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, self.high_rom_bank(), location))
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if !self.ram_enabled {
+                    return Ok(0xffu8);
+                }
+
+                Ok(ram_bank_offset(&self.ram, self.ram_bank(), location).map_or(0xffu8, |o| self.ram[o]))
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=RAM_ENABLE_END_ADDRESS => {
+                self.ram_enabled = value & 0x0fu8 == RAM_ENABLE_VALUE;
+            },
+            ROM_BANK_NUMBER_START_ADDRESS..=ROM_BANK_NUMBER_END_ADDRESS => {
+                // Bank 0 can't be selected here; the zero check sees all five bits
+                self.bank_1 = (value & 0x1fu8).max(0x01u8);
+            },
+            RAM_BANK_NUMBER_START_ADDRESS..=RAM_BANK_NUMBER_END_ADDRESS => {
+                self.bank_2 = value & 0x03u8;
+            },
+            BANKING_MODE_START_ADDRESS..=BANKING_MODE_END_ADDRESS => {
+                self.banking_mode = value & 0x01u8 > 0;
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if self.ram_enabled {
+                    if let Some(offset) = ram_bank_offset(&self.ram, self.ram_bank(), location) {
+                        self.ram[offset] = value;
+                    }
+                }
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
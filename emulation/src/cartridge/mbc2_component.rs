@@ -0,0 +1,101 @@
+use crate::{
+    cartridge::memory_bank_controller::{cartridge_locations, read_rom_bank, MemoryBankController},
+    memory_component::{MemoryComponent, MemoryError},
+    rom::{
+        EXTERNAL_RAM_END_ADDRESS,
+        EXTERNAL_RAM_START_ADDRESS,
+        FIXED_BANK_END_ADDRESS,
+        PROGRAM_AREA_END_ADDRESS,
+        PROGRAM_AREA_START_ADDRESS,
+        SWITCHABLE_BANK_START_ADDRESS,
+    },
+};
+
+// The built-in RAM is 512 half-bytes, echoed through the whole RAM area
+const BUILT_IN_RAM_SIZE: usize = 0x200usize;
+
+// Address bit 8 picks which register a write to 0x0000-0x3FFF lands in
+const REGISTER_SELECT_BIT: u16 = 0x0100u16;
+
+const RAM_ENABLE_VALUE: u8 = 0x0au8;
+
+/// The MBC2 memory bank controller.
+///
+/// Up to 256KB of ROM and 512x4 bits of built-in RAM. Both registers share
+/// 0x0000-0x3FFF, told apart by bit 8 of the address.
+pub struct Mbc2Component {
+    ram: Vec<u8>,
+    ram_enabled: bool,
+    rom: Vec<u8>,
+    rom_bank: u8,
+}
+
+impl Mbc2Component {
+    pub fn new(rom: Vec<u8>) -> Self {
+        Mbc2Component {
+            ram: vec![0x00u8; BUILT_IN_RAM_SIZE],
+            ram_enabled: false,
+            rom,
+            rom_bank: 0x01u8,
+        }
+    }
+
+    fn ram_offset(location: u16) -> usize {
+        (location - EXTERNAL_RAM_START_ADDRESS) as usize % BUILT_IN_RAM_SIZE
+    }
+}
+
+impl MemoryBankController for Mbc2Component {
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+impl MemoryComponent for Mbc2Component {
+    fn mapped_locations(&self) -> Vec<u16> {
+        cartridge_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=FIXED_BANK_END_ADDRESS => Ok(read_rom_bank(&self.rom, 0, location)),
+            SWITCHABLE_BANK_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, self.rom_bank as usize, location))
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if !self.ram_enabled {
+                    return Ok(0xffu8);
+                }
+
+                // Only the low nibble exists; the upper one floats high
+                Ok(0xf0u8 | self.ram[Mbc2Component::ram_offset(location)])
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=FIXED_BANK_END_ADDRESS => {
+                if location & REGISTER_SELECT_BIT == 0 {
+                    self.ram_enabled = value & 0x0fu8 == RAM_ENABLE_VALUE;
+                } else {
+                    self.rom_bank = (value & 0x0fu8).max(0x01u8);
+                }
+            },
+            SWITCHABLE_BANK_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {},
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if self.ram_enabled {
+                    self.ram[Mbc2Component::ram_offset(location)] = value & 0x0fu8;
+                }
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
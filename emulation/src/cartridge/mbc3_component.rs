@@ -0,0 +1,180 @@
+use crate::{
+    cartridge::{
+        memory_bank_controller::{cartridge_locations, ram_bank_offset, read_rom_bank, MemoryBankController},
+        ClockSource,
+        RealTimeClock,
+    },
+    memory_component::{MemoryComponent, MemoryError},
+    rom::{
+        EXTERNAL_RAM_END_ADDRESS,
+        EXTERNAL_RAM_START_ADDRESS,
+        FIXED_BANK_END_ADDRESS,
+        PROGRAM_AREA_END_ADDRESS,
+        PROGRAM_AREA_START_ADDRESS,
+        SWITCHABLE_BANK_START_ADDRESS,
+    },
+};
+
+const RAM_ENABLE_END_ADDRESS: u16 = 0x1fffu16;
+const ROM_BANK_NUMBER_START_ADDRESS: u16 = 0x2000u16;
+const ROM_BANK_NUMBER_END_ADDRESS: u16 = 0x3fffu16;
+const RAM_BANK_NUMBER_START_ADDRESS: u16 = 0x4000u16;
+const RAM_BANK_NUMBER_END_ADDRESS: u16 = 0x5fffu16;
+const LATCH_CLOCK_START_ADDRESS: u16 = 0x6000u16;
+const LATCH_CLOCK_END_ADDRESS: u16 = 0x7fffu16;
+
+const RAM_ENABLE_VALUE: u8 = 0x0au8;
+
+const RTC_REGISTER_START: u8 = 0x08u8;
+const RTC_REGISTER_END: u8 = 0x0cu8;
+
+/// The MBC3 memory bank controller.
+///
+/// Up to 2MB of ROM selected by a 7-bit bank number, four 8KB RAM banks and,
+/// on "+TIMER" cartridges, a real-time clock whose registers are mapped into
+/// 0xA000-0xBFFF in place of RAM by selecting banks 0x08-0x0C.
+pub struct Mbc3Component {
+    ram: Vec<u8>,
+    ram_and_timer_enabled: bool,
+    ram_bank: u8,
+    real_time_clock: Option<RealTimeClock>,
+    rom: Vec<u8>,
+    rom_bank: u8,
+}
+
+impl Mbc3Component {
+    pub fn new(rom: Vec<u8>, ram_size: usize, clock_source: Option<Box<dyn ClockSource>>) -> Self {
+        Mbc3Component {
+            ram: vec![0x00u8; ram_size],
+            ram_and_timer_enabled: false,
+            ram_bank: 0x00u8,
+            real_time_clock: clock_source.map(RealTimeClock::new),
+            rom,
+            rom_bank: 0x01u8,
+        }
+    }
+
+    pub fn real_time_clock(&self) -> Option<&RealTimeClock> {
+        self.real_time_clock.as_ref()
+    }
+
+    fn selected_clock_register(&self) -> Option<u8> {
+        match self.ram_bank {
+            RTC_REGISTER_START..=RTC_REGISTER_END => Some(self.ram_bank),
+            _ => None,
+        }
+    }
+}
+
+impl MemoryBankController for Mbc3Component {
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn save_data(&self) -> Vec<u8> {
+        let mut data = self.ram.clone();
+
+        if let Some(real_time_clock) = &self.real_time_clock {
+            data.extend_from_slice(&real_time_clock.save_trailer());
+        }
+
+        data
+    }
+
+    fn load_save_data(&mut self, data: &[u8]) {
+        let length = self.ram.len().min(data.len());
+
+        self.ram[..length].copy_from_slice(&data[..length]);
+
+        if let Some(real_time_clock) = &mut self.real_time_clock {
+            real_time_clock.load_trailer(&data[length..]);
+        }
+    }
+
+    fn save_data_matches(&self, saved: &[u8]) -> bool {
+        if saved.len() < self.ram.len() {
+            return false;
+        }
+
+        let (ram, trailer) = saved.split_at(self.ram.len());
+
+        if ram != self.ram.as_slice() {
+            return false;
+        }
+
+        match &self.real_time_clock {
+            Some(real_time_clock) => real_time_clock.trailer_matches(trailer),
+            None => trailer.is_empty(),
+        }
+    }
+}
+
+impl MemoryComponent for Mbc3Component {
+    fn mapped_locations(&self) -> Vec<u16> {
+        cartridge_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=FIXED_BANK_END_ADDRESS => Ok(read_rom_bank(&self.rom, 0, location)),
+            SWITCHABLE_BANK_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, self.rom_bank as usize, location))
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if !self.ram_and_timer_enabled {
+                    return Ok(0xffu8);
+                }
+
+                match (self.selected_clock_register(), &self.real_time_clock) {
+                    (Some(register), Some(real_time_clock)) => Ok(real_time_clock.read(register)),
+                    (Some(_), None) => Ok(0xffu8),
+                    (None, _) => {
+                        Ok(ram_bank_offset(&self.ram, self.ram_bank as usize, location).map_or(0xffu8, |o| self.ram[o]))
+                    },
+                }
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=RAM_ENABLE_END_ADDRESS => {
+                self.ram_and_timer_enabled = value & 0x0fu8 == RAM_ENABLE_VALUE;
+            },
+            ROM_BANK_NUMBER_START_ADDRESS..=ROM_BANK_NUMBER_END_ADDRESS => {
+                self.rom_bank = (value & 0x7fu8).max(0x01u8);
+            },
+            RAM_BANK_NUMBER_START_ADDRESS..=RAM_BANK_NUMBER_END_ADDRESS => {
+                self.ram_bank = value & 0x0fu8;
+            },
+            LATCH_CLOCK_START_ADDRESS..=LATCH_CLOCK_END_ADDRESS => {
+                if let Some(real_time_clock) = &mut self.real_time_clock {
+                    real_time_clock.latch(value);
+                }
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if !self.ram_and_timer_enabled {
+                    return Ok(());
+                }
+
+                match (self.selected_clock_register(), &mut self.real_time_clock) {
+                    (Some(register), Some(real_time_clock)) => real_time_clock.write(register, value),
+                    (Some(_), None) => {},
+                    (None, _) => {
+                        if let Some(offset) = ram_bank_offset(&self.ram, self.ram_bank as usize, location) {
+                            self.ram[offset] = value;
+                        }
+                    },
+                };
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::{
+    cartridge::{
+        memory_bank_controller::{cartridge_locations, ram_bank_offset, read_rom_bank, MemoryBankController},
+        RumbleEvent,
+    },
+    memory_component::{MemoryComponent, MemoryError},
+    rom::{
+        EXTERNAL_RAM_END_ADDRESS,
+        EXTERNAL_RAM_START_ADDRESS,
+        FIXED_BANK_END_ADDRESS,
+        PROGRAM_AREA_END_ADDRESS,
+        PROGRAM_AREA_START_ADDRESS,
+        SWITCHABLE_BANK_START_ADDRESS,
+    },
+};
+
+const RAM_ENABLE_END_ADDRESS: u16 = 0x1fffu16;
+const ROM_BANK_LOW_START_ADDRESS: u16 = 0x2000u16;
+const ROM_BANK_LOW_END_ADDRESS: u16 = 0x2fffu16;
+const ROM_BANK_HIGH_START_ADDRESS: u16 = 0x3000u16;
+const ROM_BANK_HIGH_END_ADDRESS: u16 = 0x3fffu16;
+const RAM_BANK_NUMBER_START_ADDRESS: u16 = 0x4000u16;
+const RAM_BANK_NUMBER_END_ADDRESS: u16 = 0x5fffu16;
+const UNUSED_REGISTER_START_ADDRESS: u16 = 0x6000u16;
+
+const RAM_ENABLE_VALUE: u8 = 0x0au8;
+
+// Rumble cartridges drive the motor from bit 3 of the RAM bank number
+const RUMBLE_BIT: u8 = 0b00001000;
+
+/// The MBC5 memory bank controller.
+///
+/// Up to 8MB of ROM selected by a 9-bit bank number (bank 0 is selectable at
+/// 0x4000-0x7FFF) and sixteen 8KB RAM banks.
+pub struct Mbc5Component {
+    ram: Vec<u8>,
+    ram_bank: u8,
+    ram_enabled: bool,
+    rom: Vec<u8>,
+    rom_bank: u16,
+    rumble: Option<bool>,
+    rumble_subscribers: Vec<Sender<RumbleEvent>>,
+}
+
+impl Mbc5Component {
+    pub fn new(rom: Vec<u8>, ram_size: usize, has_rumble: bool) -> Self {
+        Mbc5Component {
+            ram: vec![0x00u8; ram_size],
+            ram_bank: 0x00u8,
+            ram_enabled: false,
+            rom,
+            rom_bank: 0x0001u16,
+            rumble: if has_rumble { Some(false) } else { None },
+            rumble_subscribers: Vec::new(),
+        }
+    }
+
+    pub fn rumble(&self) -> bool {
+        self.rumble.unwrap_or(false)
+    }
+
+    fn set_ram_bank(&mut self, value: u8) {
+        match self.rumble {
+            Some(rumble) => {
+                let active = value & RUMBLE_BIT > 0;
+
+                self.ram_bank = value & 0x07u8;
+
+                if active != rumble {
+                    self.rumble = Some(active);
+
+                    let event = if active {
+                        RumbleEvent::Started
+                    } else {
+                        RumbleEvent::Stopped
+                    };
+
+                    // Drop subscribers that have gone away
+                    self.rumble_subscribers.retain(|s| s.send(event).is_ok());
+                }
+            },
+            None => self.ram_bank = value & 0x0fu8,
+        };
+    }
+}
+
+impl MemoryBankController for Mbc5Component {
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+
+    fn subscribe_rumble(&mut self) -> Receiver<RumbleEvent> {
+        let (sender, receiver) = channel();
+
+        if self.rumble.is_some() {
+            self.rumble_subscribers.push(sender);
+        }
+
+        receiver
+    }
+}
+
+impl MemoryComponent for Mbc5Component {
+    fn mapped_locations(&self) -> Vec<u16> {
+        cartridge_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=FIXED_BANK_END_ADDRESS => Ok(read_rom_bank(&self.rom, 0, location)),
+            SWITCHABLE_BANK_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, self.rom_bank as usize, location))
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if !self.ram_enabled {
+                    return Ok(0xffu8);
+                }
+
+                Ok(ram_bank_offset(&self.ram, self.ram_bank as usize, location).map_or(0xffu8, |o| self.ram[o]))
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=RAM_ENABLE_END_ADDRESS => {
+                self.ram_enabled = value & 0x0fu8 == RAM_ENABLE_VALUE;
+            },
+            ROM_BANK_LOW_START_ADDRESS..=ROM_BANK_LOW_END_ADDRESS => {
+                self.rom_bank = (self.rom_bank & 0x100u16) | value as u16;
+            },
+            ROM_BANK_HIGH_START_ADDRESS..=ROM_BANK_HIGH_END_ADDRESS => {
+                self.rom_bank = (self.rom_bank & 0x0ffu16) | (((value & 0x01u8) as u16) << 8);
+            },
+            RAM_BANK_NUMBER_START_ADDRESS..=RAM_BANK_NUMBER_END_ADDRESS => {
+                self.set_ram_bank(value);
+            },
+            UNUSED_REGISTER_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {},
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if self.ram_enabled {
+                    if let Some(offset) = ram_bank_offset(&self.ram, self.ram_bank as usize, location) {
+                        self.ram[offset] = value;
+                    }
+                }
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use crate::{
+    cartridge::RumbleEvent,
+    memory_component::MemoryComponent,
+    rom::{EXTERNAL_RAM_END_ADDRESS, EXTERNAL_RAM_START_ADDRESS, PROGRAM_AREA_END_ADDRESS, PROGRAM_AREA_START_ADDRESS, RAM_BANK_SIZE, ROM_BANK_SIZE},
+};
+
+/// A memory bank controller maps the cartridge ROM and external RAM into the
+/// address space, switching banks in response to writes to the ROM area.
+pub trait MemoryBankController: MemoryComponent {
+    /// The external RAM, in bank order.
+    fn ram(&self) -> &[u8];
+
+    fn ram_mut(&mut self) -> &mut [u8];
+
+    /// Subscribes to changes of the rumble motor. Controllers without a motor
+    /// hand back a receiver that never yields an event.
+    fn subscribe_rumble(&mut self) -> Receiver<RumbleEvent> {
+        channel().1
+    }
+
+    /// Serializes the battery-backed state in the .sav layout: the external
+    /// RAM, followed by any controller-specific trailer.
+    fn save_data(&self) -> Vec<u8> {
+        self.ram().to_vec()
+    }
+
+    /// Restores state written by `save_data`. Short images only fill the start
+    /// of RAM.
+    fn load_save_data(&mut self, data: &[u8]) {
+        let ram = self.ram_mut();
+        let length = ram.len().min(data.len());
+
+        ram[..length].copy_from_slice(&data[..length]);
+    }
+
+    /// Whether data written by `save_data` earlier still holds the current
+    /// state, so there is no need to write it again.
+    fn save_data_matches(&self, saved: &[u8]) -> bool {
+        self.ram() == saved
+    }
+}
+
+/// The locations every cartridge maps, whether or not it has RAM.
+pub fn cartridge_locations() -> Vec<u16> {
+    (PROGRAM_AREA_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS)
+        .chain(EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS)
+        .collect()
+}
+
+/// Reads from a 16KB ROM bank, wrapping the bank number to the ROM size the
+/// way the unconnected upper bank lines do.
+pub fn read_rom_bank(rom: &[u8], bank: usize, location: u16) -> u8 {
+    let banks = (rom.len() / ROM_BANK_SIZE).max(1);
+
+    let offset = (bank % banks) * ROM_BANK_SIZE + (location as usize % ROM_BANK_SIZE);
+
+    rom.get(offset).copied().unwrap_or(0xffu8)
+}
+
+/// Maps a location in 0xA000-0xBFFF to an offset into banked external RAM.
+pub fn ram_bank_offset(ram: &[u8], bank: usize, location: u16) -> Option<usize> {
+    if ram.is_empty() {
+        return None;
+    }
+
+    let offset = bank * RAM_BANK_SIZE + (location - EXTERNAL_RAM_START_ADDRESS) as usize;
+
+    Some(offset % ram.len())
+}
@@ -0,0 +1,27 @@
+mod battery_save;
+mod cartridge;
+mod cartridge_header;
+mod cartridge_type;
+mod clock_source;
+mod mbc1_component;
+mod mbc2_component;
+mod mbc3_component;
+mod mbc5_component;
+mod memory_bank_controller;
+mod real_time_clock;
+mod rom_only_component;
+mod rumble_event;
+
+pub use battery_save::{BatterySave, DEFAULT_FLUSH_INTERVAL};
+pub use cartridge::{Cartridge, CartridgeError};
+pub use cartridge_header::CartridgeHeader;
+pub use cartridge_type::CartridgeType;
+pub use clock_source::{ClockSource, ManualClockSource, SystemClockSource};
+pub use mbc1_component::Mbc1Component;
+pub use mbc2_component::Mbc2Component;
+pub use mbc3_component::Mbc3Component;
+pub use mbc5_component::Mbc5Component;
+pub use memory_bank_controller::MemoryBankController;
+pub use real_time_clock::{RealTimeClock, RtcRegisters, RTC_TRAILER_SIZE};
+pub use rom_only_component::RomOnlyComponent;
+pub use rumble_event::RumbleEvent;
@@ -0,0 +1,258 @@
+use crate::cartridge::ClockSource;
+
+pub const RTC_SECONDS_REGISTER: u8 = 0x08u8;
+pub const RTC_MINUTES_REGISTER: u8 = 0x09u8;
+pub const RTC_HOURS_REGISTER: u8 = 0x0au8;
+pub const RTC_DAYS_LOW_REGISTER: u8 = 0x0bu8;
+pub const RTC_DAYS_HIGH_REGISTER: u8 = 0x0cu8;
+
+/// The size of the RTC trailer appended to battery RAM in .sav files, as
+/// written by BGB, VBA-M, SameBoy and others.
+pub const RTC_TRAILER_SIZE: usize = 48usize;
+
+const DAYS_HIGH_BIT: u8 = 0b00000001;
+const HALT_BIT: u8 = 0b01000000;
+const DAY_CARRY_BIT: u8 = 0b10000000;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const DAY_COUNTER_LIMIT: u64 = 512;
+
+/// The MBC3 clock counters, as seen through registers 0x08-0x0C.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RtcRegisters {
+    pub day_carry: bool,
+    pub days: u16,
+    pub halt: bool,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl RtcRegisters {
+    /// Moves the counters forward, setting the day carry when the 9-bit day
+    /// counter overflows.
+    pub fn advance(&mut self, seconds: u64) {
+        if self.halt || seconds == 0 {
+            return;
+        }
+
+        let total = self.seconds as u64
+            + self.minutes as u64 * 60
+            + self.hours as u64 * 60 * 60
+            + self.days as u64 * SECONDS_PER_DAY
+            + seconds;
+
+        let days = total / SECONDS_PER_DAY;
+
+        if days >= DAY_COUNTER_LIMIT {
+            self.day_carry = true;
+        }
+
+        self.days = (days % DAY_COUNTER_LIMIT) as u16;
+        self.hours = ((total / (60 * 60)) % 24) as u8;
+        self.minutes = ((total / 60) % 60) as u8;
+        self.seconds = (total % 60) as u8;
+    }
+
+    pub fn read(&self, register: u8) -> u8 {
+        match register {
+            RTC_SECONDS_REGISTER => self.seconds,
+            RTC_MINUTES_REGISTER => self.minutes,
+            RTC_HOURS_REGISTER => self.hours,
+            RTC_DAYS_LOW_REGISTER => self.days as u8,
+            RTC_DAYS_HIGH_REGISTER => self.days_high(),
+            _ => 0xffu8,
+        }
+    }
+
+    pub fn write(&mut self, register: u8, value: u8) {
+        match register {
+            RTC_SECONDS_REGISTER => self.seconds = value & 0x3fu8,
+            RTC_MINUTES_REGISTER => self.minutes = value & 0x3fu8,
+            RTC_HOURS_REGISTER => self.hours = value & 0x1fu8,
+            RTC_DAYS_LOW_REGISTER => self.days = (self.days & 0x100u16) | value as u16,
+            RTC_DAYS_HIGH_REGISTER => {
+                self.days = (self.days & 0x0ffu16) | (((value & DAYS_HIGH_BIT) as u16) << 8);
+                self.halt = value & HALT_BIT > 0;
+                self.day_carry = value & DAY_CARRY_BIT > 0;
+            },
+            _ => {},
+        };
+    }
+
+    fn days_high(&self) -> u8 {
+        let mut value = (self.days >> 8) as u8 & DAYS_HIGH_BIT;
+
+        if self.halt {
+            value |= HALT_BIT;
+        }
+
+        if self.day_carry {
+            value |= DAY_CARRY_BIT;
+        }
+
+        value
+    }
+
+    fn to_trailer_words(self) -> [u32; 5] {
+        [
+            self.seconds as u32,
+            self.minutes as u32,
+            self.hours as u32,
+            self.days as u8 as u32,
+            self.days_high() as u32,
+        ]
+    }
+
+    fn from_trailer_words(words: &[u32]) -> Self {
+        let mut registers = RtcRegisters::default();
+
+        for (i, word) in words.iter().enumerate() {
+            registers.write(RTC_SECONDS_REGISTER + i as u8, *word as u8);
+        }
+
+        registers
+    }
+}
+
+/// The MBC3 real-time clock.
+///
+/// The counters are kept relative to the injected clock source so time keeps
+/// passing while the emulator isn't running. Reads see the latched copy, which
+/// is refreshed by writing 0x00 then 0x01 to 0x6000-0x7FFF.
+pub struct RealTimeClock {
+    clock_source: Box<dyn ClockSource>,
+    last_updated: u64,
+    latch_armed: bool,
+    latched: RtcRegisters,
+    registers: RtcRegisters,
+}
+
+impl RealTimeClock {
+    pub fn new(clock_source: Box<dyn ClockSource>) -> Self {
+        let last_updated = clock_source.now();
+
+        RealTimeClock {
+            clock_source,
+            last_updated,
+            latch_armed: false,
+            latched: RtcRegisters::default(),
+            registers: RtcRegisters::default(),
+        }
+    }
+
+    /// The live counters, brought up to date with the clock source.
+    pub fn current(&self) -> RtcRegisters {
+        let mut registers = self.registers;
+
+        registers.advance(self.clock_source.now().saturating_sub(self.last_updated));
+
+        registers
+    }
+
+    pub fn latch(&mut self, value: u8) {
+        if self.latch_armed && value == 0x01u8 {
+            self.latched = self.current();
+        }
+
+        self.latch_armed = value == 0x00u8;
+    }
+
+    pub fn latched(&self) -> RtcRegisters {
+        self.latched
+    }
+
+    pub fn read(&self, register: u8) -> u8 {
+        self.latched.read(register)
+    }
+
+    pub fn write(&mut self, register: u8, value: u8) {
+        self.update();
+
+        self.registers.write(register, value);
+        self.latched.write(register, value);
+    }
+
+    /// Serializes the clock into the 48-byte .sav trailer: the live and
+    /// latched registers as ten little-endian words, then a 64-bit UNIX
+    /// timestamp.
+    pub fn save_trailer(&self) -> [u8; RTC_TRAILER_SIZE] {
+        let mut trailer = [0x00u8; RTC_TRAILER_SIZE];
+
+        let words = self.current().to_trailer_words().into_iter().chain(self.latched.to_trailer_words());
+
+        for (i, word) in words.enumerate() {
+            trailer[i * 4..(i + 1) * 4].copy_from_slice(&word.to_le_bytes());
+        }
+
+        trailer[40..48].copy_from_slice(&self.clock_source.now().to_le_bytes());
+
+        trailer
+    }
+
+    /// Restores the clock from a .sav trailer, catching up on the time that
+    /// passed since it was written. Also accepts the older 44-byte variant with
+    /// a 32-bit timestamp.
+    pub fn load_trailer(&mut self, trailer: &[u8]) {
+        let Some((registers, latched, timestamp)) = parse_trailer(trailer) else {
+            return;
+        };
+
+        self.registers = registers;
+        self.latched = latched;
+        self.last_updated = timestamp;
+
+        self.update();
+    }
+
+    /// Whether a trailer written by `save_trailer` still describes this clock
+    /// once caught up to now. Only the timestamp differs for a clock that has
+    /// just kept running, and that's no reason to rewrite the .sav file.
+    pub fn trailer_matches(&self, trailer: &[u8]) -> bool {
+        if trailer.len() != RTC_TRAILER_SIZE {
+            return false;
+        }
+
+        let Some((mut registers, latched, timestamp)) = parse_trailer(trailer) else {
+            return false;
+        };
+
+        registers.advance(self.clock_source.now().saturating_sub(timestamp));
+
+        registers == self.current() && latched == self.latched
+    }
+
+    fn update(&mut self) {
+        self.registers = self.current();
+        self.last_updated = self.clock_source.now();
+    }
+}
+
+/// Splits a .sav trailer into the live registers, the latched registers and
+/// the timestamp they were written at.
+fn parse_trailer(trailer: &[u8]) -> Option<(RtcRegisters, RtcRegisters, u64)> {
+    if trailer.len() < 44 {
+        return None;
+    }
+
+    let words: Vec<u32> = trailer[0..40]
+        .chunks_exact(4)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+        .collect();
+
+    let timestamp = if trailer.len() >= RTC_TRAILER_SIZE {
+        let mut bytes = [0x00u8; 8];
+
+        bytes.copy_from_slice(&trailer[40..48]);
+
+        u64::from_le_bytes(bytes)
+    } else {
+        u32::from_le_bytes([trailer[40], trailer[41], trailer[42], trailer[43]]) as u64
+    };
+
+    Some((
+        RtcRegisters::from_trailer_words(&words[0..5]),
+        RtcRegisters::from_trailer_words(&words[5..10]),
+        timestamp,
+    ))
+}
@@ -0,0 +1,65 @@
+use crate::{
+    cartridge::memory_bank_controller::{cartridge_locations, ram_bank_offset, read_rom_bank, MemoryBankController},
+    memory_component::{MemoryComponent, MemoryError},
+    rom::{EXTERNAL_RAM_END_ADDRESS, EXTERNAL_RAM_START_ADDRESS, PROGRAM_AREA_END_ADDRESS, PROGRAM_AREA_START_ADDRESS, ROM_BANK_SIZE},
+};
+
+/// A cartridge without a memory bank controller: 32KB of ROM and, optionally,
+/// up to 8KB of RAM wired straight to the bus.
+pub struct RomOnlyComponent {
+    ram: Vec<u8>,
+    rom: Vec<u8>,
+}
+
+impl RomOnlyComponent {
+    pub fn new(rom: Vec<u8>, ram_size: usize) -> Self {
+        RomOnlyComponent {
+            ram: vec![0x00u8; ram_size],
+            rom,
+        }
+    }
+}
+
+impl MemoryBankController for RomOnlyComponent {
+    fn ram(&self) -> &[u8] {
+        &self.ram
+    }
+
+    fn ram_mut(&mut self) -> &mut [u8] {
+        &mut self.ram
+    }
+}
+
+impl MemoryComponent for RomOnlyComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        cartridge_locations()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            PROGRAM_AREA_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => {
+                Ok(read_rom_bank(&self.rom, location as usize / ROM_BANK_SIZE, location))
+            },
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                // Without RAM the bus floats high
+                Ok(ram_bank_offset(&self.ram, 0, location).map_or(0xffu8, |o| self.ram[o]))
+            },
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            // Writes to ROM are ignored without a memory bank controller
+            PROGRAM_AREA_START_ADDRESS..=PROGRAM_AREA_END_ADDRESS => Ok(()),
+            EXTERNAL_RAM_START_ADDRESS..=EXTERNAL_RAM_END_ADDRESS => {
+                if let Some(offset) = ram_bank_offset(&self.ram, 0, location) {
+                    self.ram[offset] = value;
+                }
+
+                Ok(())
+            },
+            _ => Err(MemoryError::WriteError(location, value, "invalid state")),
+        }
+    }
+}
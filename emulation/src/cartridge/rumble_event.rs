@@ -0,0 +1,6 @@
+/// A change in the state of a cartridge's rumble motor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RumbleEvent {
+    Started,
+    Stopped,
+}
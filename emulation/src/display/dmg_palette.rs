@@ -0,0 +1,22 @@
+/// The RGB colors DMG shades 0-3 are shown in, lightest first.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DmgPalette {
+    /// The green tint of the original Game Boy's screen.
+    #[default]
+    Green,
+    Greyscale,
+    /// The olive tones of the Game Boy Pocket's screen.
+    Pocket,
+    Custom([[u8; 3]; 4]),
+}
+
+impl DmgPalette {
+    pub fn shades(&self) -> [[u8; 3]; 4] {
+        match self {
+            DmgPalette::Green => [[0xe0, 0xf8, 0xd0], [0x88, 0xc0, 0x70], [0x34, 0x68, 0x56], [0x08, 0x18, 0x20]],
+            DmgPalette::Greyscale => [[0xff, 0xff, 0xff], [0xaa, 0xaa, 0xaa], [0x55, 0x55, 0x55], [0x00, 0x00, 0x00]],
+            DmgPalette::Pocket => [[0xc4, 0xcf, 0xa1], [0x8b, 0x95, 0x6d], [0x4d, 0x53, 0x3c], [0x1f, 0x1f, 0x1f]],
+            DmgPalette::Custom(shades) => *shades,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod dmg_palette;
+mod rgb_converter;
+
+pub use dmg_palette::DmgPalette;
+pub use rgb_converter::RgbConverter;
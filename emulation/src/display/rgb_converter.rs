@@ -0,0 +1,105 @@
+use crate::{display::DmgPalette, emulator::Emulator};
+
+const CHANNEL_MASK: u16 = 0b11111;
+const GREEN_SHIFT: u16 = 5u16;
+const BLUE_SHIFT: u16 = 10u16;
+
+// Each corrected channel is a weighted sum of the three, out of 32, clamped
+// so the brightest white comes out a little dim, as on the LCD
+const CORRECTION_WEIGHTS: [[u32; 3]; 3] = [[26, 4, 2], [0, 24, 8], [6, 4, 22]];
+const CORRECTION_MAX: u32 = 960u32;
+const CORRECTION_SHIFT: u32 = 2u32;
+
+/// Turns frames into 24-bit RGB for display, so every frontend shows them the
+/// same way.
+///
+/// DMG shades go through the selected palette. CGB colors are widened from 5
+/// to 8 bits per channel, or, with color correction on, mixed and dimmed to
+/// look like they do on the CGB's LCD rather than on a modern screen. SGB
+/// colors were made for a TV, so they're always just widened.
+pub struct RgbConverter {
+    color_correction: bool,
+    palette: DmgPalette,
+}
+
+impl RgbConverter {
+    pub fn new() -> Self {
+        RgbConverter {
+            color_correction: false,
+            palette: DmgPalette::default(),
+        }
+    }
+
+    pub fn with_palette(palette: DmgPalette) -> Self {
+        RgbConverter {
+            palette,
+            ..RgbConverter::new()
+        }
+    }
+
+    pub fn color_correction(&self) -> bool {
+        self.color_correction
+    }
+
+    /// Converts an RGB555 color.
+    pub fn color_rgb(&self, color: u16) -> [u8; 3] {
+        let channels = split_channels(color);
+
+        if self.color_correction {
+            CORRECTION_WEIGHTS.map(|weights| {
+                let mixed: u32 = weights.iter().zip(channels).map(|(weight, channel)| weight * channel).sum();
+
+                (mixed.min(CORRECTION_MAX) >> CORRECTION_SHIFT) as u8
+            })
+        } else {
+            widen(color)
+        }
+    }
+
+    /// The emulator's last frame, row by row, three bytes a pixel. Uses the
+    /// bordered SGB frame on the SGB, the CGB colors in CGB mode, and the DMG
+    /// shades otherwise.
+    pub fn frame_rgb(&self, emulator: &Emulator) -> Option<Vec<u8>> {
+        if let Some(colors) = emulator.sgb_framebuffer() {
+            return Some(colors.into_iter().flat_map(widen).collect());
+        }
+
+        if let Some(colors) = emulator.color_framebuffer() {
+            return Some(colors.iter().flat_map(|color| self.color_rgb(*color)).collect());
+        }
+
+        emulator.framebuffer().map(|shades| shades.iter().flat_map(|shade| self.shade_rgb(*shade)).collect())
+    }
+
+    pub fn palette(&self) -> DmgPalette {
+        self.palette
+    }
+
+    pub fn set_color_correction(&mut self, color_correction: bool) {
+        self.color_correction = color_correction;
+    }
+
+    pub fn set_palette(&mut self, palette: DmgPalette) {
+        self.palette = palette;
+    }
+
+    /// Converts a DMG shade, 0 being the lightest.
+    pub fn shade_rgb(&self, shade: u8) -> [u8; 3] {
+        self.palette.shades()[(shade & 0b11) as usize]
+    }
+}
+
+impl Default for RgbConverter {
+    fn default() -> Self {
+        RgbConverter::new()
+    }
+}
+
+fn split_channels(color: u16) -> [u32; 3] {
+    [color, color >> GREEN_SHIFT, color >> BLUE_SHIFT].map(|channel| (channel & CHANNEL_MASK) as u32)
+}
+
+/// Spreads each 5-bit channel over 8 bits, so full brightness stays full.
+fn widen(color: u16) -> [u8; 3] {
+    split_channels(color).map(|channel| ((channel << 3) | (channel >> 2)) as u8)
+}
@@ -0,0 +1,5 @@
+mod oam_dma_component;
+mod vram_dma_component;
+
+pub use oam_dma_component::OamDmaComponent;
+pub use vram_dma_component::VramDmaComponent;
@@ -0,0 +1,120 @@
+use crate::memory_component::{MemoryComponent, MemoryError};
+
+const DMA_ADDRESS: u16 = 0xff46u16;
+
+// The CPU keeps its own bus to the I/O registers and HRAM
+const CPU_BUS_START_ADDRESS: u16 = 0xff00u16;
+
+const OAM_DMA_LENGTH: u8 = 0xa0u8;
+
+// Sources past 0xDF read work RAM through its echo
+const ECHO_RAM_SOURCE: u8 = 0xe0u8;
+const ECHO_RAM_OFFSET: u8 = 0x20u8;
+
+/// Object attribute memory DMA (0xFF46).
+///
+/// Writing XX starts copying 0xXX00-0xXX9F into OAM after one cycle of setup,
+/// one byte per machine cycle. The copying itself is done by the memory
+/// mapping, which can reach both ends; this only keeps track of where the
+/// transfer is. While a byte is being copied the CPU can only reach the I/O
+/// registers and HRAM, and reading anything else sees the byte on the bus.
+/// Writing again restarts the transfer, though the old one carries on through
+/// the new one's setup cycle.
+pub struct OamDmaComponent {
+    bus_value: Option<u8>,
+    position: Option<u8>,
+    register: u8,
+    source: u16,
+    starting: bool,
+}
+
+impl OamDmaComponent {
+    pub fn new() -> Self {
+        OamDmaComponent {
+            bus_value: None,
+            position: None,
+            register: 0xffu8,
+            source: 0x0000u16,
+            starting: false,
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        self.starting || self.position.is_some()
+    }
+
+    /// What the CPU sees when it reads `location` this cycle, if the transfer
+    /// is in its way.
+    pub fn conflict(&self, location: u16) -> Option<u8> {
+        if location >= CPU_BUS_START_ADDRESS {
+            return None;
+        }
+
+        self.bus_value
+    }
+
+    /// Advances the transfer by one machine cycle, returning the address to
+    /// copy from and the OAM offset to copy to, if a byte moves this cycle.
+    pub fn next_transfer(&mut self) -> Option<(u16, u8)> {
+        let transfer = self.position.map(|position| (self.source + position as u16, position));
+
+        self.position = match self.position {
+            Some(position) if position + 1 < OAM_DMA_LENGTH => Some(position + 1),
+            _ => None,
+        };
+
+        if self.starting {
+            let page = if self.register >= ECHO_RAM_SOURCE {
+                self.register - ECHO_RAM_OFFSET
+            } else {
+                self.register
+            };
+
+            self.source = (page as u16) << 8;
+            self.position = Some(0x00u8);
+            self.starting = false;
+        }
+
+        // Set again once the byte has been read
+        self.bus_value = None;
+
+        transfer
+    }
+
+    /// Records the byte copied this cycle, which is what the CPU sees if it
+    /// reads from the blocked bus.
+    pub fn set_bus_value(&mut self, value: u8) {
+        self.bus_value = Some(value);
+    }
+}
+
+impl Default for OamDmaComponent {
+    fn default() -> Self {
+        OamDmaComponent::new()
+    }
+}
+
+impl MemoryComponent for OamDmaComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![DMA_ADDRESS]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        if location != DMA_ADDRESS {
+            return Err(MemoryError::ReadError(location, "invalid state"));
+        }
+
+        Ok(self.register)
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        if location != DMA_ADDRESS {
+            return Err(MemoryError::WriteError(location, value, "invalid state"));
+        }
+
+        self.register = value;
+        self.starting = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use crate::memory_component::{MemoryComponent, MemoryError};
+
+const HDMA1_ADDRESS: u16 = 0xff51u16;
+const HDMA2_ADDRESS: u16 = 0xff52u16;
+const HDMA3_ADDRESS: u16 = 0xff53u16;
+const HDMA4_ADDRESS: u16 = 0xff54u16;
+const HDMA5_ADDRESS: u16 = 0xff55u16;
+
+const BLOCK_SIZE: u8 = 0x10u8;
+
+const ADDRESS_LOW_MASK: u8 = 0b11110000;
+const DESTINATION_HIGH_MASK: u8 = 0b00011111;
+const VRAM_OFFSET_MASK: u16 = 0x1fffu16;
+
+const LENGTH_MASK: u8 = 0b01111111;
+const HBLANK_MODE_BIT: u8 = 0b10000000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum VramDmaMode {
+    General,
+    HBlank,
+}
+
+/// CGB VRAM DMA (HDMA1-HDMA5, 0xFF51-0xFF55).
+///
+/// HDMA1-HDMA4 set the source and the VRAM destination, both aligned to 0x10
+/// bytes. Writing HDMA5 starts copying (N + 1) blocks of 0x10 bytes, where N
+/// is its low 7 bits, into the selected VRAM bank. With bit 7 clear this is a
+/// general-purpose transfer, copying every block in one go. With bit 7 set
+/// it's an H-Blank transfer, copying one block each time the PPU enters
+/// H-Blank, or straight away if it's already there. Writing HDMA5 with bit 7
+/// clear during an H-Blank transfer cancels it instead.
+///
+/// Blocks take the same real time at either speed, two bytes per machine
+/// cycle at normal speed and one at double speed, and the CPU waits while one
+/// is copied. As with OAM DMA, the memory mapping does the copying itself.
+///
+/// HDMA5 reads back the blocks left, minus one, with bit 7 clear while an
+/// H-Blank transfer is running, so it reads 0xFF once a transfer is done.
+/// Outside CGB mode, and for HDMA1-HDMA4, reads give 0xFF.
+pub struct VramDmaComponent {
+    block_position: Option<u8>,
+    cgb_mode: bool,
+    destination: u16,
+    double_speed: bool,
+    hblank: bool,
+    length: u8,
+    mode: Option<VramDmaMode>,
+    source: u16,
+}
+
+impl VramDmaComponent {
+    pub fn new() -> Self {
+        VramDmaComponent {
+            block_position: None,
+            cgb_mode: false,
+            destination: 0x0000u16,
+            double_speed: false,
+            hblank: false,
+            length: LENGTH_MASK,
+            mode: None,
+            source: 0x0000u16,
+        }
+    }
+
+    /// Whether a block is being copied, holding up the CPU.
+    pub fn active(&self) -> bool {
+        self.block_position.is_some()
+    }
+
+    pub fn bytes_per_cycle(&self) -> usize {
+        if self.double_speed {
+            1usize
+        } else {
+            2usize
+        }
+    }
+
+    fn finish_block(&mut self) {
+        self.block_position = None;
+        self.length = self.length.wrapping_sub(1) & LENGTH_MASK;
+
+        if self.length == LENGTH_MASK {
+            self.mode = None;
+        } else if self.mode == Some(VramDmaMode::General) {
+            self.block_position = Some(0x00u8);
+        }
+    }
+
+    /// Moves the transfer on by one byte, returning the address to copy from
+    /// and the VRAM offset to copy to, if a block is being copied.
+    pub fn next_transfer(&mut self) -> Option<(u16, u16)> {
+        let position = self.block_position?;
+
+        let transfer = (self.source, self.destination);
+
+        self.source = self.source.wrapping_add(1);
+        self.destination = (self.destination + 1) & VRAM_OFFSET_MASK;
+
+        if position + 1 < BLOCK_SIZE {
+            self.block_position = Some(position + 1);
+        } else {
+            self.finish_block();
+        }
+
+        Some(transfer)
+    }
+
+    pub fn set_cgb_mode(&mut self, cgb_mode: bool) {
+        self.cgb_mode = cgb_mode;
+    }
+
+    /// Tells the transfer whether the PPU is in H-Blank, starting the next
+    /// block of an H-Blank transfer when it enters it.
+    pub fn set_hblank(&mut self, hblank: bool) {
+        if hblank && !self.hblank {
+            self.start_hblank_block();
+        }
+
+        self.hblank = hblank;
+    }
+
+    fn start_hblank_block(&mut self) {
+        if self.mode == Some(VramDmaMode::HBlank) && self.block_position.is_none() {
+            self.block_position = Some(0x00u8);
+        }
+    }
+
+    fn write_hdma5(&mut self, value: u8) {
+        if self.mode == Some(VramDmaMode::HBlank) && value & HBLANK_MODE_BIT == 0 {
+            self.mode = None;
+
+            return;
+        }
+
+        self.length = value & LENGTH_MASK;
+
+        if value & HBLANK_MODE_BIT > 0 {
+            self.mode = Some(VramDmaMode::HBlank);
+
+            if self.hblank {
+                self.start_hblank_block();
+            }
+        } else {
+            self.mode = Some(VramDmaMode::General);
+            self.block_position = Some(0x00u8);
+        }
+    }
+}
+
+impl Default for VramDmaComponent {
+    fn default() -> Self {
+        VramDmaComponent::new()
+    }
+}
+
+impl MemoryComponent for VramDmaComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        (HDMA1_ADDRESS..=HDMA5_ADDRESS).collect()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            HDMA5_ADDRESS if self.cgb_mode => match self.mode {
+                Some(VramDmaMode::HBlank) => Ok(self.length),
+                _ => Ok(HBLANK_MODE_BIT | self.length),
+            },
+            // The rest are write-only
+            HDMA1_ADDRESS..=HDMA5_ADDRESS => Ok(0xffu8),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn set_double_speed(&mut self, double_speed: bool) {
+        self.double_speed = double_speed;
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            HDMA1_ADDRESS..=HDMA5_ADDRESS if !self.cgb_mode => {},
+            HDMA1_ADDRESS => self.source = (self.source & 0x00ff) | ((value as u16) << 8),
+            HDMA2_ADDRESS => self.source = (self.source & 0xff00) | (value & ADDRESS_LOW_MASK) as u16,
+            HDMA3_ADDRESS => {
+                self.destination = (self.destination & 0x00ff) | (((value & DESTINATION_HIGH_MASK) as u16) << 8)
+            },
+            HDMA4_ADDRESS => self.destination = (self.destination & 0xff00) | (value & ADDRESS_LOW_MASK) as u16,
+            HDMA5_ADDRESS => self.write_hdma5(value),
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -1,30 +1,81 @@
 use std::collections::HashMap;
 
-use crate::addresses::PROGRAM_COUNTER_START;
+use crate::addresses::{PROGRAM_COUNTER_START, STACK_POINTER_START};
+use crate::apu::SoundComponent;
 use crate::bits::{bit_add, bit_subtract, SignedInt, UnsignedInt};
+use crate::boot::{BootRomComponent, PostBootState};
+use crate::cartridge::Cartridge;
+use crate::emulator_builder::EmulatorBuilder;
+use crate::dma::{OamDmaComponent, VramDmaComponent};
 use crate::flag::Flag;
+use crate::interrupt::{Interrupt, InterruptComponent};
 use crate::instruction::{OpError, OpResult};
 use crate::instruction::{Instruction, Op};
-use crate::memory_component::MemoryError;
+use crate::joypad::{Button, JoypadComponent, P1_ADDRESS};
+use crate::memory_component::{MemoryError, SpeedSwitchComponent, WorkRamComponent};
 use crate::memory_mapping::MemoryMapping;
+use crate::model::Model;
 use crate::opcode::OpcodePattern;
+use crate::ppu::{PpuComponent, PpuMode};
+use crate::serial::{LinkCable, SerialTransferComponent};
+use crate::sgb::SgbComponent;
 use crate::register::{Register, RegisterPair};
 use crate::memory_component::MemoryComponent;
+use crate::rom::{CbgCompatibility, INITIAL_INSTRUCTION_ADDRESS};
+use crate::timer::TimerComponent;
 
+/// 70224 clocks per frame, counted in machine cycles.
+pub const CYCLES_PER_FRAME: usize = 17556usize;
+
+/// How long the CPU sits idle while switching speed.
+const SPEED_SWITCH_CYCLES: usize = 2050usize;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EmulationState {
     Halt,
     Run,
     Stop,
 }
 
+/// Where the components the emulator coordinates sit in the memory mapping,
+/// found when components are registered rather than on every cycle.
+#[derive(Default)]
+struct ComponentIndices {
+    interrupts: Option<usize>,
+    joypad: Option<usize>,
+    oam_dma: Option<usize>,
+    ppu: Option<usize>,
+    sgb: Option<usize>,
+    speed_switch: Option<usize>,
+    vram_dma: Option<usize>,
+}
+
+impl ComponentIndices {
+    fn find(memory_mapping: &MemoryMapping) -> Self {
+        ComponentIndices {
+            interrupts: memory_mapping.component_index::<InterruptComponent>(),
+            joypad: memory_mapping.component_index::<JoypadComponent>(),
+            oam_dma: memory_mapping.component_index::<OamDmaComponent>(),
+            ppu: memory_mapping.component_index::<PpuComponent>(),
+            sgb: memory_mapping.component_index::<SgbComponent>(),
+            speed_switch: memory_mapping.component_index::<SpeedSwitchComponent>(),
+            vram_dma: memory_mapping.component_index::<VramDmaComponent>(),
+        }
+    }
+}
+
 pub struct Emulator {
+    component_indices: ComponentIndices,
     cycles_processed: usize,
     flags: u8,
+    halt_bug: bool,
     instructions: Vec<Op>,
     instruction_map: HashMap<(bool, u8), usize>,
     interrupt_master_enable: bool,
+    interrupt_master_enable_delay: u8,
     jumped: bool,
     memory_mapping: MemoryMapping,
+    model: Model,
     name_map: HashMap<(bool, u8), String>,
     prefixed: bool,
     program_counter: u16,
@@ -34,15 +85,29 @@ pub struct Emulator {
 }
 
 impl Emulator {
+    pub fn builder() -> EmulatorBuilder {
+        EmulatorBuilder::new()
+    }
+
+    /// An emulator with no components or instructions. `Emulator::builder`
+    /// sets up a complete one.
     pub fn new() -> Self {
+        Emulator::with_model(Model::default())
+    }
+
+    pub fn with_model(model: Model) -> Self {
         Emulator {
+            component_indices: ComponentIndices::default(),
             cycles_processed: 0usize,
             flags: 0x00u8,
+            halt_bug: false,
             interrupt_master_enable: false,
+            interrupt_master_enable_delay: 0u8,
             instructions: Vec::new(),
             instruction_map: HashMap::new(),
             jumped: false,
             memory_mapping: MemoryMapping::new(),
+            model,
             name_map: HashMap::new(),
             prefixed: false,
             program_counter: PROGRAM_COUNTER_START,
@@ -82,6 +147,8 @@ impl Emulator {
 
     pub fn add_memory_component(&mut self, memory_component: Box<dyn MemoryComponent>) {
         self.memory_mapping.register_component(memory_component);
+
+        self.component_indices = ComponentIndices::find(&self.memory_mapping);
     }
 
     pub fn add_signed<S: SignedInt, U: TryFrom<S> + UnsignedInt>(&mut self, a: U, b: S, with_carry: bool) -> U {
@@ -153,10 +220,51 @@ impl Emulator {
         self.set_a(value);
     }
 
+    fn boot_rom_enabled(&self) -> bool {
+        self.memory_component::<BootRomComponent>().is_some_and(|boot_rom| boot_rom.enabled())
+    }
+
+    pub fn cartridge(&self) -> Option<&Cartridge> {
+        self.memory_component::<Cartridge>()
+    }
+
+    pub fn cartridge_mut(&mut self) -> Option<&mut Cartridge> {
+        self.memory_component_mut::<Cartridge>()
+    }
+
+    /// Whether the PPU and work RAM are running as a CGB, rather than a CGB
+    /// running a DMG game.
+    pub fn cgb_mode(&self) -> bool {
+        self.memory_component::<PpuComponent>().is_some_and(|ppu| ppu.cgb_mode())
+    }
+
+    /// Plugs a link cable into the serial port.
+    pub fn connect_link_cable(&mut self, link_cable: Box<dyn LinkCable>) {
+        if let Some(serial) = self.memory_component_mut::<SerialTransferComponent>() {
+            serial.connect(link_cable);
+        }
+    }
+
+    /// The last frame drawn by the PPU as RGB555 colors, only available in
+    /// CGB mode.
+    pub fn color_framebuffer(&self) -> Option<&[u16]> {
+        if !self.cgb_mode() {
+            return None;
+        }
+
+        self.memory_component::<PpuComponent>().map(|ppu| ppu.color_framebuffer())
+    }
+
     pub fn cycles(&self) -> usize {
         self.cycles_processed
     }
 
+    /// Whether a CGB is running at double speed.
+    pub fn double_speed(&self) -> bool {
+        self.indexed_component::<SpeedSwitchComponent>(self.component_indices.speed_switch)
+            .is_some_and(|key1| key1.double_speed())
+    }
+
     pub fn flag(&self, flag: Flag) -> bool {
         self.flags & (flag as u8) > 0
     }
@@ -180,10 +288,26 @@ impl Emulator {
         self.jumped = true;
     }
 
+    /// The last frame drawn by the PPU, as DMG shades 0-3, or raw color
+    /// numbers in CGB mode.
+    pub fn framebuffer(&self) -> Option<&[u8]> {
+        self.memory_component::<PpuComponent>().map(|ppu| ppu.framebuffer())
+    }
+
     pub fn hl(&self) -> u16 {
         self.register_pair(&RegisterPair::Hl)
     }
 
+    /// Looks up a component by the index cached for it in
+    /// `component_indices`, without searching the memory mapping.
+    fn indexed_component<T: MemoryComponent>(&self, index: Option<usize>) -> Option<&T> {
+        self.memory_mapping.component_at::<T>(index?)
+    }
+
+    fn indexed_component_mut<T: MemoryComponent>(&mut self, index: Option<usize>) -> Option<&mut T> {
+        self.memory_mapping.component_at_mut::<T>(index?)
+    }
+
     pub fn instruction_name(&self, op: (bool, u8)) -> Option<&String> {
         self.name_map.get(&op)
     }
@@ -192,10 +316,86 @@ impl Emulator {
         self.interrupt_master_enable
     }
 
+    /// The cable plugged into the serial port, if any.
+    pub fn link_cable(&self) -> Option<&dyn LinkCable> {
+        self.memory_component::<SerialTransferComponent>().and_then(|serial| serial.link_cable())
+    }
+
+    /// Maps a boot ROM over the start of the cartridge and points the CPU at
+    /// it. The boot ROM unmaps itself when it's done.
+    pub fn load_boot_rom(&mut self, boot_rom: BootRomComponent) {
+        self.add_memory_component(Box::new(boot_rom));
+
+        self.program_counter = PROGRAM_COUNTER_START;
+    }
+
+    /// Maps the cartridge into memory and, unless a boot ROM is going to run
+    /// first, points the CPU at its entry point, as the boot ROM would have
+    /// done on hand-off.
+    ///
+    /// CGB models run the cartridge in CGB mode unless its header says it
+    /// only supports the DMG, and the SGB only takes commands from cartridges
+    /// whose header asks for its functions.
+    pub fn load_cartridge(&mut self, cartridge: Cartridge) {
+        let cgb_mode = self.model.is_cgb()
+            && cartridge.header().cgb_compatibility() != CbgCompatibility::CGBIncompatible;
+        let sgb_support = cartridge.header().sgb_support();
+
+        self.add_memory_component(Box::new(cartridge));
+        self.set_cgb_mode(cgb_mode);
+
+        if let Some(sgb) = self.memory_component_mut::<SgbComponent>() {
+            sgb.set_enabled(sgb_support);
+        }
+
+        if !self.boot_rom_enabled() {
+            self.program_counter = INITIAL_INSTRUCTION_ADDRESS;
+            self.stack_pointer = STACK_POINTER_START;
+        }
+    }
+
+    /// Looks up a registered component by type, e.g. to reach host-facing
+    /// state that isn't visible through the address space.
+    pub fn memory_component<T: MemoryComponent>(&self) -> Option<&T> {
+        self.memory_mapping.component::<T>()
+    }
+
+    pub fn memory_component_mut<T: MemoryComponent>(&mut self) -> Option<&mut T> {
+        self.memory_mapping.component_mut::<T>()
+    }
+
     pub fn memory_location(&self, location: u16) -> u8 {
         self.memory_mapping.read(location).unwrap()
     }
 
+    /// The hardware being emulated, which decides the components it was
+    /// built with and the state skipping the boot ROM leaves behind.
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// What the CPU sees at `location` if OAM DMA has the bus this cycle.
+    fn oam_dma_conflict(&self, location: u16) -> Option<u8> {
+        self.indexed_component::<OamDmaComponent>(self.component_indices.oam_dma)?.conflict(location)
+    }
+
+    fn joypad_input_low(&self) -> bool {
+        self.indexed_component::<JoypadComponent>(self.component_indices.joypad)
+            .is_some_and(|joypad| joypad.input_low())
+    }
+
+    /// Interrupts that are both requested and enabled, regardless of IME.
+    pub fn pending_interrupts(&self) -> u8 {
+        self.indexed_component::<InterruptComponent>(self.component_indices.interrupts)
+            .map_or(0x00u8, |interrupts| interrupts.pending())
+    }
+
+    pub fn press_button(&mut self, button: Button) {
+        if let Some(joypad) = self.indexed_component_mut::<JoypadComponent>(self.component_indices.joypad) {
+            joypad.press(button);
+        }
+    }
+
     pub fn prefixed(&self) -> bool {
         self.prefixed
     }
@@ -204,10 +404,48 @@ impl Emulator {
         self.cycles_processed = 0;
     }
 
+    /// Runs one step of the CPU: either services a pending interrupt, or
+    /// fetches and executes one opcode. While halted or stopped, this idles
+    /// for a single cycle instead.
     pub fn process_opcode(&mut self) -> OpResult {
+        match self.state {
+            EmulationState::Halt => {
+                // Peripherals keep running until one of them wakes the CPU
+                if self.pending_interrupts() == 0 {
+                    self.tick();
+
+                    return Ok(());
+                }
+
+                self.state = EmulationState::Run;
+            },
+            EmulationState::Stop => {
+                // The whole system clock is stopped until a selected button
+                // is pressed
+                if !self.joypad_input_low() {
+                    self.cycles_processed += 1;
+
+                    return Ok(());
+                }
+
+                self.state = EmulationState::Run;
+            },
+            EmulationState::Run => {},
+        };
+
+        // A prefixed opcode can't be split by an interrupt
+        if !self.prefixed && self.service_interrupt()? {
+            return Ok(());
+        }
+
         let opcode = self.read(self.program_counter)?;
 
-        self.program_counter = self.program_counter.wrapping_add(1);
+        // The HALT bug fetches the byte after HALT twice
+        if self.halt_bug {
+            self.halt_bug = false;
+        } else {
+            self.program_counter = self.program_counter.wrapping_add(1);
+        }
 
         let op_index = if self.prefixed {
             self.prefixed = false;
@@ -221,16 +459,52 @@ impl Emulator {
         
         op(self, opcode)?;
 
+        // EI takes effect after the instruction following it
+        if !self.prefixed && self.interrupt_master_enable_delay > 0 {
+            self.interrupt_master_enable_delay -= 1;
+
+            if self.interrupt_master_enable_delay == 0 {
+                self.interrupt_master_enable = true;
+            }
+        }
+
         Ok(())
     }
 
+    /// Runs instructions until the PPU finishes its next frame, or for a
+    /// frame's worth of cycles while the LCD is off or the CPU is stopped.
+    /// At double speed a frame takes twice as many cycles.
+    pub fn run_frame(&mut self) -> OpResult {
+        let frames = self.indexed_component::<PpuComponent>(self.component_indices.ppu).map(|ppu| ppu.frames());
+        let speed = if self.double_speed() { 2usize } else { 1usize };
+        let target = self.cycles_processed + CYCLES_PER_FRAME * speed;
+
+        loop {
+            self.process_opcode()?;
+
+            // Nothing draws while the CPU is stopped
+            let frame_done = match self.indexed_component::<PpuComponent>(self.component_indices.ppu) {
+                Some(ppu) if ppu.lcd_enabled() && self.state != EmulationState::Stop => Some(ppu.frames()) != frames,
+                _ => self.cycles_processed >= target,
+            };
+
+            if frame_done {
+                return Ok(());
+            }
+        }
+    }
+
     pub fn program_counter(&self) -> u16 {
         self.program_counter
     }
 
     pub fn read(&mut self, location: u16) -> Result<u8, MemoryError> {
         // Process cycle
-        self.cycles_processed += 1;
+        self.tick();
+
+        if let Some(value) = self.oam_dma_conflict(location) {
+            return Ok(value);
+        }
 
         self.memory_mapping.read(location)
     }
@@ -285,10 +559,93 @@ impl Emulator {
         u16::from_le_bytes([low, high])
     }
 
+    pub fn release_button(&mut self, button: Button) {
+        if let Some(joypad) = self.indexed_component_mut::<JoypadComponent>(self.component_indices.joypad) {
+            joypad.release(button);
+        }
+    }
+
+    /// Raises an interrupt request, as a peripheral would.
+    pub fn request_interrupt(&mut self, interrupt: Interrupt) {
+        if let Some(interrupts) = self.indexed_component_mut::<InterruptComponent>(self.component_indices.interrupts) {
+            interrupts.request(interrupt as u8);
+        }
+    }
+
+    /// Sets IME once the next instruction has finished, as `EI` does.
+    pub fn schedule_interrupt_master_enable(&mut self) {
+        if !self.interrupt_master_enable {
+            self.interrupt_master_enable_delay = 2;
+        }
+    }
+
+    /// Jumps to the highest priority pending interrupt, if IME allows it.
+    ///
+    /// Takes 5 machine cycles: two idle, two to push PC and one to jump.
+    fn service_interrupt(&mut self) -> Result<bool, MemoryError> {
+        if !self.interrupt_master_enable {
+            return Ok(false);
+        }
+
+        let pending = match self.indexed_component::<InterruptComponent>(self.component_indices.interrupts) {
+            Some(interrupts) => interrupts.pending(),
+            None => return Ok(false),
+        };
+
+        let interrupt = match Interrupt::highest_priority(pending) {
+            Some(interrupt) => interrupt,
+            None => return Ok(false),
+        };
+
+        self.interrupt_master_enable = false;
+
+        self.tick();
+        self.tick();
+
+        let [low_value, high_value] = self.program_counter.to_le_bytes();
+
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+        self.write(self.stack_pointer, high_value)?;
+
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+        self.write(self.stack_pointer, low_value)?;
+
+        // The request is only cleared once the push is done
+        if let Some(interrupts) = self.indexed_component_mut::<InterruptComponent>(self.component_indices.interrupts) {
+            interrupts.acknowledge(interrupt);
+        }
+
+        self.set_program_counter(interrupt.vector());
+
+        Ok(true)
+    }
+
     pub fn set_a(&mut self, value: u8) {
         self.set_register(Register::A, value);
     }
 
+    /// Sets the rate the APU generates samples at, which a frontend can nudge
+    /// to keep its audio buffer level.
+    pub fn set_audio_sample_rate(&mut self, sample_rate: u32) {
+        if let Some(sound) = self.memory_component_mut::<SoundComponent>() {
+            sound.set_sample_rate(sample_rate);
+        }
+    }
+
+    fn set_cgb_mode(&mut self, cgb_mode: bool) {
+        if let Some(ppu) = self.memory_component_mut::<PpuComponent>() {
+            ppu.set_cgb_mode(cgb_mode);
+        }
+
+        if let Some(work_ram) = self.memory_component_mut::<WorkRamComponent>() {
+            work_ram.set_cgb_mode(cgb_mode);
+        }
+
+        if let Some(vram_dma) = self.memory_component_mut::<VramDmaComponent>() {
+            vram_dma.set_cgb_mode(cgb_mode);
+        }
+    }
+
     pub fn set_flag(&mut self, flag: Flag, value: bool) {
         self.flags = if value {
             self.flags | (flag as u8)
@@ -297,22 +654,30 @@ impl Emulator {
         };
     }
 
+    /// Makes the next fetch read the same byte again, as happens when HALT
+    /// is executed with IME clear and an interrupt already pending.
+    pub fn set_halt_bug(&mut self) {
+        self.halt_bug = true;
+    }
+
     pub fn set_hl(&mut self, value: u16) {
         self.set_register_pair(RegisterPair::Hl, value);
     }
 
+    /// Sets IME straight away, cancelling any pending `EI`.
     pub fn set_interrupt_master_enable(&mut self, value: bool) {
         self.interrupt_master_enable = value;
+        self.interrupt_master_enable_delay = 0;
     }
 
     pub fn set_prefix(&mut self, value: bool) {
         self.prefixed = value;
     }
-    
+
     pub fn set_program_counter(&mut self, value: u16) {
         self.program_counter = value;
 
-        self.cycles_processed += 1;
+        self.tick();
     }
 
     pub fn set_register(&mut self, register: Register, value: u8) {
@@ -341,10 +706,164 @@ impl Emulator {
         self.state = value;
     }
 
+    /// The last frame as the SGB shows it on the TV, inside its border, as
+    /// SGB_SCREEN_WIDTH x SGB_SCREEN_HEIGHT RGB555 colors. Only available
+    /// on the SGB.
+    pub fn sgb_framebuffer(&self) -> Option<Vec<u16>> {
+        self.memory_component::<SgbComponent>().map(|sgb| sgb.framebuffer())
+    }
+
+    /// Whether `STOP` would switch speed rather than stop the CPU.
+    pub fn speed_switch_armed(&self) -> bool {
+        self.indexed_component::<SpeedSwitchComponent>(self.component_indices.speed_switch)
+            .is_some_and(|key1| key1.armed())
+    }
+
+    /// Starts at the cartridge's entry point in the state the model's boot
+    /// ROM leaves behind, without running it.
+    pub fn skip_boot_rom(&mut self) {
+        let header_checksum = self.cartridge().map_or(0x00u8, |cartridge| cartridge.header().header_checksum());
+
+        let state = PostBootState::new(self.model, header_checksum);
+
+        self.set_register(Register::A, state.a);
+        self.set_register(Register::B, state.b);
+        self.set_register(Register::C, state.c);
+        self.set_register(Register::D, state.d);
+        self.set_register(Register::E, state.e);
+        self.set_register(Register::H, state.h);
+        self.set_register(Register::L, state.l);
+
+        self.flags = state.f;
+        self.program_counter = INITIAL_INSTRUCTION_ADDRESS;
+        self.stack_pointer = STACK_POINTER_START;
+
+        // Components that aren't there have nothing to set up
+        for (location, value) in state.io {
+            let _ = self.memory_mapping.write(location, value);
+        }
+
+        if let (Some(divider), Some(timer)) = (state.divider, self.memory_component_mut::<TimerComponent>()) {
+            timer.set_divider(divider);
+        }
+    }
+
+    pub fn state(&self) -> EmulationState {
+        self.state
+    }
+
+    /// Toggles between normal and double speed, idling while the clock
+    /// settles. Only the CPU and the components it clocks, like the timer,
+    /// speed up.
+    pub fn switch_speed(&mut self) {
+        if let Some(key1) = self.indexed_component_mut::<SpeedSwitchComponent>(self.component_indices.speed_switch) {
+            key1.switch();
+        }
+
+        let double_speed = self.double_speed();
+
+        self.memory_mapping.set_double_speed(double_speed);
+
+        for _ in 0..SPEED_SWITCH_CYCLES {
+            self.tick();
+        }
+    }
+
     pub fn stack_pointer(&self) -> u16 {
         self.stack_pointer
     }
 
+    /// Steps every component, forwards their interrupt requests to the
+    /// interrupt controller, moves any DMA transfers along, and hands
+    /// finished frames to the SGB.
+    fn step_components(&mut self) {
+        let requests = self.memory_mapping.step();
+        let index = self.component_indices.interrupts;
+
+        if requests > 0 {
+            if let Some(interrupts) = self.indexed_component_mut::<InterruptComponent>(index) {
+                interrupts.request(requests);
+            }
+        }
+
+        self.step_oam_dma();
+        self.step_vram_dma();
+
+        if self.component_indices.sgb.is_some() {
+            self.step_sgb();
+        }
+    }
+
+    /// Copies the next byte of an OAM DMA transfer into OAM.
+    fn step_oam_dma(&mut self) {
+        let index = self.component_indices.oam_dma;
+
+        let transfer = self.indexed_component_mut::<OamDmaComponent>(index).and_then(|dma| dma.next_transfer());
+
+        let Some((source, offset)) = transfer else {
+            return;
+        };
+
+        let value = self.memory_mapping.read(source).unwrap_or(0xffu8);
+
+        if let Some(dma) = self.indexed_component_mut::<OamDmaComponent>(index) {
+            dma.set_bus_value(value);
+        }
+
+        if let Some(ppu) = self.indexed_component_mut::<PpuComponent>(self.component_indices.ppu) {
+            ppu.write_oam(offset, value);
+        }
+    }
+
+    /// Hands a newly finished frame to the SGB.
+    fn step_sgb(&mut self) {
+        let Some(ppu) = self.indexed_component::<PpuComponent>(self.component_indices.ppu) else {
+            return;
+        };
+
+        let frames = ppu.frames();
+
+        match self.indexed_component::<SgbComponent>(self.component_indices.sgb) {
+            Some(sgb) if sgb.frames() != frames => {},
+            _ => return,
+        }
+
+        let framebuffer = ppu.framebuffer().to_vec();
+
+        if let Some(sgb) = self.indexed_component_mut::<SgbComponent>(self.component_indices.sgb) {
+            sgb.receive_frame(frames, &framebuffer);
+        }
+    }
+
+    /// Copies the next bytes of a VRAM DMA transfer into VRAM.
+    fn step_vram_dma(&mut self) {
+        // The PPU sits in H-Blank while the LCD is off too
+        let ppu = self.component_indices.ppu;
+        let hblank = self.indexed_component::<PpuComponent>(ppu).is_some_and(|ppu| ppu.mode() == PpuMode::HBlank);
+
+        let index = self.component_indices.vram_dma;
+
+        let Some(dma) = self.indexed_component_mut::<VramDmaComponent>(index) else {
+            return;
+        };
+
+        dma.set_hblank(hblank);
+
+        for _ in 0..dma.bytes_per_cycle() {
+            let transfer = self.indexed_component_mut::<VramDmaComponent>(index).and_then(|dma| dma.next_transfer());
+
+            let Some((source, destination)) = transfer else {
+                return;
+            };
+
+            let value = self.memory_mapping.read(source).unwrap_or(0xffu8);
+
+            if let Some(ppu) = self.indexed_component_mut::<PpuComponent>(ppu) {
+                ppu.write_vram(destination, value);
+            }
+        }
+    }
+
     pub fn subtract_from_a(&mut self, value: u8, with_carry: bool) {
         let value = self.subtract_unsigned(self.register(&Register::A), value, with_carry);
 
@@ -367,11 +886,46 @@ impl Emulator {
         dif
     }
 
+    /// The audio generated since the last call, as interleaved left and right
+    /// samples.
+    pub fn take_audio_samples(&mut self) -> Vec<f32> {
+        self.memory_component_mut::<SoundComponent>().map(|sound| sound.take_samples()).unwrap_or_default()
+    }
+
+    /// Spends one machine cycle, letting the other components catch up with
+    /// the CPU. While VRAM DMA is copying a block the CPU waits for it first,
+    /// with the cycles counted all the same.
+    pub fn tick(&mut self) {
+        while self.vram_dma_active() {
+            self.cycles_processed += 1;
+
+            self.step_components();
+        }
+
+        self.cycles_processed += 1;
+
+        self.step_components();
+    }
+
+    fn vram_dma_active(&self) -> bool {
+        self.indexed_component::<VramDmaComponent>(self.component_indices.vram_dma).is_some_and(|dma| dma.active())
+    }
+
     pub fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
         // Process cycle
-        self.cycles_processed += 1;
+        self.tick();
 
-        self.memory_mapping.write(location, value)
+        if self.oam_dma_conflict(location).is_some() {
+            return Ok(());
+        }
+
+        self.memory_mapping.write(location, value)?;
+
+        if location == P1_ADDRESS && self.component_indices.sgb.is_some() {
+            self.write_sgb_joypad(value);
+        }
+
+        Ok(())
     }
 
     pub fn write_hl_location(&mut self, value: u8) -> Result<(), MemoryError> {
@@ -379,4 +933,20 @@ impl Emulator {
 
         self.write(location, value)
     }
+
+    /// Lets the SGB see the select lines, which carry its command packets,
+    /// and tells the joypad which player it's reading in multiplayer mode.
+    fn write_sgb_joypad(&mut self, value: u8) {
+        let Some(sgb) = self.indexed_component_mut::<SgbComponent>(self.component_indices.sgb) else {
+            return;
+        };
+
+        sgb.write_joypad(value);
+
+        let player = sgb.player();
+
+        if let Some(joypad) = self.indexed_component_mut::<JoypadComponent>(self.component_indices.joypad) {
+            joypad.set_sgb_player(player);
+        }
+    }
 }
\ No newline at end of file
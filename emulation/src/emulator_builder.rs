@@ -0,0 +1,71 @@
+use crate::{
+    add_instructions,
+    apu::SoundComponent,
+    dma::{OamDmaComponent, VramDmaComponent},
+    emulator::Emulator,
+    interrupt::InterruptComponent,
+    joypad::JoypadComponent,
+    memory_component::{SpeedSwitchComponent, StackComponent, UnusableRamComponent, UnusedIoComponent, WorkRamComponent},
+    model::Model,
+    ppu::PpuComponent,
+    serial::SerialTransferComponent,
+    sgb::SgbComponent,
+    timer::TimerComponent,
+};
+
+/// Builds an emulator with the components and instructions of the chosen
+/// model.
+pub struct EmulatorBuilder {
+    model: Model,
+}
+
+impl EmulatorBuilder {
+    pub fn new() -> Self {
+        EmulatorBuilder {
+            model: Model::default(),
+        }
+    }
+
+    pub fn build(&self) -> Emulator {
+        let mut emulator = Emulator::with_model(self.model);
+
+        // Add components, starting with the open bus the I/O registers sit on
+        emulator.add_memory_component(Box::new(UnusedIoComponent::new()));
+        emulator.add_memory_component(Box::new(InterruptComponent::new()));
+        emulator.add_memory_component(Box::new(JoypadComponent::new()));
+        emulator.add_memory_component(Box::new(OamDmaComponent::new()));
+        emulator.add_memory_component(Box::new(PpuComponent::new()));
+        emulator.add_memory_component(Box::new(SerialTransferComponent::new()));
+        emulator.add_memory_component(Box::new(SoundComponent::with_model(self.model)));
+        emulator.add_memory_component(Box::new(StackComponent::new()));
+        emulator.add_memory_component(Box::new(TimerComponent::new()));
+        emulator.add_memory_component(Box::new(UnusableRamComponent::new()));
+        emulator.add_memory_component(Box::new(WorkRamComponent::new()));
+
+        if self.model.is_cgb() {
+            emulator.add_memory_component(Box::new(SpeedSwitchComponent::new()));
+            emulator.add_memory_component(Box::new(VramDmaComponent::new()));
+        }
+
+        if self.model == Model::Sgb {
+            emulator.add_memory_component(Box::new(SgbComponent::new()));
+        }
+
+        // Add instructions
+        add_instructions(&mut emulator);
+
+        emulator
+    }
+
+    pub fn model(&mut self, model: Model) -> &mut Self {
+        self.model = model;
+
+        self
+    }
+}
+
+impl Default for EmulatorBuilder {
+    fn default() -> Self {
+        EmulatorBuilder::new()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     emulator::Emulator,
-    instruction::{general_instructions::ei, Instruction, OpResult},
+    instruction::{Instruction, OpResult},
     opcode::Opcode,
 };
 
@@ -128,7 +128,9 @@ pub const RET_IF_CONDITION: Instruction = Instruction {
 /// Enables ime, and oads into PC memory specified by sp, and increments sp by
 /// two.
 pub fn reti(emulator: &mut Emulator, opcode: u8) -> OpResult {
-    ei(emulator, opcode)?;
+    // Unlike EI, this takes effect immediately
+    emulator.set_interrupt_master_enable(true);
+
     ret(emulator, opcode)?;
 
     Ok(())
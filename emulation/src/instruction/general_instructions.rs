@@ -2,6 +2,7 @@ use crate::{
     emulator::{EmulationState, Emulator},
     flag::Flag,
     instruction::{Instruction, OpResult},
+    timer::TimerComponent,
 };
 
 /// CPL A
@@ -91,9 +92,9 @@ pub const DI: Instruction = Instruction {
 /// 
 /// IME <- 1
 /// 
-/// Sets the ime to 1.
+/// Sets the ime to 1, once the instruction after this one has finished.
 pub fn ei(emulator: &mut Emulator, _: u8) -> OpResult {
-    emulator.set_interrupt_master_enable(true);
+    emulator.schedule_interrupt_master_enable();
 
     Ok(())
 }
@@ -127,9 +128,15 @@ pub const FLIP_CARRY: Instruction = Instruction {
 /// 
 /// Halt
 /// 
-/// Sets the emulator to HALT mode.
+/// Sets the emulator to HALT mode, until an enabled interrupt is requested.
+/// With IME clear and an interrupt already pending, the CPU carries on but
+/// reads the next byte twice instead.
 pub fn halt(emulator: &mut Emulator, _: u8) -> OpResult {
-    emulator.set_state(EmulationState::Halt);
+    if !emulator.interrupt_master_enable() && emulator.pending_interrupts() > 0 {
+        emulator.set_halt_bug();
+    } else {
+        emulator.set_state(EmulationState::Halt);
+    }
 
     Ok(())
 }
@@ -192,9 +199,22 @@ pub const SET_CARRY: Instruction = Instruction {
 /// 
 /// Stop
 /// 
-/// Sets the emulator to STOP mode.
+/// Sets the emulator to STOP mode, until a button is pressed. On CGB with a
+/// speed switch armed through KEY1, switches speed instead.
 pub fn stop(emulator: &mut Emulator, _: u8) -> OpResult {
-    emulator.set_state(EmulationState::Stop);
+    // The second byte of STOP is ignored
+    emulator.read_immediate_n()?;
+
+    // Both a speed switch and stopping the clock reset DIV
+    if let Some(timer) = emulator.memory_component_mut::<TimerComponent>() {
+        timer.reset_divider();
+    }
+
+    if emulator.speed_switch_armed() {
+        emulator.switch_speed();
+    } else {
+        emulator.set_state(EmulationState::Stop);
+    }
 
     Ok(())
 }
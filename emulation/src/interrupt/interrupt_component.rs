@@ -0,0 +1,81 @@
+use crate::{
+    interrupt::Interrupt,
+    memory_component::{MemoryComponent, MemoryError},
+};
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+const INTERRUPT_ENABLE_ADDRESS: u16 = 0xffffu16;
+
+// Only five interrupt sources exist; the rest of IF reads back as 1
+const INTERRUPT_MASK: u8 = 0b00011111;
+const INTERRUPT_FLAG_UNUSED_BITS: u8 = 0b11100000;
+
+/// The interrupt request (IF, 0xFF0F) and enable (IE, 0xFFFF) registers.
+///
+/// Other components raise requests by returning them from `step`, which the
+/// memory mapping forwards here.
+pub struct InterruptComponent {
+    enabled: u8,
+    requested: u8,
+}
+
+impl InterruptComponent {
+    pub fn new() -> Self {
+        InterruptComponent {
+            enabled: 0x00u8,
+            requested: 0x00u8,
+        }
+    }
+
+    /// Clears a request, as the CPU does when it services it.
+    pub fn acknowledge(&mut self, interrupt: Interrupt) {
+        self.requested &= !(interrupt as u8);
+    }
+
+    pub fn enabled(&self) -> u8 {
+        self.enabled
+    }
+
+    /// The requests that are also enabled, regardless of IME.
+    pub fn pending(&self) -> u8 {
+        self.requested & self.enabled & INTERRUPT_MASK
+    }
+
+    pub fn request(&mut self, mask: u8) {
+        self.requested |= mask & INTERRUPT_MASK;
+    }
+
+    pub fn requested(&self) -> u8 {
+        self.requested
+    }
+}
+
+impl Default for InterruptComponent {
+    fn default() -> Self {
+        InterruptComponent::new()
+    }
+}
+
+impl MemoryComponent for InterruptComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![INTERRUPT_FLAG_ADDRESS, INTERRUPT_ENABLE_ADDRESS]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            INTERRUPT_FLAG_ADDRESS => Ok(INTERRUPT_FLAG_UNUSED_BITS | self.requested),
+            INTERRUPT_ENABLE_ADDRESS => Ok(self.enabled),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            INTERRUPT_FLAG_ADDRESS => self.requested = value & INTERRUPT_MASK,
+            INTERRUPT_ENABLE_ADDRESS => self.enabled = value,
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::rom::{
+    LCDC_INTERRUPT_START_ADDRESS,
+    SERIAL_TRANSFER_COMPLETION_INTERRUPT_START_ADDRESS,
+    TERMINAL_NEGATIVE_EDGE_INTERRUPT_START_ADDRESS,
+    TIMER_OVERFLOW_INTERRUPT_START_ADDRESS,
+    VERTICAL_BANKING_INTERRUPT_START_ADDRESS,
+};
+
+/// The interrupt sources, as their bits in IF and IE.
+///
+/// Lower bits have higher priority when several are pending at once.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Interrupt {
+    VBlank = 0b00000001,
+    LcdStat = 0b00000010,
+    Timer = 0b00000100,
+    Serial = 0b00001000,
+    Joypad = 0b00010000,
+}
+
+impl Interrupt {
+    /// Every interrupt, highest priority first.
+    pub const ALL: [Interrupt; 5] = [
+        Interrupt::VBlank,
+        Interrupt::LcdStat,
+        Interrupt::Timer,
+        Interrupt::Serial,
+        Interrupt::Joypad,
+    ];
+
+    /// The highest priority interrupt set in a mask, if any.
+    pub fn highest_priority(mask: u8) -> Option<Interrupt> {
+        Interrupt::ALL.iter().copied().find(|i| mask & (*i as u8) > 0)
+    }
+
+    pub fn vector(&self) -> u16 {
+        match self {
+            Interrupt::VBlank => VERTICAL_BANKING_INTERRUPT_START_ADDRESS,
+            Interrupt::LcdStat => LCDC_INTERRUPT_START_ADDRESS,
+            Interrupt::Timer => TIMER_OVERFLOW_INTERRUPT_START_ADDRESS,
+            Interrupt::Serial => SERIAL_TRANSFER_COMPLETION_INTERRUPT_START_ADDRESS,
+            Interrupt::Joypad => TERMINAL_NEGATIVE_EDGE_INTERRUPT_START_ADDRESS,
+        }
+    }
+}
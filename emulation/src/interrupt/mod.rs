@@ -0,0 +1,5 @@
+mod interrupt_component;
+mod interrupt_source;
+
+pub use interrupt_component::InterruptComponent;
+pub use interrupt_source::Interrupt;
@@ -0,0 +1,29 @@
+/// The eight buttons, as bits of the joypad's pressed state.
+///
+/// The low nibble holds the directions and the high nibble the actions, each
+/// in the order they appear on the P1 input lines.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u8)]
+pub enum Button {
+    Right = 0b00000001,
+    Left = 0b00000010,
+    Up = 0b00000100,
+    Down = 0b00001000,
+    A = 0b00010000,
+    B = 0b00100000,
+    Select = 0b01000000,
+    Start = 0b10000000,
+}
+
+impl Button {
+    pub const ALL: [Button; 8] = [
+        Button::Right,
+        Button::Left,
+        Button::Up,
+        Button::Down,
+        Button::A,
+        Button::B,
+        Button::Select,
+        Button::Start,
+    ];
+}
@@ -0,0 +1,153 @@
+use crate::{
+    interrupt::Interrupt,
+    joypad::Button,
+    memory_component::{MemoryComponent, MemoryError},
+};
+
+pub const P1_ADDRESS: u16 = 0xff00u16;
+
+// Select lines are active low
+const DIRECTION_SELECT_BIT: u8 = 0b00010000;
+const ACTION_SELECT_BIT: u8 = 0b00100000;
+const SELECT_MASK: u8 = DIRECTION_SELECT_BIT | ACTION_SELECT_BIT;
+
+const INPUT_MASK: u8 = 0b00001111;
+const UNUSED_BITS: u8 = 0b11000000;
+
+/// The joypad register (P1, 0xFF00).
+///
+/// The buttons form a 2x4 matrix: writing 0 to bit 4 selects the directions
+/// and to bit 5 the actions, and the low nibble then reads 0 for each pressed
+/// button on a selected row. Any input line falling from 1 to 0, whether from
+/// a press or a change of selection, requests the joypad interrupt.
+///
+/// Once an SGB is in multiplayer mode, the low nibble reads the current
+/// joypad's ID, 0xF for the first and counting down, while neither row is
+/// selected. Only the first joypad has any buttons pressed.
+pub struct JoypadComponent {
+    input_lines: u8,
+    interrupt_requested: bool,
+    pressed: u8,
+    select: u8,
+    sgb_player: Option<u8>,
+}
+
+impl JoypadComponent {
+    pub fn new() -> Self {
+        JoypadComponent {
+            input_lines: INPUT_MASK,
+            interrupt_requested: false,
+            pressed: 0x00u8,
+            select: SELECT_MASK,
+            sgb_player: None,
+        }
+    }
+
+    /// Whether any input line is held low, which also ends STOP mode.
+    pub fn input_low(&self) -> bool {
+        self.input_lines != INPUT_MASK
+    }
+
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.pressed & (button as u8) > 0
+    }
+
+    pub fn press(&mut self, button: Button) {
+        self.set_pressed(button, true);
+    }
+
+    pub fn release(&mut self, button: Button) {
+        self.set_pressed(button, false);
+    }
+
+    pub fn set_pressed(&mut self, button: Button, pressed: bool) {
+        if pressed {
+            self.pressed |= button as u8;
+        } else {
+            self.pressed &= !(button as u8);
+        }
+
+        self.update_input_lines();
+    }
+
+    /// Selects the joypad P1 reports on for an SGB in multiplayer mode, or
+    /// `None` outside of it.
+    pub fn set_sgb_player(&mut self, sgb_player: Option<u8>) {
+        self.sgb_player = sgb_player;
+
+        self.update_input_lines();
+    }
+
+    fn current_input_lines(&self) -> u8 {
+        let mut lines = INPUT_MASK;
+
+        match self.sgb_player {
+            Some(player) if self.select == SELECT_MASK => return INPUT_MASK & !player,
+            Some(player) if player > 0 => return lines,
+            _ => {},
+        }
+
+        if self.select & DIRECTION_SELECT_BIT == 0 {
+            lines &= !(self.pressed & INPUT_MASK);
+        }
+
+        if self.select & ACTION_SELECT_BIT == 0 {
+            lines &= !(self.pressed >> 4);
+        }
+
+        lines
+    }
+
+    fn update_input_lines(&mut self) {
+        let lines = self.current_input_lines();
+
+        // Any line going from high to low
+        if self.input_lines & !lines > 0 {
+            self.interrupt_requested = true;
+        }
+
+        self.input_lines = lines;
+    }
+}
+
+impl Default for JoypadComponent {
+    fn default() -> Self {
+        JoypadComponent::new()
+    }
+}
+
+impl MemoryComponent for JoypadComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![P1_ADDRESS]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        if location != P1_ADDRESS {
+            return Err(MemoryError::ReadError(location, "invalid state"));
+        }
+
+        Ok(UNUSED_BITS | self.select | self.input_lines)
+    }
+
+    fn step(&mut self) -> u8 {
+        if self.interrupt_requested {
+            self.interrupt_requested = false;
+
+            Interrupt::Joypad as u8
+        } else {
+            0x00u8
+        }
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        if location != P1_ADDRESS {
+            return Err(MemoryError::WriteError(location, value, "invalid state"));
+        }
+
+        self.select = value & SELECT_MASK;
+
+        self.update_input_lines();
+
+        Ok(())
+    }
+}
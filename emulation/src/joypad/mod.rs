@@ -0,0 +1,5 @@
+mod button;
+mod joypad_component;
+
+pub use button::Button;
+pub use joypad_component::{JoypadComponent, P1_ADDRESS};
@@ -1,22 +1,41 @@
+#[macro_use]
+extern crate lazy_static;
 extern crate num;
 #[macro_use]
 extern crate num_derive;
 extern crate num_traits;
 
 pub mod addresses;
+pub mod apu;
 mod bits;
+pub mod boot;
+pub mod cartridge;
 mod condition;
+pub mod display;
+pub mod dma;
 mod emulator;
+mod emulator_builder;
 pub mod flag;
 pub mod instruction;
+pub mod interrupt;
+pub mod joypad;
 mod memory_component;
 mod memory_mapping;
+pub mod model;
 pub mod opcode;
+pub mod ppu;
 pub mod register;
+pub mod rom;
+pub mod serial;
+pub mod sgb;
+pub mod timer;
 
 pub use crate::{
-    emulator::Emulator,
-    memory_component::{MemoryComponent, MemoryError},
+    cartridge::Cartridge,
+    emulator::{EmulationState, Emulator, CYCLES_PER_FRAME},
+    emulator_builder::EmulatorBuilder,
+    memory_component::{MemoryComponent, MemoryError, SpeedSwitchComponent},
+    model::Model,
     register::Register,
 };
 use instruction::{
@@ -29,7 +48,6 @@ use instruction::{
     logical_instructions::add_logical_instructions,
     rotating_instructions::add_rotating_instructions,
 };
-use memory_component::{SerialTransferComponent, SoundComponent, StackComponent, UnusableRamComponent, WorkRamComponent};
 
 pub fn add_instructions(emulator: &mut Emulator) {
     add_arithmetic_instructions(emulator);
@@ -43,20 +61,9 @@ pub fn add_instructions(emulator: &mut Emulator) {
 }
 
 
+/// A DMG, as built by `Emulator::builder`.
 impl Default for Emulator {
     fn default() -> Self {
-        let mut emulator = Emulator::new();
-
-        // Add components
-        emulator.add_memory_component(Box::new(SerialTransferComponent::new()));
-        emulator.add_memory_component(Box::new(SoundComponent::new()));
-        emulator.add_memory_component(Box::new(StackComponent::new()));
-        emulator.add_memory_component(Box::new(UnusableRamComponent::new()));
-        emulator.add_memory_component(Box::new(WorkRamComponent::new()));
-
-        // Add instructions
-        add_instructions(&mut emulator);
-
-        emulator
+        Emulator::builder().build()
     }
 }
\ No newline at end of file
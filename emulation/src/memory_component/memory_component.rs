@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{any::Any, fmt::Display};
 
 #[derive(Clone, Debug)]
 pub enum MemoryError {
@@ -19,15 +19,38 @@ impl Display for MemoryError {
     }
 }
 
-pub trait MemoryComponent {
+pub trait MemoryComponent: Any {
     fn mapped_locations(&self) -> Vec<u16> {
         (0u16..u16::MAX).collect()
     }
 
+    /// Whether writing to `location` changed which locations the component
+    /// covers, so the memory mapping has to be rebuilt.
+    fn mapping_changed(&self, _location: u16) -> bool {
+        false
+    }
+
+    /// Whether the component stays mapped over components registered after
+    /// it, as the boot ROM does over the cartridge.
+    fn overlay(&self) -> bool {
+        false
+    }
+
     fn read(&self, location: u16) -> Result<u8, MemoryError> {
         Err(MemoryError::ReadError(location, "unimplemented"))
     }
 
+    /// Tells the component whether the CPU runs at double speed. Components
+    /// clocked in real time rather than by the CPU are still stepped once
+    /// per machine cycle, so they use this to keep their rates.
+    fn set_double_speed(&mut self, _double_speed: bool) {}
+
+    /// Advances the component by one machine cycle, returning any interrupts
+    /// it requested as a mask of `Interrupt` bits.
+    fn step(&mut self) -> u8 {
+        0x00u8
+    }
+
     fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
         Err(MemoryError::WriteError(location, value, "unimplemented"))
     }
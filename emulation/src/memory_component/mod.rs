@@ -1,16 +1,15 @@
-mod audio_component;
 mod memory_component;
-mod serial_transfer_component;
-mod sound_component;
+mod speed_switch_component;
 mod stack_component;
 mod unimplemented_memory;
 mod unusable_ram_component;
+mod unused_io_component;
 mod work_ram_component;
 
 pub use memory_component::{MemoryComponent, MemoryError};
-pub use serial_transfer_component::SerialTransferComponent;
-pub use sound_component::SoundComponent;
+pub use speed_switch_component::SpeedSwitchComponent;
 pub use stack_component::StackComponent;
 pub use unimplemented_memory::UnimplementedMemory;
 pub use unusable_ram_component::UnusableRamComponent;
+pub use unused_io_component::UnusedIoComponent;
 pub use work_ram_component::WorkRamComponent;
\ No newline at end of file
@@ -0,0 +1,79 @@
+use super::{MemoryComponent, MemoryError};
+
+const KEY1_ADDRESS: u16 = 0xff4du16;
+
+const SWITCH_ARMED_BIT: u8 = 0b00000001;
+const CURRENT_SPEED_BIT: u8 = 0b10000000;
+const UNUSED_BITS: u8 = 0b01111110;
+
+/// The CGB speed switch register (KEY1, 0xFF4D).
+///
+/// Writing bit 0 arms the switch, and the next `STOP` toggles between normal
+/// and double speed instead of stopping the CPU.
+pub struct SpeedSwitchComponent {
+    armed: bool,
+    double_speed: bool,
+}
+
+impl SpeedSwitchComponent {
+    pub fn new() -> Self {
+        SpeedSwitchComponent {
+            armed: false,
+            double_speed: false,
+        }
+    }
+
+    pub fn armed(&self) -> bool {
+        self.armed
+    }
+
+    pub fn double_speed(&self) -> bool {
+        self.double_speed
+    }
+
+    /// Performs an armed switch, disarming it again.
+    pub fn switch(&mut self) {
+        self.armed = false;
+        self.double_speed = !self.double_speed;
+    }
+}
+
+impl Default for SpeedSwitchComponent {
+    fn default() -> Self {
+        SpeedSwitchComponent::new()
+    }
+}
+
+impl MemoryComponent for SpeedSwitchComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![KEY1_ADDRESS]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        if location != KEY1_ADDRESS {
+            return Err(MemoryError::ReadError(location, "invalid state"));
+        }
+
+        let mut value = UNUSED_BITS;
+
+        if self.armed {
+            value |= SWITCH_ARMED_BIT;
+        }
+
+        if self.double_speed {
+            value |= CURRENT_SPEED_BIT;
+        }
+
+        Ok(value)
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        if location != KEY1_ADDRESS {
+            return Err(MemoryError::WriteError(location, value, "invalid state"));
+        }
+
+        self.armed = value & SWITCH_ARMED_BIT > 0;
+
+        Ok(())
+    }
+}
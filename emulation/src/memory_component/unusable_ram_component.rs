@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use super::{MemoryComponent, MemoryError};
 
-const UNUSABLE_AREA_START_ADDRESS: u16 = 0xc000u16;
-const UNUSABLE_AREA_END_ADDRESS: u16 = 0xdfffu16;
+const UNUSABLE_AREA_START_ADDRESS: u16 = 0xfea0u16;
+const UNUSABLE_AREA_END_ADDRESS: u16 = 0xfeffu16;
 
 pub struct UnusableRamComponent {
     memory_state: HashMap<u16, u8>,
@@ -0,0 +1,35 @@
+use super::{MemoryComponent, MemoryError};
+
+const IO_START_ADDRESS: u16 = 0xff00u16;
+const IO_END_ADDRESS: u16 = 0xff7fu16;
+
+/// The I/O registers (0xFF00-0xFF7F) nothing else maps, like 0xFF03 or
+/// 0xFF7F. Nothing drives the bus there, so they read 0xFF and ignore
+/// writes. Components registered later map over it.
+pub struct UnusedIoComponent {}
+
+impl UnusedIoComponent {
+    pub fn new() -> Self {
+        UnusedIoComponent {}
+    }
+}
+
+impl Default for UnusedIoComponent {
+    fn default() -> Self {
+        UnusedIoComponent::new()
+    }
+}
+
+impl MemoryComponent for UnusedIoComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        (IO_START_ADDRESS..=IO_END_ADDRESS).collect()
+    }
+
+    fn read(&self, _: u16) -> Result<u8, MemoryError> {
+        Ok(0xffu8)
+    }
+
+    fn write(&mut self, _: u16, _: u8) -> Result<(), MemoryError> {
+        Ok(())
+    }
+}
@@ -1,50 +1,99 @@
-use std::collections::HashMap;
-
 use super::{MemoryComponent, MemoryError};
 
 const WORK_RAM_START_ADDRESS: u16 = 0xc000u16;
+const WORK_RAM_BANK_ADDRESS: u16 = 0xd000u16;
 const WORK_RAM_END_ADDRESS: u16 = 0xdfffu16;
 
 const ECHO_RAM_START_ADDRESS: u16 = 0xe000u16;
 const ECHO_RAM_END_ADDRESS: u16 = 0xfdffu16;
+const ECHO_RAM_OFFSET: u16 = 0x2000u16;
+
+const SVBK_ADDRESS: u16 = 0xff70u16;
+
+const BANK_SIZE: usize = 0x1000usize;
+const BANK_COUNT: usize = 8usize;
 
+const SVBK_BANK_MASK: u8 = 0b00000111;
+const SVBK_UNUSED_BITS: u8 = 0b11111000;
+
+/// Work RAM (0xC000-0xDFFF), along with its echo (0xE000-0xFDFF).
+///
+/// In CGB mode 0xD000-0xDFFF can be switched between banks 1-7 through SVBK
+/// (0xFF70), where selecting bank 0 selects bank 1. Outside CGB mode SVBK
+/// reads 0xFF and ignores writes.
 pub struct WorkRamComponent {
-    memory_state: HashMap<u16, u8>,
+    bank: u8,
+    cgb_mode: bool,
+    memory: Vec<u8>,
 }
 
 impl WorkRamComponent {
     pub fn new() -> Self {
-        let mut memory_state = HashMap::new();
+        WorkRamComponent {
+            bank: 0x00u8,
+            cgb_mode: false,
+            memory: vec![0x00u8; BANK_SIZE * BANK_COUNT],
+        }
+    }
+
+    pub fn set_cgb_mode(&mut self, cgb_mode: bool) {
+        self.cgb_mode = cgb_mode;
 
-        for i in ECHO_RAM_START_ADDRESS..(ECHO_RAM_END_ADDRESS + 1) {
-            memory_state.insert(i, 0x00u8);
+        if !cgb_mode {
+            self.bank = 0x00u8;
         }
+    }
+
+    fn offset(&self, location: u16) -> usize {
+        let location = if location >= ECHO_RAM_START_ADDRESS {
+            location - ECHO_RAM_OFFSET
+        } else {
+            location
+        };
 
-        for i in WORK_RAM_START_ADDRESS..(WORK_RAM_END_ADDRESS + 1) {
-            memory_state.insert(i, 0x00u8);
+        if location < WORK_RAM_BANK_ADDRESS {
+            (location - WORK_RAM_START_ADDRESS) as usize
+        } else {
+            self.bank.max(1) as usize * BANK_SIZE + (location - WORK_RAM_BANK_ADDRESS) as usize
         }
+    }
+}
 
-        WorkRamComponent { memory_state }
+impl Default for WorkRamComponent {
+    fn default() -> Self {
+        WorkRamComponent::new()
     }
 }
 
 impl MemoryComponent for WorkRamComponent {
     fn mapped_locations(&self) -> Vec<u16> {
-        self.memory_state.keys().cloned().collect()
+        (WORK_RAM_START_ADDRESS..=WORK_RAM_END_ADDRESS)
+            .chain(ECHO_RAM_START_ADDRESS..=ECHO_RAM_END_ADDRESS)
+            .chain([SVBK_ADDRESS])
+            .collect()
     }
 
     fn read(&self, location: u16) -> Result<u8, MemoryError> {
-        self.memory_state.get(&location).copied().ok_or(MemoryError::ReadError(location, "invalid state"))
+        match location {
+            WORK_RAM_START_ADDRESS..=ECHO_RAM_END_ADDRESS => Ok(self.memory[self.offset(location)]),
+            SVBK_ADDRESS if self.cgb_mode => Ok(SVBK_UNUSED_BITS | self.bank),
+            SVBK_ADDRESS => Ok(0xffu8),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
     }
 
     fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
-        if self.memory_state.contains_key(&location) {
-            self.memory_state.insert(location, value);
+        match location {
+            WORK_RAM_START_ADDRESS..=ECHO_RAM_END_ADDRESS => {
+                let offset = self.offset(location);
 
-            Ok(())
-        } else {
-            Err(MemoryError::WriteError(location, value, "invalid state"))
-        }
+                self.memory[offset] = value;
+            },
+            SVBK_ADDRESS if self.cgb_mode => self.bank = value & SVBK_BANK_MASK,
+            SVBK_ADDRESS => {},
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
     }
 }
-
@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::memory_component::{MemoryComponent, MemoryError, UnimplementedMemory};
 
 pub struct MemoryMapping {
@@ -9,7 +11,7 @@ impl MemoryMapping {
     pub fn new() -> Self {
         let mut memory_mapping = MemoryMapping {
             components: vec![],
-            memory_mapping: vec![0; u16::MAX as usize],
+            memory_mapping: vec![0; u16::MAX as usize + 1],
         };
 
         memory_mapping.register_component(Box::new(UnimplementedMemory::new()));
@@ -17,6 +19,31 @@ impl MemoryMapping {
         memory_mapping
     }
 
+    /// Finds the most recently registered component of the given type.
+    pub fn component<T: MemoryComponent>(&self) -> Option<&T> {
+        self.components.iter().rev().find_map(|c| (c.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
+    pub fn component_mut<T: MemoryComponent>(&mut self) -> Option<&mut T> {
+        self.components.iter_mut().rev().find_map(|c| (c.as_mut() as &mut dyn Any).downcast_mut::<T>())
+    }
+
+    /// The component registered at `index`, if it's of the given type.
+    /// Components never move once registered, so an index found with
+    /// `component_index` stays valid.
+    pub fn component_at<T: MemoryComponent>(&self, index: usize) -> Option<&T> {
+        self.components.get(index).and_then(|c| (c.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
+    pub fn component_at_mut<T: MemoryComponent>(&mut self, index: usize) -> Option<&mut T> {
+        self.components.get_mut(index).and_then(|c| (c.as_mut() as &mut dyn Any).downcast_mut::<T>())
+    }
+
+    /// Where the most recently registered component of the given type sits.
+    pub fn component_index<T: MemoryComponent>(&self) -> Option<usize> {
+        self.components.iter().rposition(|c| (c.as_ref() as &dyn Any).is::<T>())
+    }
+
     pub fn read(&self, location: u16) -> Result<u8, MemoryError> {
         let component_index = self.memory_mapping[location as usize];
 
@@ -30,18 +57,60 @@ impl MemoryMapping {
 
         self.components.push(component);
 
-        for location in &self.components[component_index].mapped_locations() {
-            self.memory_mapping[*location as usize] = component_index;
-        }
+        self.map_component(component_index);
+        self.map_overlays();
 
         self
     }
 
+    fn map_component(&mut self, component_index: usize) {
+        for location in self.components[component_index].mapped_locations() {
+            self.memory_mapping[location as usize] = component_index;
+        }
+    }
+
+    /// Puts overlays back on top of whatever was registered after them.
+    fn map_overlays(&mut self) {
+        for component_index in 0..self.components.len() {
+            if self.components[component_index].overlay() {
+                self.map_component(component_index);
+            }
+        }
+    }
+
+    /// Maps every location again from scratch, for when a component stops
+    /// covering some of them.
+    fn remap(&mut self) {
+        for component_index in 0..self.components.len() {
+            self.map_component(component_index);
+        }
+
+        self.map_overlays();
+    }
+
+    pub fn set_double_speed(&mut self, double_speed: bool) {
+        for component in self.components.iter_mut() {
+            component.set_double_speed(double_speed);
+        }
+    }
+
+    /// Advances every component by one machine cycle, returning the
+    /// interrupts they requested.
+    pub fn step(&mut self) -> u8 {
+        self.components.iter_mut().fold(0x00u8, |requests, c| requests | c.step())
+    }
+
     pub fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
         let component_index = self.memory_mapping[location as usize];
 
         let component = self.components.get_mut(component_index).unwrap();
 
-        component.write(location, value)
+        component.write(location, value)?;
+
+        if component.mapping_changed(location) {
+            self.remap();
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,23 @@
+/// The hardware being emulated, for the behaviour that differs between
+/// models.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Model {
+    /// The original Game Boy.
+    #[default]
+    Dmg,
+    /// The Game Boy Pocket and Light.
+    Mgb,
+    /// The Super Game Boy.
+    Sgb,
+    /// The Game Boy Color.
+    Cgb,
+    /// The Game Boy Advance, running Game Boy Color software.
+    Agb,
+}
+
+impl Model {
+    /// Whether this is a Game Boy Color, or runs its software.
+    pub fn is_cgb(&self) -> bool {
+        matches!(self, Model::Cgb | Model::Agb)
+    }
+}
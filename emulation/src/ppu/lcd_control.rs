@@ -0,0 +1,34 @@
+/// The bits of the LCD control register (LCDC, 0xFF40).
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum LcdControlFlag {
+    BackgroundEnable = 0b00000001,
+    ObjectEnable = 0b00000010,
+    ObjectSize = 0b00000100,
+    BackgroundTileMap = 0b00001000,
+    TileData = 0b00010000,
+    WindowEnable = 0b00100000,
+    WindowTileMap = 0b01000000,
+    LcdEnable = 0b10000000,
+}
+
+/// The bits of the LCD status register (STAT, 0xFF41) above the mode.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum LcdStatusFlag {
+    Coincidence = 0b00000100,
+    HBlankInterrupt = 0b00001000,
+    VBlankInterrupt = 0b00010000,
+    OamScanInterrupt = 0b00100000,
+    CoincidenceInterrupt = 0b01000000,
+}
+
+/// The PPU modes, numbered as they read back from the low STAT bits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum PpuMode {
+    HBlank = 0b00,
+    VBlank = 0b01,
+    OamScan = 0b10,
+    Drawing = 0b11,
+}
@@ -0,0 +1,11 @@
+mod lcd_control;
+mod palette_ram;
+mod ppu_component;
+mod sprite;
+mod tile_attribute;
+
+pub use lcd_control::{LcdControlFlag, LcdStatusFlag, PpuMode};
+pub use palette_ram::PaletteRam;
+pub use ppu_component::{PpuComponent, SCREEN_HEIGHT, SCREEN_WIDTH};
+pub use sprite::{Sprite, SpriteFlag};
+pub use tile_attribute::{TileAttributeFlag, TILE_ATTRIBUTE_PALETTE_MASK};
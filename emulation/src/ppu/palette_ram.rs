@@ -0,0 +1,73 @@
+const PALETTE_RAM_SIZE: usize = 0x40usize;
+
+const INDEX_MASK: u8 = 0b00111111;
+const AUTO_INCREMENT_BIT: u8 = 0b10000000;
+const SPEC_UNUSED_BITS: u8 = 0b01000000;
+
+const COLOR_MASK: u16 = 0x7fffu16;
+
+/// One of the CGB's two palette memories, for the background (BCPS/BCPD,
+/// 0xFF68-0xFF69) or objects (OCPS/OCPD, 0xFF6A-0xFF6B).
+///
+/// Eight palettes of four colors, each color a little-endian RGB555 value.
+/// The spec register selects a byte to reach through the data register, and
+/// can move on to the next byte after every write.
+pub struct PaletteRam {
+    auto_increment: bool,
+    data: [u8; PALETTE_RAM_SIZE],
+    index: u8,
+}
+
+impl PaletteRam {
+    /// Starts out white, as the boot ROM leaves it.
+    pub fn new() -> Self {
+        PaletteRam {
+            auto_increment: false,
+            data: [0xffu8; PALETTE_RAM_SIZE],
+            index: 0x00u8,
+        }
+    }
+
+    pub fn color(&self, palette: u8, color: u8) -> u16 {
+        let offset = (palette as usize & 0b111) * 8 + color as usize * 2;
+
+        u16::from_le_bytes([self.data[offset], self.data[offset + 1]]) & COLOR_MASK
+    }
+
+    pub fn read_data(&self) -> u8 {
+        self.data[self.index as usize]
+    }
+
+    pub fn read_spec(&self) -> u8 {
+        let mut value = SPEC_UNUSED_BITS | self.index;
+
+        if self.auto_increment {
+            value |= AUTO_INCREMENT_BIT;
+        }
+
+        value
+    }
+
+    /// Writes the selected byte, unless the PPU is drawing with it. The
+    /// index moves on either way.
+    pub fn write_data(&mut self, value: u8, accessible: bool) {
+        if accessible {
+            self.data[self.index as usize] = value;
+        }
+
+        if self.auto_increment {
+            self.index = (self.index + 1) & INDEX_MASK;
+        }
+    }
+
+    pub fn write_spec(&mut self, value: u8) {
+        self.auto_increment = value & AUTO_INCREMENT_BIT > 0;
+        self.index = value & INDEX_MASK;
+    }
+}
+
+impl Default for PaletteRam {
+    fn default() -> Self {
+        PaletteRam::new()
+    }
+}
@@ -0,0 +1,640 @@
+use crate::{
+    interrupt::Interrupt,
+    memory_component::{MemoryComponent, MemoryError},
+    ppu::{
+        LcdControlFlag, LcdStatusFlag, PaletteRam, PpuMode, Sprite, SpriteFlag, TileAttributeFlag,
+        TILE_ATTRIBUTE_PALETTE_MASK,
+    },
+};
+
+pub const SCREEN_WIDTH: usize = 160usize;
+pub const SCREEN_HEIGHT: usize = 144usize;
+
+const VRAM_START_ADDRESS: u16 = 0x8000u16;
+const VRAM_END_ADDRESS: u16 = 0x9fffu16;
+const OAM_START_ADDRESS: u16 = 0xfe00u16;
+const OAM_END_ADDRESS: u16 = 0xfe9fu16;
+
+const LCDC_ADDRESS: u16 = 0xff40u16;
+const STAT_ADDRESS: u16 = 0xff41u16;
+const SCY_ADDRESS: u16 = 0xff42u16;
+const SCX_ADDRESS: u16 = 0xff43u16;
+const LY_ADDRESS: u16 = 0xff44u16;
+const LYC_ADDRESS: u16 = 0xff45u16;
+const BGP_ADDRESS: u16 = 0xff47u16;
+const OBP0_ADDRESS: u16 = 0xff48u16;
+const OBP1_ADDRESS: u16 = 0xff49u16;
+const WY_ADDRESS: u16 = 0xff4au16;
+const WX_ADDRESS: u16 = 0xff4bu16;
+const VBK_ADDRESS: u16 = 0xff4fu16;
+const BCPS_ADDRESS: u16 = 0xff68u16;
+const BCPD_ADDRESS: u16 = 0xff69u16;
+const OCPS_ADDRESS: u16 = 0xff6au16;
+const OCPD_ADDRESS: u16 = 0xff6bu16;
+
+const VRAM_SIZE: usize = 0x2000usize;
+const VRAM_BANK_COUNT: usize = 2usize;
+const OAM_SIZE: usize = 0xa0usize;
+const OAM_SPRITE_COUNT: usize = OAM_SIZE / Sprite::SIZE;
+
+// Offsets into VRAM
+const TILE_MAP_LOW_OFFSET: usize = 0x1800usize;
+const TILE_MAP_HIGH_OFFSET: usize = 0x1c00usize;
+const SIGNED_TILE_DATA_OFFSET: usize = 0x1000usize;
+const TILE_SIZE: usize = 16usize;
+const TILE_MAP_WIDTH: usize = 32usize;
+
+// Timing, in dots (4 per machine cycle)
+const DOTS_PER_CYCLE: usize = 4usize;
+const OAM_SCAN_DOTS: usize = 80usize;
+const DRAWING_DOTS: usize = 172usize;
+const DOTS_PER_LINE: usize = 456usize;
+const VBLANK_START_LINE: u8 = 144u8;
+const LINES_PER_FRAME: u8 = 154u8;
+
+const MAX_SPRITES_PER_LINE: usize = 10usize;
+const WINDOW_X_OFFSET: i16 = 7i16;
+
+// Only the interrupt enables in STAT are writable
+const STAT_WRITABLE_MASK: u8 = 0b01111000;
+const STAT_UNUSED_BITS: u8 = 0b10000000;
+
+const VBK_UNUSED_BITS: u8 = 0b11111110;
+
+/// The pixel processing unit.
+///
+/// Maps VRAM (0x8000-0x9FFF), OAM (0xFE00-0xFE9F) and the LCD registers
+/// (0xFF40-0xFF4B, apart from DMA at 0xFF46), and steps through OAM scan
+/// (mode 2), drawing (mode 3), H-Blank (mode 0) and V-Blank (mode 1). Each
+/// line is rendered in one go when drawing starts, into a framebuffer of DMG
+/// shades (0 is white, 3 is black).
+///
+/// In CGB mode there is a second VRAM bank, selected through VBK (0xFF4F),
+/// holding more tiles and the background map attributes, and colors come
+/// from the palette memories behind BCPS/BCPD and OCPS/OCPD (0xFF68-0xFF6B).
+/// Lines are then also rendered into a framebuffer of RGB555 colors. Outside
+/// CGB mode these registers read 0xFF and ignore writes.
+pub struct PpuComponent {
+    background_palettes: PaletteRam,
+    bgp: u8,
+    cgb_mode: bool,
+    color_framebuffer: Vec<u16>,
+    dot: usize,
+    double_speed: bool,
+    frames: usize,
+    framebuffer: Vec<u8>,
+    lcdc: u8,
+    ly: u8,
+    lyc: u8,
+    mode: PpuMode,
+    oam: Vec<u8>,
+    object_palettes: PaletteRam,
+    obp0: u8,
+    obp1: u8,
+    scx: u8,
+    scy: u8,
+    stat: u8,
+    stat_line: bool,
+    vram: Vec<u8>,
+    vram_bank: u8,
+    window_line: u8,
+    wx: u8,
+    wy: u8,
+}
+
+impl PpuComponent {
+    pub fn new() -> Self {
+        PpuComponent {
+            background_palettes: PaletteRam::new(),
+            bgp: 0xfcu8,
+            cgb_mode: false,
+            color_framebuffer: vec![0x0000u16; SCREEN_WIDTH * SCREEN_HEIGHT],
+            dot: 0usize,
+            double_speed: false,
+            frames: 0usize,
+            framebuffer: vec![0x00u8; SCREEN_WIDTH * SCREEN_HEIGHT],
+            lcdc: 0x00u8,
+            ly: 0x00u8,
+            lyc: 0x00u8,
+            mode: PpuMode::HBlank,
+            oam: vec![0x00u8; OAM_SIZE],
+            object_palettes: PaletteRam::new(),
+            obp0: 0xffu8,
+            obp1: 0xffu8,
+            scx: 0x00u8,
+            scy: 0x00u8,
+            stat: 0x00u8,
+            stat_line: false,
+            vram: vec![0x00u8; VRAM_SIZE * VRAM_BANK_COUNT],
+            vram_bank: 0x00u8,
+            window_line: 0x00u8,
+            wx: 0x00u8,
+            wy: 0x00u8,
+        }
+    }
+
+    pub fn cgb_mode(&self) -> bool {
+        self.cgb_mode
+    }
+
+    /// Switches between DMG rendering and CGB rendering, with its extra VRAM
+    /// bank and color palettes.
+    pub fn set_cgb_mode(&mut self, cgb_mode: bool) {
+        self.cgb_mode = cgb_mode;
+
+        if !cgb_mode {
+            self.vram_bank = 0x00u8;
+        }
+    }
+
+    /// The last completed frame, row by row, as RGB555 colors. Only drawn to
+    /// in CGB mode.
+    pub fn color_framebuffer(&self) -> &[u16] {
+        &self.color_framebuffer
+    }
+
+    /// The number of frames completed, counted when V-Blank starts.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// The last completed frame, row by row, as DMG shades 0-3. In CGB mode
+    /// these are the raw color numbers instead, before any palette.
+    pub fn framebuffer(&self) -> &[u8] {
+        &self.framebuffer
+    }
+
+    pub fn ly(&self) -> u8 {
+        self.ly
+    }
+
+    pub fn mode(&self) -> PpuMode {
+        self.mode
+    }
+
+    fn lcdc_flag(&self, flag: LcdControlFlag) -> bool {
+        self.lcdc & (flag as u8) > 0
+    }
+
+    pub fn lcd_enabled(&self) -> bool {
+        self.lcdc_flag(LcdControlFlag::LcdEnable)
+    }
+
+    fn oam_accessible(&self) -> bool {
+        !self.lcd_enabled() || !matches!(self.mode, PpuMode::OamScan | PpuMode::Drawing)
+    }
+
+    /// Writes a byte into OAM regardless of mode, as OAM DMA does.
+    pub fn write_oam(&mut self, offset: u8, value: u8) {
+        self.oam[offset as usize] = value;
+    }
+
+    /// Writes a byte into the selected VRAM bank regardless of mode, as VRAM
+    /// DMA does.
+    pub fn write_vram(&mut self, offset: u16, value: u8) {
+        self.vram[self.vram_bank as usize * VRAM_SIZE + offset as usize] = value;
+    }
+
+    fn vram_accessible(&self) -> bool {
+        !self.lcd_enabled() || self.mode != PpuMode::Drawing
+    }
+
+    fn vram_offset(&self, location: u16) -> usize {
+        self.vram_bank as usize * VRAM_SIZE + (location - VRAM_START_ADDRESS) as usize
+    }
+
+    fn read_stat(&self) -> u8 {
+        let mut stat = STAT_UNUSED_BITS | self.stat | self.mode as u8;
+
+        if self.ly == self.lyc {
+            stat |= LcdStatusFlag::Coincidence as u8;
+        }
+
+        stat
+    }
+
+    fn stat_line(&self) -> bool {
+        let mode_source = match self.mode {
+            PpuMode::HBlank => Some(LcdStatusFlag::HBlankInterrupt),
+            PpuMode::VBlank => Some(LcdStatusFlag::VBlankInterrupt),
+            PpuMode::OamScan => Some(LcdStatusFlag::OamScanInterrupt),
+            PpuMode::Drawing => None,
+        };
+
+        let mode_line = mode_source.is_some_and(|flag| self.stat & (flag as u8) > 0);
+        let coincidence_line = self.ly == self.lyc && self.stat & (LcdStatusFlag::CoincidenceInterrupt as u8) > 0;
+
+        mode_line || coincidence_line
+    }
+
+    fn write_lcdc(&mut self, value: u8) {
+        let was_enabled = self.lcd_enabled();
+
+        self.lcdc = value;
+
+        if was_enabled && !self.lcd_enabled() {
+            // Turning the LCD off resets it to the top of the screen
+            self.dot = 0;
+            self.ly = 0;
+            self.mode = PpuMode::HBlank;
+            self.stat_line = false;
+            self.window_line = 0;
+        } else if !was_enabled && self.lcd_enabled() {
+            self.dot = 0;
+            self.ly = 0;
+            self.mode = PpuMode::OamScan;
+        }
+    }
+
+    fn palette_shade(palette: u8, color: u8) -> u8 {
+        (palette >> (color * 2)) & 0b11
+    }
+
+    /// Reads the 2-bit color of a pixel within a tile.
+    fn tile_pixel(&self, tile_offset: usize, x: u8, y: u8) -> u8 {
+        let row = tile_offset + y as usize * 2;
+
+        let low = self.vram[row];
+        let high = self.vram[row + 1];
+
+        let bit = 7 - x;
+
+        (((high >> bit) & 0b1) << 1) | ((low >> bit) & 0b1)
+    }
+
+    fn tile_data_offset(&self, tile: u8) -> usize {
+        if self.lcdc_flag(LcdControlFlag::TileData) {
+            tile as usize * TILE_SIZE
+        } else {
+            // 0x8800 addressing: tile numbers are signed, relative to 0x9000
+            (SIGNED_TILE_DATA_OFFSET as isize + tile as i8 as isize * TILE_SIZE as isize) as usize
+        }
+    }
+
+    /// The color of a pixel of a tile map, along with its CGB attributes,
+    /// which are all clear outside CGB mode.
+    fn tile_map_pixel(&self, map_offset: usize, x: u8, y: u8) -> (u8, u8) {
+        let map_index = map_offset + (y as usize / 8) * TILE_MAP_WIDTH + x as usize / 8;
+
+        let tile = self.vram[map_index];
+
+        let attributes = if self.cgb_mode {
+            self.vram[VRAM_SIZE + map_index]
+        } else {
+            0x00u8
+        };
+
+        let flag = |flag: TileAttributeFlag| attributes & (flag as u8) > 0;
+
+        let bank_offset = if flag(TileAttributeFlag::Bank) { VRAM_SIZE } else { 0usize };
+        let x = if flag(TileAttributeFlag::XFlip) { 7 - x % 8 } else { x % 8 };
+        let y = if flag(TileAttributeFlag::YFlip) { 7 - y % 8 } else { y % 8 };
+
+        (self.tile_pixel(bank_offset + self.tile_data_offset(tile), x, y), attributes)
+    }
+
+    fn render_line(&mut self) {
+        let line = self.ly;
+        let row = line as usize * SCREEN_WIDTH;
+
+        // Raw background colors and attributes, needed to resolve object
+        // priority
+        let mut background_colors = [0x00u8; SCREEN_WIDTH];
+        let mut background_attributes = [0x00u8; SCREEN_WIDTH];
+
+        let window_visible = self.lcdc_flag(LcdControlFlag::WindowEnable) && line >= self.wy;
+        let mut window_drawn = false;
+
+        // In CGB mode the background is always drawn, and the enable bit
+        // decides whether it can cover objects instead
+        if self.cgb_mode || self.lcdc_flag(LcdControlFlag::BackgroundEnable) {
+            let background_map = if self.lcdc_flag(LcdControlFlag::BackgroundTileMap) {
+                TILE_MAP_HIGH_OFFSET
+            } else {
+                TILE_MAP_LOW_OFFSET
+            };
+
+            let window_map = if self.lcdc_flag(LcdControlFlag::WindowTileMap) {
+                TILE_MAP_HIGH_OFFSET
+            } else {
+                TILE_MAP_LOW_OFFSET
+            };
+
+            let pixels = background_colors.iter_mut().zip(background_attributes.iter_mut());
+
+            for (x, (color, attributes)) in pixels.enumerate() {
+                let window_x = x as i16 + WINDOW_X_OFFSET - self.wx as i16;
+
+                (*color, *attributes) = if window_visible && window_x >= 0 {
+                    window_drawn = true;
+
+                    self.tile_map_pixel(window_map, window_x as u8, self.window_line)
+                } else {
+                    let background_x = (x as u8).wrapping_add(self.scx);
+                    let background_y = line.wrapping_add(self.scy);
+
+                    self.tile_map_pixel(background_map, background_x, background_y)
+                };
+            }
+        }
+
+        // The window keeps its own line counter, which only moves on lines
+        // where it was actually drawn
+        if window_drawn {
+            self.window_line = self.window_line.wrapping_add(1);
+        }
+
+        let pixels = background_colors.iter().zip(background_attributes.iter());
+
+        for (x, (color, attributes)) in pixels.enumerate() {
+            if self.cgb_mode {
+                let palette = attributes & TILE_ATTRIBUTE_PALETTE_MASK;
+
+                self.color_framebuffer[row + x] = self.background_palettes.color(palette, *color);
+                self.framebuffer[row + x] = *color;
+            } else {
+                self.framebuffer[row + x] = PpuComponent::palette_shade(self.bgp, *color);
+            }
+        }
+
+        if self.lcdc_flag(LcdControlFlag::ObjectEnable) {
+            self.render_sprites(line, &background_colors, &background_attributes);
+        }
+    }
+
+    fn render_sprites(
+        &mut self,
+        line: u8,
+        background_colors: &[u8; SCREEN_WIDTH],
+        background_attributes: &[u8; SCREEN_WIDTH],
+    ) {
+        let height = if self.lcdc_flag(LcdControlFlag::ObjectSize) {
+            16u8
+        } else {
+            8u8
+        };
+
+        // OAM scan picks the first ten objects on the line, in OAM order
+        let mut sprites: Vec<Sprite> = (0..OAM_SPRITE_COUNT)
+            .map(|i| Sprite::from_oam(&self.oam, i))
+            .filter(|s| s.on_line(line, height))
+            .take(MAX_SPRITES_PER_LINE)
+            .collect();
+
+        // On DMG the leftmost object wins, then the one earliest in OAM. In
+        // CGB mode only OAM order counts.
+        if !self.cgb_mode {
+            sprites.sort_by_key(|s| (s.x, s.index));
+        }
+
+        let row = line as usize * SCREEN_WIDTH;
+
+        // In CGB mode, clearing the background enable bit puts every object
+        // in front of the background
+        let background_priority = !self.cgb_mode || self.lcdc_flag(LcdControlFlag::BackgroundEnable);
+
+        let background = background_colors.iter().zip(background_attributes.iter());
+
+        for (x, (background_color, background_attributes)) in background.enumerate() {
+            for sprite in &sprites {
+                let sprite_x = x as i16 + 8 - sprite.x as i16;
+
+                if !(0..8).contains(&sprite_x) {
+                    continue;
+                }
+
+                let mut sprite_y = line as i16 + 16 - sprite.y as i16;
+
+                if sprite.flag(SpriteFlag::YFlip) {
+                    sprite_y = height as i16 - 1 - sprite_y;
+                }
+
+                let sprite_x = if sprite.flag(SpriteFlag::XFlip) {
+                    7 - sprite_x
+                } else {
+                    sprite_x
+                };
+
+                let tile = if height == 16 {
+                    sprite.tile & 0xfeu8
+                } else {
+                    sprite.tile
+                };
+
+                let bank_offset = if self.cgb_mode && sprite.flag(SpriteFlag::Bank) {
+                    VRAM_SIZE
+                } else {
+                    0usize
+                };
+
+                // Tall objects run straight on into the next tile
+                let tile_offset = bank_offset + tile as usize * TILE_SIZE;
+                let color = self.tile_pixel(tile_offset, sprite_x as u8, sprite_y as u8);
+
+                if color == 0 {
+                    continue;
+                }
+
+                let behind_background = sprite.flag(SpriteFlag::BehindBackground)
+                    || background_attributes & (TileAttributeFlag::Priority as u8) > 0;
+
+                // The highest priority opaque object decides, even when it
+                // ends up hidden behind the background
+                if !background_priority || !behind_background || *background_color == 0 {
+                    if self.cgb_mode {
+                        let cgb_color = self.object_palettes.color(sprite.cgb_palette(), color);
+
+                        self.color_framebuffer[row + x] = cgb_color;
+                        self.framebuffer[row + x] = color;
+                    } else {
+                        let palette = if sprite.flag(SpriteFlag::Palette) {
+                            self.obp1
+                        } else {
+                            self.obp0
+                        };
+
+                        self.framebuffer[row + x] = PpuComponent::palette_shade(palette, color);
+                    }
+                }
+
+                break;
+            }
+        }
+    }
+}
+
+impl Default for PpuComponent {
+    fn default() -> Self {
+        PpuComponent::new()
+    }
+}
+
+impl MemoryComponent for PpuComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        (VRAM_START_ADDRESS..=VRAM_END_ADDRESS)
+            .chain(OAM_START_ADDRESS..=OAM_END_ADDRESS)
+            .chain(LCDC_ADDRESS..=LYC_ADDRESS)
+            .chain(BGP_ADDRESS..=WX_ADDRESS)
+            .chain([VBK_ADDRESS])
+            .chain(BCPS_ADDRESS..=OCPD_ADDRESS)
+            .collect()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            VRAM_START_ADDRESS..=VRAM_END_ADDRESS => {
+                if self.vram_accessible() {
+                    Ok(self.vram[self.vram_offset(location)])
+                } else {
+                    Ok(0xffu8)
+                }
+            },
+            OAM_START_ADDRESS..=OAM_END_ADDRESS => {
+                if self.oam_accessible() {
+                    Ok(self.oam[(location - OAM_START_ADDRESS) as usize])
+                } else {
+                    Ok(0xffu8)
+                }
+            },
+            LCDC_ADDRESS => Ok(self.lcdc),
+            STAT_ADDRESS => Ok(self.read_stat()),
+            SCY_ADDRESS => Ok(self.scy),
+            SCX_ADDRESS => Ok(self.scx),
+            LY_ADDRESS => Ok(self.ly),
+            LYC_ADDRESS => Ok(self.lyc),
+            BGP_ADDRESS => Ok(self.bgp),
+            OBP0_ADDRESS => Ok(self.obp0),
+            OBP1_ADDRESS => Ok(self.obp1),
+            WY_ADDRESS => Ok(self.wy),
+            WX_ADDRESS => Ok(self.wx),
+            VBK_ADDRESS | BCPS_ADDRESS..=OCPD_ADDRESS if !self.cgb_mode => Ok(0xffu8),
+            VBK_ADDRESS => Ok(VBK_UNUSED_BITS | self.vram_bank),
+            BCPS_ADDRESS => Ok(self.background_palettes.read_spec()),
+            BCPD_ADDRESS if !self.vram_accessible() => Ok(0xffu8),
+            BCPD_ADDRESS => Ok(self.background_palettes.read_data()),
+            OCPS_ADDRESS => Ok(self.object_palettes.read_spec()),
+            OCPD_ADDRESS if !self.vram_accessible() => Ok(0xffu8),
+            OCPD_ADDRESS => Ok(self.object_palettes.read_data()),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn set_double_speed(&mut self, double_speed: bool) {
+        self.double_speed = double_speed;
+    }
+
+    fn step(&mut self) -> u8 {
+        if !self.lcd_enabled() {
+            return 0x00u8;
+        }
+
+        let mut requests = 0x00u8;
+
+        // The dot clock doesn't speed up with the CPU
+        self.dot += if self.double_speed {
+            DOTS_PER_CYCLE / 2
+        } else {
+            DOTS_PER_CYCLE
+        };
+
+        match self.mode {
+            PpuMode::OamScan => {
+                if self.dot >= OAM_SCAN_DOTS {
+                    self.mode = PpuMode::Drawing;
+
+                    self.render_line();
+                }
+            },
+            PpuMode::Drawing => {
+                if self.dot >= OAM_SCAN_DOTS + DRAWING_DOTS {
+                    self.mode = PpuMode::HBlank;
+                }
+            },
+            PpuMode::HBlank => {
+                if self.dot >= DOTS_PER_LINE {
+                    self.dot -= DOTS_PER_LINE;
+                    self.ly += 1;
+
+                    if self.ly == VBLANK_START_LINE {
+                        self.mode = PpuMode::VBlank;
+                        self.frames += 1;
+
+                        requests |= Interrupt::VBlank as u8;
+                    } else {
+                        self.mode = PpuMode::OamScan;
+                    }
+                }
+            },
+            PpuMode::VBlank => {
+                if self.dot >= DOTS_PER_LINE {
+                    self.dot -= DOTS_PER_LINE;
+                    self.ly += 1;
+
+                    if self.ly == LINES_PER_FRAME {
+                        self.ly = 0;
+                        self.window_line = 0;
+                        self.mode = PpuMode::OamScan;
+                    }
+                }
+            },
+        };
+
+        // STAT requests an interrupt when any of its enabled sources goes
+        // high while none were high before
+        let stat_line = self.stat_line();
+
+        if stat_line && !self.stat_line {
+            requests |= Interrupt::LcdStat as u8;
+        }
+
+        self.stat_line = stat_line;
+
+        requests
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            VRAM_START_ADDRESS..=VRAM_END_ADDRESS => {
+                if self.vram_accessible() {
+                    let offset = self.vram_offset(location);
+
+                    self.vram[offset] = value;
+                }
+            },
+            OAM_START_ADDRESS..=OAM_END_ADDRESS => {
+                if self.oam_accessible() {
+                    self.oam[(location - OAM_START_ADDRESS) as usize] = value;
+                }
+            },
+            LCDC_ADDRESS => self.write_lcdc(value),
+            STAT_ADDRESS => self.stat = value & STAT_WRITABLE_MASK,
+            SCY_ADDRESS => self.scy = value,
+            SCX_ADDRESS => self.scx = value,
+            // LY is read-only
+            LY_ADDRESS => {},
+            LYC_ADDRESS => self.lyc = value,
+            BGP_ADDRESS => self.bgp = value,
+            OBP0_ADDRESS => self.obp0 = value,
+            OBP1_ADDRESS => self.obp1 = value,
+            WY_ADDRESS => self.wy = value,
+            WX_ADDRESS => self.wx = value,
+            VBK_ADDRESS | BCPS_ADDRESS..=OCPD_ADDRESS if !self.cgb_mode => {},
+            VBK_ADDRESS => self.vram_bank = value & 0b1,
+            BCPS_ADDRESS => self.background_palettes.write_spec(value),
+            BCPD_ADDRESS => {
+                let accessible = self.vram_accessible();
+
+                self.background_palettes.write_data(value, accessible);
+            },
+            OCPS_ADDRESS => self.object_palettes.write_spec(value),
+            OCPD_ADDRESS => {
+                let accessible = self.vram_accessible();
+
+                self.object_palettes.write_data(value, accessible);
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+/// The bits of an object's attribute byte. In CGB mode the low three bits
+/// pick the palette instead of `Palette`.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum SpriteFlag {
+    Bank = 0b00001000,
+    Palette = 0b00010000,
+    XFlip = 0b00100000,
+    YFlip = 0b01000000,
+    BehindBackground = 0b10000000,
+}
+
+const CGB_PALETTE_MASK: u8 = 0b00000111;
+
+/// An object attribute entry, as stored in OAM.
+///
+/// Positions are stored offset by (8, 16) so objects can sit partially off
+/// the top and left edges of the screen.
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite {
+    pub attributes: u8,
+    pub index: usize,
+    pub tile: u8,
+    pub x: u8,
+    pub y: u8,
+}
+
+impl Sprite {
+    pub const SIZE: usize = 4;
+
+    pub fn from_oam(oam: &[u8], index: usize) -> Self {
+        let entry = &oam[index * Sprite::SIZE..(index + 1) * Sprite::SIZE];
+
+        Sprite {
+            attributes: entry[3],
+            index,
+            tile: entry[2],
+            x: entry[1],
+            y: entry[0],
+        }
+    }
+
+    /// The object palette used in CGB mode.
+    pub fn cgb_palette(&self) -> u8 {
+        self.attributes & CGB_PALETTE_MASK
+    }
+
+    pub fn flag(&self, flag: SpriteFlag) -> bool {
+        self.attributes & (flag as u8) > 0
+    }
+
+    /// Whether the object covers the given line for the current object height.
+    pub fn on_line(&self, line: u8, height: u8) -> bool {
+        let top = self.y as i16 - 16;
+
+        (line as i16) >= top && (line as i16) < top + height as i16
+    }
+}
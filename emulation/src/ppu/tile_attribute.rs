@@ -0,0 +1,13 @@
+/// The bits of a CGB background map attribute, stored in VRAM bank 1 at the
+/// same offset as the tile number it applies to. The low three bits pick the
+/// palette.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum TileAttributeFlag {
+    Bank = 0b00001000,
+    XFlip = 0b00100000,
+    YFlip = 0b01000000,
+    Priority = 0b10000000,
+}
+
+pub const TILE_ATTRIBUTE_PALETTE_MASK: u8 = 0b00000111;
@@ -2,120 +2,306 @@ use std::collections::HashMap;
 
 use num::ToPrimitive;
 
+use crate::cartridge::CartridgeType;
+
 // Restarts
-const RESTART_DATA_SIZE: u8 = 0x8u8;
-const RESTART_AREA_START_ADDRESS: u16 = 0x0000u16;
-const RESTART_AREA_END_ADDRESS: u16 = 0x003fu16;
-const RESTART_ZERO_ADDRESS: u16 = 0x0000u16;
-const RESTART_ONE_ADDRESS: u16 = 0x0008u16;
-const RESTART_TWO_ADDRESS: u16 = 0x0010u16;
-const RESTART_THREE_ADDRESS: u16 = 0x0018u16;
-const RESTART_FOUR_ADDRESS: u16 = 0x0020u16;
-const RESTART_FIVE_ADDRESS: u16 = 0x0028u16;
-const RESTART_SIX_ADDRESS: u16 = 0x0030u16;
-const RESTART_SEVEN_ADDRESS: u16 = 0x0038u16;
+pub const RESTART_DATA_SIZE: u8 = 0x8u8;
+pub const RESTART_AREA_START_ADDRESS: u16 = 0x0000u16;
+pub const RESTART_AREA_END_ADDRESS: u16 = 0x003fu16;
+pub const RESTART_ZERO_ADDRESS: u16 = 0x0000u16;
+pub const RESTART_ONE_ADDRESS: u16 = 0x0008u16;
+pub const RESTART_TWO_ADDRESS: u16 = 0x0010u16;
+pub const RESTART_THREE_ADDRESS: u16 = 0x0018u16;
+pub const RESTART_FOUR_ADDRESS: u16 = 0x0020u16;
+pub const RESTART_FIVE_ADDRESS: u16 = 0x0028u16;
+pub const RESTART_SIX_ADDRESS: u16 = 0x0030u16;
+pub const RESTART_SEVEN_ADDRESS: u16 = 0x0038u16;
 
 // Interrupts
-const INTERRUPT_DATA_SIZE: u8 = 0x8u8;
-const INTERRUPT_AREA_START_ADDRESS: u16 = 0x0040u16;
-const INTERRUPT_AREA_END_ADDRESS: u16 = 0x0067u16;
-const VERTICAL_BANKING_INTERRUPT_START_ADDRESS: u16 = 0x0040u16;
-const LCDC_INTERRUPT_START_ADDRESS: u16 = 0x0048u16;
-const TIMER_OVERFLOW_INTERRUPT_START_ADDRESS: u16 = 0x0050u16;
-const SERIAL_TRANSFER_COMPLETION_INTERRUPT_START_ADDRESS: u16 = 0x0058u16;
-const TERMINAL_NEGATIVE_EDGE_INTERRUPT_START_ADDRESS: u16 = 0x0060u16;
+pub const INTERRUPT_DATA_SIZE: u8 = 0x8u8;
+pub const INTERRUPT_AREA_START_ADDRESS: u16 = 0x0040u16;
+pub const INTERRUPT_AREA_END_ADDRESS: u16 = 0x0067u16;
+pub const VERTICAL_BANKING_INTERRUPT_START_ADDRESS: u16 = 0x0040u16;
+pub const LCDC_INTERRUPT_START_ADDRESS: u16 = 0x0048u16;
+pub const TIMER_OVERFLOW_INTERRUPT_START_ADDRESS: u16 = 0x0050u16;
+pub const SERIAL_TRANSFER_COMPLETION_INTERRUPT_START_ADDRESS: u16 = 0x0058u16;
+pub const TERMINAL_NEGATIVE_EDGE_INTERRUPT_START_ADDRESS: u16 = 0x0060u16;
 
 // Header
-const HEADER_START_ADDRESS: u16 = 0x0100u16;
-const HEADER_END_ADDRESS: u16 = 0x014fu16;
-const INITIAL_INSTRUCTION_ADDRESS: u16 = 0x0100u16;
-const JUMP_INSTRUCTION_ADDRESS: u16 = 0x0101u16;
-const JUMP_TARGET_LOW_ADDRESS: u16 = 0x0102u16;
-const JUMP_TARGET_HIGH_ADDRESS: u16 = 0x0103u16;
+pub const HEADER_START_ADDRESS: u16 = 0x0100u16;
+pub const HEADER_END_ADDRESS: u16 = 0x014fu16;
+pub const INITIAL_INSTRUCTION_ADDRESS: u16 = 0x0100u16;
+pub const JUMP_INSTRUCTION_ADDRESS: u16 = 0x0101u16;
+pub const JUMP_TARGET_LOW_ADDRESS: u16 = 0x0102u16;
+pub const JUMP_TARGET_HIGH_ADDRESS: u16 = 0x0103u16;
+pub const LOGO_START_ADDRESS: u16 = 0x0104u16;
+pub const LOGO_END_ADDRESS: u16 = 0x0133u16;
 
 // Program area
-const PROGRAM_AREA_END_ADDRESS: u16 = 0x7fffu16;
+pub const PROGRAM_AREA_START_ADDRESS: u16 = 0x0000u16;
+pub const PROGRAM_AREA_END_ADDRESS: u16 = 0x7fffu16;
+pub const FIXED_BANK_END_ADDRESS: u16 = 0x3fffu16;
+pub const ROM_BANK_SIZE: usize = 0x4000usize;
+pub const SWITCHABLE_BANK_START_ADDRESS: u16 = 0x4000u16;
+
+// External RAM
+pub const EXTERNAL_RAM_START_ADDRESS: u16 = 0xa000u16;
+pub const EXTERNAL_RAM_END_ADDRESS: u16 = 0xbfffu16;
+pub const RAM_BANK_SIZE: usize = 0x2000usize;
 
 // Markers
-const CGB_COMPATIBILITY_ADDRESS: u16 = 0x0143u16;
-const GAME_TITLE_END_ADDRESS: u16 = 0x0142u16;
-const GAME_TITLE_START_ADDRESS: u16 = 0x0134u16;
-const NEW_MAKER_CODE_ADDRESS_HIGH: u16 = 0x0144u16;
-const NEW_MAKER_CODE_ADDRESS_LOW: u16 = 0x0145u16;
-const OLD_MAKER_CODE_ADDRESS: u16 = 0x014bu16;
-const USE_NEW_MAKER_CODE_VALUE: u16 = 0x33u16;
+pub const CARTRIDGE_TYPE_ADDRESS: u16 = 0x0147u16;
+pub const CGB_COMPATIBILITY_ADDRESS: u16 = 0x0143u16;
+pub const GAME_TITLE_END_ADDRESS: u16 = 0x0142u16;
+pub const GAME_TITLE_START_ADDRESS: u16 = 0x0134u16;
+pub const GLOBAL_CHECKSUM_HIGH_ADDRESS: u16 = 0x014eu16;
+pub const GLOBAL_CHECKSUM_LOW_ADDRESS: u16 = 0x014fu16;
+pub const HEADER_CHECKSUM_ADDRESS: u16 = 0x014du16;
+pub const HEADER_CHECKSUM_START_ADDRESS: u16 = 0x0134u16;
+pub const HEADER_CHECKSUM_END_ADDRESS: u16 = 0x014cu16;
+pub const NEW_MAKER_CODE_ADDRESS_HIGH: u16 = 0x0144u16;
+pub const NEW_MAKER_CODE_ADDRESS_LOW: u16 = 0x0145u16;
+pub const OLD_MAKER_CODE_ADDRESS: u16 = 0x014bu16;
+pub const RAM_SIZE_ADDRESS: u16 = 0x0149u16;
+pub const ROM_SIZE_ADDRESS: u16 = 0x0148u16;
+pub const SGB_FLAG_ADDRESS: u16 = 0x0146u16;
+pub const SGB_SUPPORT_VALUE: u8 = 0x03u8;
+pub const USE_NEW_MAKER_CODE_VALUE: u8 = 0x33u8;
+
+// The logo the boot ROM compares against before handing off to the cartridge
+pub const NINTENDO_LOGO: [u8; 48] = [
+    0xce, 0xed, 0x66, 0x66, 0xcc, 0x0d, 0x00, 0x0b, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0c, 0x00, 0x0d,
+    0x00, 0x08, 0x11, 0x1f, 0x88, 0x89, 0x00, 0x0e, 0xdc, 0xcc, 0x6e, 0xe6, 0xdd, 0xdd, 0xd9, 0x99,
+    0xbb, 0xbb, 0x67, 0x63, 0x6e, 0x0e, 0xec, 0xcc, 0xdd, 0xdc, 0x99, 0x9f, 0xbb, 0xb9, 0x33, 0x3e,
+];
 
 // Maps
 lazy_static! {
-    static ref NEW_MAKER_CODES: HashMap<u8, String> = {
+    pub static ref NEW_MAKER_CODES: HashMap<&'static str, String> = {
         let mut new_maker_codes = HashMap::new();
 
-        new_maker_codes.insert(0x00u8, String::from("none"));
-        new_maker_codes.insert(0x01u8, String::from("nintendo"));
-        new_maker_codes.insert(0x08u8, String::from("capcom"));
-        new_maker_codes.insert(0x13u8, String::from("electronic arts"));
-        new_maker_codes.insert(0x18u8, String::from("hudsonsoft"));
-        new_maker_codes.insert(0x19u8, String::from("b-ai"));
-        new_maker_codes.insert(0x20u8, String::from("kss"));
-        new_maker_codes.insert(0x22u8, String::from("pow"));
-        new_maker_codes.insert(0x24u8, String::from("pcm complete"));
-        new_maker_codes.insert(0x25u8, String::from("san-x"));
-        new_maker_codes.insert(0x28u8, String::from("kemco japan"));
-        new_maker_codes.insert(0x29u8, String::from("seta"));
-        new_maker_codes.insert(0x30u8, String::from("viacom"));
-        new_maker_codes.insert(0x31u8, String::from("nintendo"));
-        new_maker_codes.insert(0x32u8, String::from("bandia"));
-        new_maker_codes.insert(0x33u8, String::from("ocean/acclaim"));
-        new_maker_codes.insert(0x34u8, String::from("konami"));
-        new_maker_codes.insert(0x35u8, String::from("hector"));
-        new_maker_codes.insert(0x37u8, String::from("taito"));
-        new_maker_codes.insert(0x38u8, String::from("hudson"));
-        new_maker_codes.insert(0x39u8, String::from("banpresto"));
-        new_maker_codes.insert(0x41u8, String::from("ubi soft"));
-        new_maker_codes.insert(0x42u8, String::from("atlus"));
-        new_maker_codes.insert(0x44u8, String::from("malibu"));
-        new_maker_codes.insert(0x46u8, String::from("angel"));
-        new_maker_codes.insert(0x47u8, String::from("pullet-proof"));
-        new_maker_codes.insert(0x49u8, String::from("irem"));
-        new_maker_codes.insert(0x50u8, String::from("absolute"));
-        new_maker_codes.insert(0x51u8, String::from("acclaim"));
-        new_maker_codes.insert(0x52u8, String::from("activision"));
-        new_maker_codes.insert(0x53u8, String::from("american sammy"));
-        new_maker_codes.insert(0x54u8, String::from("konami"));
-        new_maker_codes.insert(0x55u8, String::from("hi tech entertainment"));
-        new_maker_codes.insert(0x56u8, String::from("ljn"));
-        new_maker_codes.insert(0x57u8, String::from("matchbox"));
-        new_maker_codes.insert(0x58u8, String::from("mattel"));
-        new_maker_codes.insert(0x59u8, String::from("milton bradley"));
-        new_maker_codes.insert(0x60u8, String::from("titus"));
-        new_maker_codes.insert(0x61u8, String::from("virgin"));
-        new_maker_codes.insert(0x64u8, String::from("lucasarts"));
-        new_maker_codes.insert(0x67u8, String::from("ocean"));
-        new_maker_codes.insert(0x69u8, String::from("electronic arts"));
-        new_maker_codes.insert(0x70u8, String::from("infogrames"));
-        new_maker_codes.insert(0x71u8, String::from("interplay"));
-        new_maker_codes.insert(0x72u8, String::from("broderbund"));
-        new_maker_codes.insert(0x73u8, String::from("sculptured"));
-        new_maker_codes.insert(0x75u8, String::from("sci"));
-        new_maker_codes.insert(0x78u8, String::from("t*hq"));
-        new_maker_codes.insert(0x79u8, String::from("accolade"));
-        new_maker_codes.insert(0x80u8, String::from("misawa"));
-        new_maker_codes.insert(0x83u8, String::from("lozc"));
-        new_maker_codes.insert(0x86u8, String::from("tokuma shoten i*"));
-        new_maker_codes.insert(0x87u8, String::from("tsukuda ori*"));
-        new_maker_codes.insert(0x91u8, String::from("chun soft"));
-        new_maker_codes.insert(0x92u8, String::from("video system"));
-        new_maker_codes.insert(0x93u8, String::from("ocean/acclaim"));
-        new_maker_codes.insert(0x95u8, String::from("varie"));
-        new_maker_codes.insert(0x96u8, String::from("yonezawa/s'pal"));
-        new_maker_codes.insert(0x97u8, String::from("kaneko"));
-        new_maker_codes.insert(0x99u8, String::from("pack in soft "));
+        new_maker_codes.insert("00", String::from("none"));
+        new_maker_codes.insert("01", String::from("nintendo"));
+        new_maker_codes.insert("08", String::from("capcom"));
+        new_maker_codes.insert("13", String::from("electronic arts"));
+        new_maker_codes.insert("18", String::from("hudsonsoft"));
+        new_maker_codes.insert("19", String::from("b-ai"));
+        new_maker_codes.insert("20", String::from("kss"));
+        new_maker_codes.insert("22", String::from("pow"));
+        new_maker_codes.insert("24", String::from("pcm complete"));
+        new_maker_codes.insert("25", String::from("san-x"));
+        new_maker_codes.insert("28", String::from("kemco japan"));
+        new_maker_codes.insert("29", String::from("seta"));
+        new_maker_codes.insert("30", String::from("viacom"));
+        new_maker_codes.insert("31", String::from("nintendo"));
+        new_maker_codes.insert("32", String::from("bandia"));
+        new_maker_codes.insert("33", String::from("ocean/acclaim"));
+        new_maker_codes.insert("34", String::from("konami"));
+        new_maker_codes.insert("35", String::from("hector"));
+        new_maker_codes.insert("37", String::from("taito"));
+        new_maker_codes.insert("38", String::from("hudson"));
+        new_maker_codes.insert("39", String::from("banpresto"));
+        new_maker_codes.insert("41", String::from("ubi soft"));
+        new_maker_codes.insert("42", String::from("atlus"));
+        new_maker_codes.insert("44", String::from("malibu"));
+        new_maker_codes.insert("46", String::from("angel"));
+        new_maker_codes.insert("47", String::from("pullet-proof"));
+        new_maker_codes.insert("49", String::from("irem"));
+        new_maker_codes.insert("50", String::from("absolute"));
+        new_maker_codes.insert("51", String::from("acclaim"));
+        new_maker_codes.insert("52", String::from("activision"));
+        new_maker_codes.insert("53", String::from("american sammy"));
+        new_maker_codes.insert("54", String::from("konami"));
+        new_maker_codes.insert("55", String::from("hi tech entertainment"));
+        new_maker_codes.insert("56", String::from("ljn"));
+        new_maker_codes.insert("57", String::from("matchbox"));
+        new_maker_codes.insert("58", String::from("mattel"));
+        new_maker_codes.insert("59", String::from("milton bradley"));
+        new_maker_codes.insert("60", String::from("titus"));
+        new_maker_codes.insert("61", String::from("virgin"));
+        new_maker_codes.insert("64", String::from("lucasarts"));
+        new_maker_codes.insert("67", String::from("ocean"));
+        new_maker_codes.insert("69", String::from("electronic arts"));
+        new_maker_codes.insert("70", String::from("infogrames"));
+        new_maker_codes.insert("71", String::from("interplay"));
+        new_maker_codes.insert("72", String::from("broderbund"));
+        new_maker_codes.insert("73", String::from("sculptured"));
+        new_maker_codes.insert("75", String::from("sci"));
+        new_maker_codes.insert("78", String::from("t*hq"));
+        new_maker_codes.insert("79", String::from("accolade"));
+        new_maker_codes.insert("80", String::from("misawa"));
+        new_maker_codes.insert("83", String::from("lozc"));
+        new_maker_codes.insert("86", String::from("tokuma shoten i*"));
+        new_maker_codes.insert("87", String::from("tsukuda ori*"));
+        new_maker_codes.insert("91", String::from("chun soft"));
+        new_maker_codes.insert("92", String::from("video system"));
+        new_maker_codes.insert("93", String::from("ocean/acclaim"));
+        new_maker_codes.insert("95", String::from("varie"));
+        new_maker_codes.insert("96", String::from("yonezawa/s'pal"));
+        new_maker_codes.insert("97", String::from("kaneko"));
+        new_maker_codes.insert("99", String::from("pack in soft "));
+        new_maker_codes.insert("A4", String::from("konami"));
 
         new_maker_codes
     };
+
+    /// Maker names for the single-byte code at 0x014B, used unless it's 0x33
+    /// and the two-character code at 0x0144 takes over.
+    pub static ref OLD_MAKER_CODES: HashMap<u8, String> = {
+        let mut old_maker_codes = HashMap::new();
+
+        old_maker_codes.insert(0x00u8, String::from("none"));
+        old_maker_codes.insert(0x01u8, String::from("nintendo"));
+        old_maker_codes.insert(0x08u8, String::from("capcom"));
+        old_maker_codes.insert(0x09u8, String::from("hot-b"));
+        old_maker_codes.insert(0x0au8, String::from("jaleco"));
+        old_maker_codes.insert(0x0bu8, String::from("coconuts japan"));
+        old_maker_codes.insert(0x0cu8, String::from("elite systems"));
+        old_maker_codes.insert(0x13u8, String::from("electronic arts"));
+        old_maker_codes.insert(0x18u8, String::from("hudsonsoft"));
+        old_maker_codes.insert(0x19u8, String::from("itc entertainment"));
+        old_maker_codes.insert(0x1au8, String::from("yanoman"));
+        old_maker_codes.insert(0x1du8, String::from("japan clary"));
+        old_maker_codes.insert(0x1fu8, String::from("virgin"));
+        old_maker_codes.insert(0x24u8, String::from("pcm complete"));
+        old_maker_codes.insert(0x25u8, String::from("san-x"));
+        old_maker_codes.insert(0x28u8, String::from("kotobuki systems"));
+        old_maker_codes.insert(0x29u8, String::from("seta"));
+        old_maker_codes.insert(0x30u8, String::from("infogrames"));
+        old_maker_codes.insert(0x31u8, String::from("nintendo"));
+        old_maker_codes.insert(0x32u8, String::from("bandai"));
+        old_maker_codes.insert(0x34u8, String::from("konami"));
+        old_maker_codes.insert(0x35u8, String::from("hector"));
+        old_maker_codes.insert(0x38u8, String::from("capcom"));
+        old_maker_codes.insert(0x39u8, String::from("banpresto"));
+        old_maker_codes.insert(0x3cu8, String::from("entertainment interactive"));
+        old_maker_codes.insert(0x3eu8, String::from("gremlin"));
+        old_maker_codes.insert(0x41u8, String::from("ubi soft"));
+        old_maker_codes.insert(0x42u8, String::from("atlus"));
+        old_maker_codes.insert(0x44u8, String::from("malibu"));
+        old_maker_codes.insert(0x46u8, String::from("angel"));
+        old_maker_codes.insert(0x47u8, String::from("spectrum holobyte"));
+        old_maker_codes.insert(0x49u8, String::from("irem"));
+        old_maker_codes.insert(0x4au8, String::from("virgin"));
+        old_maker_codes.insert(0x4du8, String::from("malibu"));
+        old_maker_codes.insert(0x4fu8, String::from("u.s. gold"));
+        old_maker_codes.insert(0x50u8, String::from("absolute"));
+        old_maker_codes.insert(0x51u8, String::from("acclaim"));
+        old_maker_codes.insert(0x52u8, String::from("activision"));
+        old_maker_codes.insert(0x53u8, String::from("american sammy"));
+        old_maker_codes.insert(0x54u8, String::from("gametek"));
+        old_maker_codes.insert(0x55u8, String::from("park place"));
+        old_maker_codes.insert(0x56u8, String::from("ljn"));
+        old_maker_codes.insert(0x57u8, String::from("matchbox"));
+        old_maker_codes.insert(0x59u8, String::from("milton bradley"));
+        old_maker_codes.insert(0x5au8, String::from("mindscape"));
+        old_maker_codes.insert(0x5bu8, String::from("romstar"));
+        old_maker_codes.insert(0x5cu8, String::from("naxat soft"));
+        old_maker_codes.insert(0x5du8, String::from("tradewest"));
+        old_maker_codes.insert(0x60u8, String::from("titus"));
+        old_maker_codes.insert(0x61u8, String::from("virgin"));
+        old_maker_codes.insert(0x67u8, String::from("ocean"));
+        old_maker_codes.insert(0x69u8, String::from("electronic arts"));
+        old_maker_codes.insert(0x6eu8, String::from("elite systems"));
+        old_maker_codes.insert(0x6fu8, String::from("electro brain"));
+        old_maker_codes.insert(0x70u8, String::from("infogrames"));
+        old_maker_codes.insert(0x71u8, String::from("interplay"));
+        old_maker_codes.insert(0x72u8, String::from("broderbund"));
+        old_maker_codes.insert(0x73u8, String::from("sculptured"));
+        old_maker_codes.insert(0x75u8, String::from("the sales curve"));
+        old_maker_codes.insert(0x78u8, String::from("t*hq"));
+        old_maker_codes.insert(0x79u8, String::from("accolade"));
+        old_maker_codes.insert(0x7au8, String::from("triffix entertainment"));
+        old_maker_codes.insert(0x7cu8, String::from("microprose"));
+        old_maker_codes.insert(0x7fu8, String::from("kemco"));
+        old_maker_codes.insert(0x80u8, String::from("misawa entertainment"));
+        old_maker_codes.insert(0x83u8, String::from("lozc"));
+        old_maker_codes.insert(0x86u8, String::from("tokuma shoten intermedia"));
+        old_maker_codes.insert(0x8bu8, String::from("bullet-proof software"));
+        old_maker_codes.insert(0x8cu8, String::from("vic tokai"));
+        old_maker_codes.insert(0x8eu8, String::from("ape"));
+        old_maker_codes.insert(0x8fu8, String::from("i'max"));
+        old_maker_codes.insert(0x91u8, String::from("chunsoft"));
+        old_maker_codes.insert(0x92u8, String::from("video system"));
+        old_maker_codes.insert(0x93u8, String::from("tsuburava"));
+        old_maker_codes.insert(0x95u8, String::from("varie"));
+        old_maker_codes.insert(0x96u8, String::from("yonezawa/s'pal"));
+        old_maker_codes.insert(0x97u8, String::from("kaneko"));
+        old_maker_codes.insert(0x99u8, String::from("arc"));
+        old_maker_codes.insert(0x9au8, String::from("nihon bussan"));
+        old_maker_codes.insert(0x9bu8, String::from("tecmo"));
+        old_maker_codes.insert(0x9cu8, String::from("imagineer"));
+        old_maker_codes.insert(0x9du8, String::from("banpresto"));
+        old_maker_codes.insert(0x9fu8, String::from("nova"));
+        old_maker_codes.insert(0xa1u8, String::from("hori electric"));
+        old_maker_codes.insert(0xa2u8, String::from("bandai"));
+        old_maker_codes.insert(0xa4u8, String::from("konami"));
+        old_maker_codes.insert(0xa6u8, String::from("kawada"));
+        old_maker_codes.insert(0xa7u8, String::from("takara"));
+        old_maker_codes.insert(0xa9u8, String::from("technos japan"));
+        old_maker_codes.insert(0xaau8, String::from("broderbund"));
+        old_maker_codes.insert(0xacu8, String::from("toei animation"));
+        old_maker_codes.insert(0xadu8, String::from("toho"));
+        old_maker_codes.insert(0xafu8, String::from("namco"));
+        old_maker_codes.insert(0xb0u8, String::from("acclaim"));
+        old_maker_codes.insert(0xb1u8, String::from("ascii or nexoft"));
+        old_maker_codes.insert(0xb2u8, String::from("bandai"));
+        old_maker_codes.insert(0xb4u8, String::from("enix"));
+        old_maker_codes.insert(0xb6u8, String::from("hal"));
+        old_maker_codes.insert(0xb7u8, String::from("snk"));
+        old_maker_codes.insert(0xb9u8, String::from("pony canyon"));
+        old_maker_codes.insert(0xbau8, String::from("culture brain"));
+        old_maker_codes.insert(0xbbu8, String::from("sunsoft"));
+        old_maker_codes.insert(0xbdu8, String::from("sony imagesoft"));
+        old_maker_codes.insert(0xbfu8, String::from("sammy"));
+        old_maker_codes.insert(0xc0u8, String::from("taito"));
+        old_maker_codes.insert(0xc2u8, String::from("kemco"));
+        old_maker_codes.insert(0xc3u8, String::from("squaresoft"));
+        old_maker_codes.insert(0xc4u8, String::from("tokuma shoten intermedia"));
+        old_maker_codes.insert(0xc5u8, String::from("data east"));
+        old_maker_codes.insert(0xc6u8, String::from("tonkin house"));
+        old_maker_codes.insert(0xc8u8, String::from("koei"));
+        old_maker_codes.insert(0xc9u8, String::from("ufl"));
+        old_maker_codes.insert(0xcau8, String::from("ultra"));
+        old_maker_codes.insert(0xcbu8, String::from("vap"));
+        old_maker_codes.insert(0xccu8, String::from("use"));
+        old_maker_codes.insert(0xcdu8, String::from("meldac"));
+        old_maker_codes.insert(0xceu8, String::from("pony canyon"));
+        old_maker_codes.insert(0xcfu8, String::from("angel"));
+        old_maker_codes.insert(0xd0u8, String::from("taito"));
+        old_maker_codes.insert(0xd1u8, String::from("sofel"));
+        old_maker_codes.insert(0xd2u8, String::from("quest"));
+        old_maker_codes.insert(0xd3u8, String::from("sigma enterprises"));
+        old_maker_codes.insert(0xd4u8, String::from("ask kodansha"));
+        old_maker_codes.insert(0xd6u8, String::from("naxat soft"));
+        old_maker_codes.insert(0xd7u8, String::from("copya systems"));
+        old_maker_codes.insert(0xd9u8, String::from("banpresto"));
+        old_maker_codes.insert(0xdau8, String::from("tomy"));
+        old_maker_codes.insert(0xdbu8, String::from("ljn"));
+        old_maker_codes.insert(0xddu8, String::from("ncs"));
+        old_maker_codes.insert(0xdeu8, String::from("human"));
+        old_maker_codes.insert(0xdfu8, String::from("altron"));
+        old_maker_codes.insert(0xe0u8, String::from("jaleco"));
+        old_maker_codes.insert(0xe1u8, String::from("towachiki"));
+        old_maker_codes.insert(0xe2u8, String::from("uutaka"));
+        old_maker_codes.insert(0xe3u8, String::from("varie"));
+        old_maker_codes.insert(0xe5u8, String::from("epoch"));
+        old_maker_codes.insert(0xe7u8, String::from("athena"));
+        old_maker_codes.insert(0xe8u8, String::from("asmik"));
+        old_maker_codes.insert(0xe9u8, String::from("natsume"));
+        old_maker_codes.insert(0xeau8, String::from("king records"));
+        old_maker_codes.insert(0xebu8, String::from("atlus"));
+        old_maker_codes.insert(0xecu8, String::from("epic/sony records"));
+        old_maker_codes.insert(0xeeu8, String::from("igs"));
+        old_maker_codes.insert(0xf0u8, String::from("a wave"));
+        old_maker_codes.insert(0xf3u8, String::from("extreme entertainment"));
+        old_maker_codes.insert(0xffu8, String::from("ljn"));
+
+        old_maker_codes
+    };
 }
 
-#[derive(ToPrimitive)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ToPrimitive)]
 #[repr(u8)]
 pub enum CbgCompatibility {
     CGBIncompatible = 0x00u8,
@@ -123,59 +309,141 @@ pub enum CbgCompatibility {
     CGBExclusive = 0xc0u8,
 }
 
+impl CbgCompatibility {
+    pub fn from_flag(value: u8) -> Self {
+        match value {
+            0xc0u8 => CbgCompatibility::CGBExclusive,
+            v if v & 0x80u8 > 0 => CbgCompatibility::CGBCompatible,
+            _ => CbgCompatibility::CGBIncompatible,
+        }
+    }
+}
+
+/// The ROM size as encoded in the cartridge header at 0x0148.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[repr(u8)]
 pub enum RomSize {
-    Size256Kilobits = 256 * 1000,      // 256Kb / 32KB
-    Size512Kilobits = 512 * 1000,      // 512Kb / 64KB
-    Size1Megabits = 1 * 1000 * 1000,   // 1Mb / 128KB
-    Size2Megabits = 2 * 1000 * 1000,   // 2Mb / 256KB
-    Size4Megabits = 4 * 1000 * 1000,   // 4Mb / 512KB
-    Size8Megabits = 8 * 1000 * 1000,   // 8Mb / 1MB
-    Size16Megabits = 16 * 1000 * 1000, // 160b / 2MB
-    Size32Megabits = 32 * 1000 * 1000, // 320b / 4MB
-    Size64Megabits = 64 * 1000 * 1000, // 640b / 8MB
+    Size256Kilobits = 0x00u8, // 256Kb / 32KB
+    Size512Kilobits = 0x01u8, // 512Kb / 64KB
+    Size1Megabits = 0x02u8,   // 1Mb / 128KB
+    Size2Megabits = 0x03u8,   // 2Mb / 256KB
+    Size4Megabits = 0x04u8,   // 4Mb / 512KB
+    Size8Megabits = 0x05u8,   // 8Mb / 1MB
+    Size16Megabits = 0x06u8,  // 16Mb / 2MB
+    Size32Megabits = 0x07u8,  // 32Mb / 4MB
+    Size64Megabits = 0x08u8,  // 64Mb / 8MB
 }
 
+impl RomSize {
+    pub fn banks(&self) -> usize {
+        2usize << (*self as usize)
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.banks() * ROM_BANK_SIZE
+    }
+}
+
+/// The external RAM size as encoded in the cartridge header at 0x0149.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq, ToPrimitive)]
+#[repr(u8)]
+pub enum RamSize {
+    None = 0x00u8,
+    Size16Kilobits = 0x01u8,  // 16Kb / 2KB
+    Size64Kilobits = 0x02u8,  // 64Kb / 8KB
+    Size256Kilobits = 0x03u8, // 256Kb / 32KB
+    Size1Megabits = 0x04u8,   // 1Mb / 128KB
+    Size512Kilobits = 0x05u8, // 512Kb / 64KB
+}
+
+impl RamSize {
+    pub fn bytes(&self) -> usize {
+        match self {
+            RamSize::None => 0usize,
+            RamSize::Size16Kilobits => 0x800usize,
+            RamSize::Size64Kilobits => RAM_BANK_SIZE,
+            RamSize::Size256Kilobits => 4 * RAM_BANK_SIZE,
+            RamSize::Size1Megabits => 16 * RAM_BANK_SIZE,
+            RamSize::Size512Kilobits => 8 * RAM_BANK_SIZE,
+        }
+    }
+}
+
+/// Builds ROM images with a valid header, mostly useful for tests.
 pub struct RomBuilder {
+    cartridge_type: CartridgeType,
     cgb_compatibility: CbgCompatibility,
     game_title: Vec<u8>,
     program_data: Vec<u8>,
+    ram_size: RamSize,
     rom_size: RomSize,
+    sgb_support: bool,
 }
 
 impl RomBuilder {
     pub fn new() -> Self {
         RomBuilder {
+            cartridge_type: CartridgeType::RomOnly,
             cgb_compatibility: CbgCompatibility::CGBCompatible,
             game_title: Vec::new(),
             program_data: Vec::new(),
+            ram_size: RamSize::None,
             rom_size: RomSize::Size256Kilobits,
+            sgb_support: false,
         }
     }
 
     pub fn build(&self) -> Vec<u8> {
-        let mut rom = vec![];
+        let mut rom = vec![0x00u8; self.rom_size.bytes()];
 
-        for i in 0u16..(rom.len() as u16) {
-            // Game title
-            if i >= GAME_TITLE_START_ADDRESS && i <= GAME_TITLE_END_ADDRESS {
-                let char_index = i - GAME_TITLE_START_ADDRESS;
+        // Program data starts at the entry point and may run past the header
+        for (i, value) in self.program_data.iter().enumerate() {
+            if let Some(location) = rom.get_mut(INITIAL_INSTRUCTION_ADDRESS as usize + i) {
+                *location = *value;
+            }
+        }
 
-                rom[i as usize] = self.game_title[char_index as usize];
+        // Logo
+        rom[LOGO_START_ADDRESS as usize..=LOGO_END_ADDRESS as usize].copy_from_slice(&NINTENDO_LOGO);
 
-                continue;
-            }
+        // Game title
+        for i in GAME_TITLE_START_ADDRESS..=GAME_TITLE_END_ADDRESS {
+            let char_index = (i - GAME_TITLE_START_ADDRESS) as usize;
 
-            // CGB compatibility
-            if i == CGB_COMPATIBILITY_ADDRESS {
-                rom[i as usize] = self.cgb_compatibility.to_u8().unwrap();
+            rom[i as usize] = self.game_title.get(char_index).copied().unwrap_or(0x00u8);
+        }
 
-                continue;
-            }
+        // CGB compatibility
+        rom[CGB_COMPATIBILITY_ADDRESS as usize] = self.cgb_compatibility.to_u8().unwrap();
+
+        // SGB functions also need the old maker code to defer to the new one
+        if self.sgb_support {
+            rom[SGB_FLAG_ADDRESS as usize] = SGB_SUPPORT_VALUE;
+            rom[OLD_MAKER_CODE_ADDRESS as usize] = USE_NEW_MAKER_CODE_VALUE;
         }
 
+        // Sizes and type
+        rom[CARTRIDGE_TYPE_ADDRESS as usize] = self.cartridge_type.to_u8().unwrap();
+        rom[ROM_SIZE_ADDRESS as usize] = self.rom_size.to_u8().unwrap();
+        rom[RAM_SIZE_ADDRESS as usize] = self.ram_size.to_u8().unwrap();
+
+        // Checksums
+        rom[HEADER_CHECKSUM_ADDRESS as usize] = header_checksum(&rom);
+
+        let [global_low, global_high] = global_checksum(&rom).to_le_bytes();
+
+        rom[GLOBAL_CHECKSUM_HIGH_ADDRESS as usize] = global_high;
+        rom[GLOBAL_CHECKSUM_LOW_ADDRESS as usize] = global_low;
+
         rom
     }
 
+    pub fn cartridge_type(&mut self, cartridge_type: CartridgeType) -> &mut Self {
+        self.cartridge_type = cartridge_type;
+
+        self
+    }
+
     pub fn cgb_compatibility(&mut self, cgb_compatibility: CbgCompatibility) -> &mut Self {
         self.cgb_compatibility = cgb_compatibility;
 
@@ -188,9 +456,50 @@ impl RomBuilder {
         self
     }
 
+    pub fn program_data(&mut self, program_data: Vec<u8>) -> &mut Self {
+        self.program_data = program_data;
+
+        self
+    }
+
+    pub fn ram_size(&mut self, ram_size: RamSize) -> &mut Self {
+        self.ram_size = ram_size;
+
+        self
+    }
+
     pub fn rom_size(&mut self, rom_size: RomSize) -> &mut Self {
         self.rom_size = rom_size;
 
         self
     }
+
+    pub fn sgb_support(&mut self, sgb_support: bool) -> &mut Self {
+        self.sgb_support = sgb_support;
+
+        self
+    }
+}
+
+impl Default for RomBuilder {
+    fn default() -> Self {
+        RomBuilder::new()
+    }
+}
+
+/// Computes the header checksum over 0x0134-0x014C, as verified by the boot
+/// ROM.
+pub fn header_checksum(rom: &[u8]) -> u8 {
+    rom[HEADER_CHECKSUM_START_ADDRESS as usize..=HEADER_CHECKSUM_END_ADDRESS as usize]
+        .iter()
+        .fold(0u8, |checksum, value| checksum.wrapping_sub(*value).wrapping_sub(1))
+}
+
+/// Computes the global checksum: the sum of every byte in the ROM except the
+/// two checksum bytes themselves.
+pub fn global_checksum(rom: &[u8]) -> u16 {
+    rom.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != GLOBAL_CHECKSUM_HIGH_ADDRESS as usize && *i != GLOBAL_CHECKSUM_LOW_ADDRESS as usize)
+        .fold(0u16, |checksum, (_, value)| checksum.wrapping_add(*value as u16))
 }
@@ -0,0 +1,61 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use crate::serial::LinkCable;
+
+/// Records every byte sent on the internal clock, optionally echoing it to
+/// stdout. Test ROMs commonly report their results this way.
+///
+/// Clones share the same buffer, so a copy can be kept to read the output
+/// once the original is plugged into an emulator.
+#[derive(Clone, Default)]
+pub struct CaptureLinkCable {
+    captured: Arc<Mutex<Vec<u8>>>,
+    echo: bool,
+}
+
+impl CaptureLinkCable {
+    pub fn new() -> Self {
+        CaptureLinkCable {
+            captured: Arc::new(Mutex::new(Vec::new())),
+            echo: false,
+        }
+    }
+
+    /// A cable that also writes each byte to stdout as it arrives.
+    pub fn stdout() -> Self {
+        CaptureLinkCable {
+            echo: true,
+            ..CaptureLinkCable::new()
+        }
+    }
+
+    pub fn captured(&self) -> Vec<u8> {
+        self.captured.lock().unwrap().clone()
+    }
+
+    pub fn captured_string(&self) -> String {
+        String::from_utf8_lossy(&self.captured()).into_owned()
+    }
+
+    pub fn clear(&self) {
+        self.captured.lock().unwrap().clear();
+    }
+}
+
+impl LinkCable for CaptureLinkCable {
+    fn transfer(&mut self, outgoing: u8) -> u8 {
+        self.captured.lock().unwrap().push(outgoing);
+
+        if self.echo {
+            let mut stdout = io::stdout();
+
+            let _ = stdout.write_all(&[outgoing]);
+            let _ = stdout.flush();
+        }
+
+        0xffu8
+    }
+}
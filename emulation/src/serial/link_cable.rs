@@ -0,0 +1,28 @@
+use std::io;
+
+/// What the serial port sees of whatever is plugged into it.
+///
+/// Transfers are exchanged a byte at a time. The side driving the clock calls
+/// `transfer` once it has shifted all 8 bits, while a side waiting on an
+/// external clock polls `receive` every cycle until the other end delivers.
+/// `step` is called once per machine cycle regardless, so cables joining two
+/// emulators can keep them in time.
+pub trait LinkCable {
+    /// Sends a byte shifted out on this side's clock, returning the byte
+    /// shifted in from the other end. Nothing connected reads as 0xFF.
+    fn transfer(&mut self, outgoing: u8) -> u8;
+
+    /// The error that cut the cable off, for cables that can fail. The
+    /// frontend decides how to report it.
+    fn last_error(&self) -> Option<&io::Error> {
+        None
+    }
+
+    /// Offers a byte to be clocked out by the other end, returning the byte
+    /// received once the other end has driven a transfer.
+    fn receive(&mut self, _outgoing: u8) -> Option<u8> {
+        None
+    }
+
+    fn step(&mut self) {}
+}
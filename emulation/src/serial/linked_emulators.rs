@@ -0,0 +1,196 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{instruction::OpResult, serial::LinkCable, Emulator};
+
+/// A byte one side offers while waiting on the external clock.
+#[derive(Clone, Copy)]
+struct Offer {
+    byte: u8,
+    // First and latest cycles it was polled on
+    since: usize,
+    last: usize,
+}
+
+/// What both ends of the cable share. Each side's clock counts the cycles it
+/// has stepped through.
+#[derive(Default)]
+struct LinkState {
+    clocks: [usize; 2],
+    deliveries: [Option<(usize, u8)>; 2],
+    offers: [Option<Offer>; 2],
+}
+
+impl LinkState {
+    /// Whether a side was still waiting on the external clock as of its
+    /// latest cycle.
+    fn waiting(&self, side: usize) -> bool {
+        self.offers[side].is_some_and(|offer| offer.last + 1 == self.clocks[side])
+    }
+
+    /// Whether a side was waiting with its offer on the given cycle. A side
+    /// that hasn't reached it yet is taken to still be waiting if it was on
+    /// its latest cycle.
+    fn offer_at(&self, side: usize, cycle: usize) -> Option<u8> {
+        let offer = self.offers[side]?;
+
+        let waiting = offer.last >= cycle || self.waiting(side);
+
+        if offer.since <= cycle && waiting {
+            Some(offer.byte)
+        } else {
+            None
+        }
+    }
+}
+
+/// One end of a cable between two emulators in the same process.
+struct InProcessLinkCable {
+    side: usize,
+    state: Rc<RefCell<LinkState>>,
+}
+
+impl LinkCable for InProcessLinkCable {
+    fn receive(&mut self, outgoing: u8) -> Option<u8> {
+        let mut state = self.state.borrow_mut();
+        let now = state.clocks[self.side];
+
+        if let Some((cycle, incoming)) = state.deliveries[self.side] {
+            if now >= cycle {
+                state.deliveries[self.side] = None;
+                state.offers[self.side] = None;
+
+                return Some(incoming);
+            }
+        }
+
+        // A gap in polling or a new byte starts a new offer
+        state.offers[self.side] = match state.offers[self.side] {
+            Some(offer) if offer.byte == outgoing && offer.last + 1 == now => Some(Offer { last: now, ..offer }),
+            _ => Some(Offer {
+                byte: outgoing,
+                since: now,
+                last: now,
+            }),
+        };
+
+        None
+    }
+
+    fn step(&mut self) {
+        self.state.borrow_mut().clocks[self.side] += 1;
+    }
+
+    fn transfer(&mut self, outgoing: u8) -> u8 {
+        let mut state = self.state.borrow_mut();
+        let now = state.clocks[self.side];
+        let other = 1 - self.side;
+
+        match state.offer_at(other, now) {
+            Some(incoming) => {
+                state.deliveries[other] = Some((now, outgoing));
+                state.offers[other] = None;
+
+                incoming
+            },
+            None => 0xffu8,
+        }
+    }
+}
+
+/// Two emulators joined by a link cable and run in lockstep, for multiplayer
+/// without threads or sockets.
+///
+/// Each step runs one instruction on whichever emulator is behind, so
+/// neither gets more than an instruction ahead. Bytes are swapped on the
+/// cycle the clocking side finishes its transfer, against what the other
+/// side offered on that same cycle. The waiting side receives on that cycle
+/// too, unless its current instruction had already carried it past, in
+/// which case it receives on its next one. The result only depends on the
+/// two programs, so every run plays out the same.
+pub struct LinkedEmulators {
+    emulators: [Emulator; 2],
+    state: Rc<RefCell<LinkState>>,
+}
+
+impl LinkedEmulators {
+    /// Plugs the cable into both emulators' serial ports, replacing any cable
+    /// already connected.
+    pub fn new(first: Emulator, second: Emulator) -> Self {
+        let state = Rc::new(RefCell::new(LinkState::default()));
+        let mut emulators = [first, second];
+
+        for (side, emulator) in emulators.iter_mut().enumerate() {
+            state.borrow_mut().clocks[side] = emulator.cycles();
+
+            emulator.connect_link_cable(Box::new(InProcessLinkCable {
+                side,
+                state: state.clone(),
+            }));
+        }
+
+        LinkedEmulators { emulators, state }
+    }
+
+    /// The emulator behind the other. When level, a side waiting on the
+    /// external clock goes second, so a transfer finishing on this cycle
+    /// reaches it in time.
+    fn behind(&self) -> usize {
+        let first = self.emulators[0].cycles();
+        let second = self.emulators[1].cycles();
+
+        if second < first || (second == first && self.state.borrow().waiting(0)) {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.emulators[0].cycles().min(self.emulators[1].cycles())
+    }
+
+    pub fn first(&self) -> &Emulator {
+        &self.emulators[0]
+    }
+
+    pub fn first_mut(&mut self) -> &mut Emulator {
+        &mut self.emulators[0]
+    }
+
+    pub fn into_inner(self) -> (Emulator, Emulator) {
+        let [first, second] = self.emulators;
+
+        (first, second)
+    }
+
+    /// Steps until both emulators have run at least the given number of
+    /// cycles more.
+    pub fn run_cycles(&mut self, cycles: usize) -> OpResult {
+        let target = self.cycles() + cycles;
+
+        while self.cycles() < target {
+            self.step()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn second(&self) -> &Emulator {
+        &self.emulators[1]
+    }
+
+    pub fn second_mut(&mut self) -> &mut Emulator {
+        &mut self.emulators[1]
+    }
+
+    /// Runs one step of whichever emulator is behind.
+    pub fn step(&mut self) -> OpResult {
+        let side = self.behind();
+
+        // A stopped CPU idles without stepping the serial port, so bring the
+        // cable's clock back in line first
+        self.state.borrow_mut().clocks[side] = self.emulators[side].cycles();
+
+        self.emulators[side].process_opcode()
+    }
+}
@@ -0,0 +1,18 @@
+use crate::serial::LinkCable;
+
+/// A cable with its output wired straight back into its input, so every byte
+/// sent is received again.
+#[derive(Clone, Copy, Default)]
+pub struct LoopbackLinkCable {}
+
+impl LoopbackLinkCable {
+    pub fn new() -> Self {
+        LoopbackLinkCable {}
+    }
+}
+
+impl LinkCable for LoopbackLinkCable {
+    fn transfer(&mut self, outgoing: u8) -> u8 {
+        outgoing
+    }
+}
@@ -0,0 +1,13 @@
+mod capture_link_cable;
+mod link_cable;
+mod linked_emulators;
+mod loopback_link_cable;
+mod serial_transfer_component;
+mod socket_link_cable;
+
+pub use capture_link_cable::CaptureLinkCable;
+pub use link_cable::LinkCable;
+pub use linked_emulators::LinkedEmulators;
+pub use loopback_link_cable::LoopbackLinkCable;
+pub use serial_transfer_component::SerialTransferComponent;
+pub use socket_link_cable::{SocketLinkCable, DEFAULT_SYNC_INTERVAL};
@@ -0,0 +1,173 @@
+use crate::{
+    interrupt::Interrupt,
+    memory_component::{MemoryComponent, MemoryError},
+    serial::LinkCable,
+};
+
+const SB_ADDRESS: u16 = 0xff01u16;
+const SC_ADDRESS: u16 = 0xff02u16;
+
+const TRANSFER_START_BIT: u8 = 0b10000000;
+const INTERNAL_CLOCK_BIT: u8 = 0b00000001;
+const SC_UNUSED_BITS: u8 = 0b01111110;
+
+// The internal clock runs at 8192 Hz
+const CYCLES_PER_BIT: usize = 128usize;
+const BITS_PER_TRANSFER: usize = 8usize;
+
+/// The serial port: SB (0xFF01) holds the byte being exchanged and SC
+/// (0xFF02) starts a transfer and picks the clock.
+///
+/// On the internal clock the port shifts 8 bits at 8192 Hz, then swaps bytes
+/// with the link cable. On the external clock it waits for the other end to
+/// drive a transfer. Either way, finishing raises the serial interrupt.
+///
+/// The internal clock keeps its rate at CGB double speed, taking twice as
+/// many machine cycles per bit.
+pub struct SerialTransferComponent {
+    bits_remaining: usize,
+    cycles: usize,
+    double_speed: bool,
+    link_cable: Option<Box<dyn LinkCable>>,
+    sb: u8,
+    sc: u8,
+}
+
+impl SerialTransferComponent {
+    pub fn new() -> Self {
+        SerialTransferComponent {
+            bits_remaining: 0usize,
+            cycles: 0usize,
+            double_speed: false,
+            link_cable: None,
+            sb: 0x00u8,
+            sc: 0x00u8,
+        }
+    }
+
+    /// Plugs in a cable, replacing any already connected.
+    pub fn connect(&mut self, link_cable: Box<dyn LinkCable>) {
+        self.link_cable = Some(link_cable);
+    }
+
+    pub fn disconnect(&mut self) -> Option<Box<dyn LinkCable>> {
+        self.link_cable.take()
+    }
+
+    pub fn link_cable(&self) -> Option<&dyn LinkCable> {
+        self.link_cable.as_deref()
+    }
+
+    pub fn transferring(&self) -> bool {
+        self.sc & TRANSFER_START_BIT > 0
+    }
+
+    fn internal_clock(&self) -> bool {
+        self.sc & INTERNAL_CLOCK_BIT > 0
+    }
+
+    fn finish_transfer(&mut self, incoming: u8) -> u8 {
+        self.sb = incoming;
+        self.sc &= !TRANSFER_START_BIT;
+
+        Interrupt::Serial as u8
+    }
+
+    fn cycles_per_bit(&self) -> usize {
+        if self.double_speed {
+            CYCLES_PER_BIT * 2
+        } else {
+            CYCLES_PER_BIT
+        }
+    }
+
+    /// Advances a transfer in progress, returning the serial interrupt when
+    /// it completes.
+    fn step_transfer(&mut self) -> u8 {
+        if !self.transferring() {
+            return 0x00u8;
+        }
+
+        if !self.internal_clock() {
+            let incoming = self.link_cable.as_mut().and_then(|c| c.receive(self.sb));
+
+            return match incoming {
+                Some(incoming) => self.finish_transfer(incoming),
+                None => 0x00u8,
+            };
+        }
+
+        self.cycles += 1;
+
+        if self.cycles < self.cycles_per_bit() {
+            return 0x00u8;
+        }
+
+        self.cycles = 0;
+        self.bits_remaining -= 1;
+
+        if self.bits_remaining > 0 {
+            return 0x00u8;
+        }
+
+        let outgoing = self.sb;
+
+        let incoming = match self.link_cable.as_mut() {
+            Some(link_cable) => link_cable.transfer(outgoing),
+            None => 0xffu8,
+        };
+
+        self.finish_transfer(incoming)
+    }
+}
+
+impl Default for SerialTransferComponent {
+    fn default() -> Self {
+        SerialTransferComponent::new()
+    }
+}
+
+impl MemoryComponent for SerialTransferComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![SB_ADDRESS, SC_ADDRESS]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            SB_ADDRESS => Ok(self.sb),
+            SC_ADDRESS => Ok(SC_UNUSED_BITS | self.sc),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn set_double_speed(&mut self, double_speed: bool) {
+        self.double_speed = double_speed;
+    }
+
+    fn step(&mut self) -> u8 {
+        let requests = self.step_transfer();
+
+        if let Some(link_cable) = self.link_cable.as_mut() {
+            link_cable.step();
+        }
+
+        requests
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            SB_ADDRESS => self.sb = value,
+            SC_ADDRESS => {
+                self.sc = value & !SC_UNUSED_BITS;
+
+                if self.transferring() {
+                    self.bits_remaining = BITS_PER_TRANSFER;
+                    self.cycles = 0;
+                }
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+
+use crate::serial::LinkCable;
+
+/// Cycles between synchronisations, one byte at the normal serial clock.
+pub const DEFAULT_SYNC_INTERVAL: usize = 1024usize;
+
+// Offer present, offer id and byte, then the number of bytes sent
+const HEADER_SIZE: usize = 7usize;
+// Offer id and byte
+const SENT_SIZE: usize = 5usize;
+
+/// A link cable joining two emulators, usually in separate processes, over a
+/// stream socket.
+///
+/// Both ends stop every `sync_interval` cycles and swap messages, so neither
+/// can run ahead of the other. A side waiting on the external clock publishes
+/// the byte it offers at each sync, and the other side's internal clock
+/// transfers consume the last offer it heard about. The byte sent back is
+/// delivered at the next sync. Since everything depends only on what was
+/// exchanged at sync points, both sides see the same result on every run.
+///
+/// Both ends must use the same sync interval.
+pub struct SocketLinkCable<S: Read + Write> {
+    connected: bool,
+    consumed_peer_offer: u32,
+    cycles: usize,
+    incoming: Option<u8>,
+    last_error: Option<io::Error>,
+    offer: Option<u8>,
+    offer_id: u32,
+    peer_offer: Option<(u32, u8)>,
+    sent: Vec<(u32, u8)>,
+    stream: S,
+    sync_interval: usize,
+}
+
+impl<S: Read + Write> SocketLinkCable<S> {
+    pub fn new(stream: S) -> Self {
+        SocketLinkCable {
+            connected: true,
+            consumed_peer_offer: 0u32,
+            cycles: 0usize,
+            incoming: None,
+            last_error: None,
+            offer: None,
+            offer_id: 1u32,
+            peer_offer: None,
+            sent: Vec::new(),
+            stream,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+        }
+    }
+
+    /// Whether the other end is still there. After an I/O error the cable
+    /// behaves as if it had been unplugged, and `last_error` says why.
+    pub fn connected(&self) -> bool {
+        self.connected
+    }
+
+    pub fn with_sync_interval(mut self, sync_interval: usize) -> Self {
+        self.sync_interval = sync_interval.max(1);
+
+        self
+    }
+
+    fn exchange_messages(&mut self) -> io::Result<()> {
+        let mut message = Vec::with_capacity(HEADER_SIZE + self.sent.len() * SENT_SIZE);
+
+        match self.offer {
+            Some(byte) => {
+                message.push(0x01u8);
+                message.extend_from_slice(&self.offer_id.to_le_bytes());
+                message.push(byte);
+            },
+            None => message.extend_from_slice(&[0x00u8; 6]),
+        };
+
+        message.push(self.sent.len() as u8);
+
+        for (id, byte) in self.sent.drain(..) {
+            message.extend_from_slice(&id.to_le_bytes());
+            message.push(byte);
+        }
+
+        self.stream.write_all(&message)?;
+        self.stream.flush()?;
+
+        let mut header = [0x00u8; HEADER_SIZE];
+
+        self.stream.read_exact(&mut header)?;
+
+        let peer_offer_id = u32::from_le_bytes([header[1], header[2], header[3], header[4]]);
+
+        self.peer_offer = if header[0] > 0 && peer_offer_id != self.consumed_peer_offer {
+            Some((peer_offer_id, header[5]))
+        } else {
+            None
+        };
+
+        let mut received = vec![0x00u8; header[6] as usize * SENT_SIZE];
+
+        self.stream.read_exact(&mut received)?;
+
+        // Only a byte clocked against our current offer arrives; anything
+        // sent while we weren't listening is lost, as on real hardware
+        for entry in received.chunks(SENT_SIZE) {
+            let id = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+
+            if id == self.offer_id && self.incoming.is_none() {
+                self.incoming = Some(entry[4]);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync(&mut self) {
+        if !self.connected {
+            return;
+        }
+
+        if let Err(e) = self.exchange_messages() {
+            self.connected = false;
+            self.last_error = Some(e);
+            self.peer_offer = None;
+        }
+    }
+}
+
+impl SocketLinkCable<TcpStream> {
+    pub fn connect_tcp<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+
+        stream.set_nodelay(true)?;
+
+        Ok(SocketLinkCable::new(stream))
+    }
+
+    /// Waits for the other end to connect.
+    pub fn listen_tcp<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let (stream, _) = TcpListener::bind(address)?.accept()?;
+
+        stream.set_nodelay(true)?;
+
+        Ok(SocketLinkCable::new(stream))
+    }
+}
+
+#[cfg(unix)]
+impl SocketLinkCable<UnixStream> {
+    pub fn connect_unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(SocketLinkCable::new(UnixStream::connect(path)?))
+    }
+
+    /// Waits for the other end to connect.
+    pub fn listen_unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let (stream, _) = UnixListener::bind(path)?.accept()?;
+
+        Ok(SocketLinkCable::new(stream))
+    }
+}
+
+impl<S: Read + Write + 'static> LinkCable for SocketLinkCable<S> {
+    fn last_error(&self) -> Option<&io::Error> {
+        self.last_error.as_ref()
+    }
+
+    fn receive(&mut self, outgoing: u8) -> Option<u8> {
+        match self.incoming.take() {
+            Some(incoming) => {
+                // The next wait is a new offer
+                self.offer_id = self.offer_id.wrapping_add(1);
+
+                Some(incoming)
+            },
+            None => {
+                self.offer = Some(outgoing);
+
+                None
+            },
+        }
+    }
+
+    fn step(&mut self) {
+        self.cycles += 1;
+
+        if self.cycles >= self.sync_interval {
+            self.cycles = 0;
+
+            self.sync();
+        }
+
+        self.offer = None;
+    }
+
+    fn transfer(&mut self, outgoing: u8) -> u8 {
+        match self.peer_offer.take() {
+            Some((id, incoming)) => {
+                self.consumed_peer_offer = id;
+                self.sent.push((id, outgoing));
+
+                incoming
+            },
+            None => 0xffu8,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod sgb_command;
+mod sgb_component;
+
+pub use sgb_command::{SgbCommand, SgbMask};
+pub use sgb_component::{SgbComponent, SGB_SCREEN_HEIGHT, SGB_SCREEN_WIDTH};
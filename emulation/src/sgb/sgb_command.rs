@@ -0,0 +1,43 @@
+/// The SGB command codes, sent in the top 5 bits of a command's first byte.
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum SgbCommand {
+    Pal01 = 0x00,
+    Pal23 = 0x01,
+    Pal03 = 0x02,
+    Pal12 = 0x03,
+    AttrBlk = 0x04,
+    AttrLin = 0x05,
+    AttrDiv = 0x06,
+    AttrChr = 0x07,
+    Sound = 0x08,
+    SouTrn = 0x09,
+    PalSet = 0x0a,
+    PalTrn = 0x0b,
+    AtrcEn = 0x0c,
+    TestEn = 0x0d,
+    IconEn = 0x0e,
+    DataSnd = 0x0f,
+    DataTrn = 0x10,
+    MltReq = 0x11,
+    Jump = 0x12,
+    ChrTrn = 0x13,
+    PctTrn = 0x14,
+    AttrTrn = 0x15,
+    AttrSet = 0x16,
+    MaskEn = 0x17,
+    ObjTrn = 0x18,
+}
+
+/// What MASK_EN shows in place of the game screen.
+#[derive(Clone, Copy, Debug, Default, Eq, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum SgbMask {
+    #[default]
+    None = 0x00,
+    /// Keeps showing the last frame from before the mask.
+    Freeze = 0x01,
+    Black = 0x02,
+    /// Fills the screen with color 0.
+    Color0 = 0x03,
+}
@@ -0,0 +1,570 @@
+use num::FromPrimitive;
+
+use crate::{
+    memory_component::{MemoryComponent, MemoryError},
+    ppu::{SCREEN_HEIGHT, SCREEN_WIDTH},
+    sgb::{SgbCommand, SgbMask},
+};
+
+pub const SGB_SCREEN_WIDTH: usize = 256usize;
+pub const SGB_SCREEN_HEIGHT: usize = 224usize;
+
+// Where the game screen sits within the border
+const SCREEN_LEFT: usize = 48usize;
+const SCREEN_TOP: usize = 40usize;
+
+// The screen in 8x8 cells, each with its own palette
+const CELLS_WIDE: usize = SCREEN_WIDTH / 8;
+const CELLS_HIGH: usize = SCREEN_HEIGHT / 8;
+const CELL_COUNT: usize = CELLS_WIDE * CELLS_HIGH;
+const CELL_MASK: u8 = 0b00011111;
+
+// Select lines, as written to P1
+const P14_BIT: u8 = 0b00010000;
+const P15_BIT: u8 = 0b00100000;
+const SELECT_MASK: u8 = P14_BIT | P15_BIT;
+
+const PACKET_SIZE: usize = 16usize;
+const PACKET_BITS: usize = PACKET_SIZE * 8;
+const LENGTH_MASK: u8 = 0b00000111;
+const COMMAND_SHIFT: u8 = 3u8;
+
+const COLOR_MASK: u16 = 0x7fffu16;
+const PALETTE_MASK: u8 = 0b11;
+const SYSTEM_PALETTE_MASK: u16 = 0x01ffu16;
+const ATTRIBUTE_FILE_MASK: u8 = 0b00111111;
+const APPLY_ATTRIBUTE_FILE_BIT: u8 = 0b10000000;
+const CANCEL_MASK_BIT: u8 = 0b01000000;
+
+// The power-on palette, before the game sends any of its own
+const DEFAULT_PALETTE: [u16; 4] = [0x67bfu16, 0x265bu16, 0x10b5u16, 0x2866u16];
+
+// VRAM transfers send 4KB as the first 256 tiles on screen
+const TRANSFER_SIZE: usize = 0x1000usize;
+const SYSTEM_PALETTE_COUNT: usize = 512usize;
+const ATTRIBUTE_FILE_COUNT: usize = 45usize;
+const ATTRIBUTE_FILE_SIZE: usize = CELL_COUNT / 4;
+
+// The border is 32x28 tiles, with up to 256 four-bit tiles and four
+// palettes of 16 colors, the first of them transparent
+const BORDER_TILE_SIZE: usize = 32usize;
+const BORDER_TILE_COUNT: usize = 256usize;
+const BORDER_MAP_WIDTH: usize = 32usize;
+const BORDER_MAP_SIZE: usize = 0x800usize;
+const BORDER_PALETTE_COUNT: usize = 4usize;
+const BORDER_TILE_MASK: u16 = 0x00ffu16;
+const BORDER_PALETTE_SHIFT: u16 = 10u16;
+const BORDER_X_FLIP_BIT: u16 = 0x4000u16;
+const BORDER_Y_FLIP_BIT: u16 = 0x8000u16;
+
+// ATTR_BLK control bits
+const BLOCK_INSIDE_BIT: u8 = 0b001;
+const BLOCK_LINE_BIT: u8 = 0b010;
+const BLOCK_OUTSIDE_BIT: u8 = 0b100;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum VramTransfer {
+    AttributeFiles,
+    BorderMap,
+    /// Border tiles 0x00-0x7F, or 0x80-0xFF.
+    BorderTiles(usize),
+    SystemPalettes,
+}
+
+/// The Super Game Boy's side of the cartridge, which takes command packets
+/// through the joypad register and draws the game inside a border on the TV.
+///
+/// A packet is 16 bytes sent a bit at a time, lowest first: writing P1 with
+/// both select lines low starts one, then pulling P15 low sends a 1 and P14
+/// low a 0, with both lines going high again after each. A 0 after the 128th
+/// bit ends it. The first byte of a command holds its code and how many
+/// packets it takes. A packet takes well under a frame to send, so one still
+/// unfinished a frame after it started is dropped, rather than completed from
+/// the pulses of ordinary joypad reads.
+///
+/// Larger transfers, like border tiles or the system palettes, are sent as
+/// the first 256 tiles of the next frame the game draws.
+///
+/// On screen, each 8x8 cell of the game uses one of four palettes, chosen by
+/// the ATTR commands, with color 0 shared between them all. The border goes
+/// on top, around the game, except where its colors are transparent.
+///
+/// Commands only take effect for cartridges whose header asks for SGB
+/// functions. Sound and the SNES-side commands are ignored.
+pub struct SgbComponent {
+    attribute_files: Vec<u8>,
+    attributes: [u8; CELL_COUNT],
+    bit: Option<usize>,
+    border_map: Vec<u8>,
+    border_palettes: [[u16; 16]; BORDER_PALETTE_COUNT],
+    border_tiles: Vec<u8>,
+    command: Vec<u8>,
+    enabled: bool,
+    frames: usize,
+    lines: u8,
+    mask: SgbMask,
+    packet: [u8; PACKET_SIZE],
+    packet_frame: usize,
+    palettes: [[u16; 4]; 4],
+    player: u8,
+    players: u8,
+    screen: Vec<u8>,
+    system_palettes: Vec<u16>,
+    transfer: Option<VramTransfer>,
+}
+
+impl SgbComponent {
+    pub fn new() -> Self {
+        SgbComponent {
+            attribute_files: vec![0x00u8; ATTRIBUTE_FILE_COUNT * ATTRIBUTE_FILE_SIZE],
+            attributes: [0x00u8; CELL_COUNT],
+            bit: None,
+            border_map: vec![0x00u8; BORDER_MAP_SIZE],
+            border_palettes: [[0x0000u16; 16]; BORDER_PALETTE_COUNT],
+            border_tiles: vec![0x00u8; BORDER_TILE_COUNT * BORDER_TILE_SIZE],
+            command: Vec::new(),
+            enabled: false,
+            frames: 0usize,
+            lines: SELECT_MASK,
+            mask: SgbMask::None,
+            packet: [0x00u8; PACKET_SIZE],
+            packet_frame: 0usize,
+            palettes: [DEFAULT_PALETTE; 4],
+            player: 0x00u8,
+            players: 1u8,
+            screen: vec![0x00u8; SCREEN_WIDTH * SCREEN_HEIGHT],
+            system_palettes: vec![0x0000u16; SYSTEM_PALETTE_COUNT * 4],
+            transfer: None,
+        }
+    }
+
+    fn apply_attribute_file(&mut self, file: u8) {
+        let file = (file & ATTRIBUTE_FILE_MASK) as usize;
+
+        if file >= ATTRIBUTE_FILE_COUNT {
+            return;
+        }
+
+        let data = &self.attribute_files[file * ATTRIBUTE_FILE_SIZE..(file + 1) * ATTRIBUTE_FILE_SIZE];
+
+        for (cell, attribute) in self.attributes.iter_mut().enumerate() {
+            *attribute = (data[cell / 4] >> (6 - (cell % 4) * 2)) & PALETTE_MASK;
+        }
+    }
+
+    fn attribute_block(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+
+        for set in data[2..].chunks_exact(6).take(count) {
+            let control = set[0];
+            let inside = set[1] & PALETTE_MASK;
+            let outside = (set[1] >> 4) & PALETTE_MASK;
+
+            // Changing only one side takes the line along with it
+            let line = match control & (BLOCK_INSIDE_BIT | BLOCK_LINE_BIT | BLOCK_OUTSIDE_BIT) {
+                BLOCK_INSIDE_BIT => Some(inside),
+                BLOCK_OUTSIDE_BIT => Some(outside),
+                _ if control & BLOCK_LINE_BIT > 0 => Some((set[1] >> 2) & PALETTE_MASK),
+                _ => None,
+            };
+
+            let (left, top, right, bottom) =
+                (set[2] & CELL_MASK, set[3] & CELL_MASK, set[4] & CELL_MASK, set[5] & CELL_MASK);
+
+            for y in 0..CELLS_HIGH as u8 {
+                for x in 0..CELLS_WIDE as u8 {
+                    let palette = if x < left || x > right || y < top || y > bottom {
+                        Some(outside).filter(|_| control & BLOCK_OUTSIDE_BIT > 0)
+                    } else if x == left || x == right || y == top || y == bottom {
+                        line
+                    } else {
+                        Some(inside).filter(|_| control & BLOCK_INSIDE_BIT > 0)
+                    };
+
+                    if let Some(palette) = palette {
+                        self.attributes[y as usize * CELLS_WIDE + x as usize] = palette;
+                    }
+                }
+            }
+        }
+    }
+
+    fn attribute_characters(&mut self, data: &[u8]) {
+        let mut x = (data[1] & CELL_MASK) as usize;
+        let mut y = (data[2] & CELL_MASK) as usize;
+        let count = (u16::from_le_bytes([data[3], data[4]]) as usize).min(CELL_COUNT);
+        let vertical = data[5] & 0b1 > 0;
+
+        for index in 0..count {
+            let Some(byte) = data.get(6 + index / 4) else {
+                return;
+            };
+
+            if x < CELLS_WIDE && y < CELLS_HIGH {
+                self.attributes[y * CELLS_WIDE + x] = (byte >> (6 - (index % 4) * 2)) & PALETTE_MASK;
+            }
+
+            if vertical {
+                y += 1;
+
+                if y == CELLS_HIGH {
+                    y = 0;
+                    x += 1;
+                }
+            } else {
+                x += 1;
+
+                if x == CELLS_WIDE {
+                    x = 0;
+                    y += 1;
+                }
+            }
+        }
+    }
+
+    fn attribute_division(&mut self, data: &[u8]) {
+        let after = data[1] & PALETTE_MASK;
+        let before = (data[1] >> 2) & PALETTE_MASK;
+        let line = (data[1] >> 4) & PALETTE_MASK;
+        let horizontal = data[1] & 0b01000000 > 0;
+        let division = (data[2] & CELL_MASK) as usize;
+
+        for y in 0..CELLS_HIGH {
+            for x in 0..CELLS_WIDE {
+                let position = if horizontal { y } else { x };
+
+                self.attributes[y * CELLS_WIDE + x] = match position.cmp(&division) {
+                    std::cmp::Ordering::Less => before,
+                    std::cmp::Ordering::Equal => line,
+                    std::cmp::Ordering::Greater => after,
+                };
+            }
+        }
+    }
+
+    fn attribute_lines(&mut self, data: &[u8]) {
+        let count = data[1] as usize;
+
+        for &line in data[2..].iter().take(count) {
+            let position = (line & CELL_MASK) as usize;
+            let palette = (line >> 5) & PALETTE_MASK;
+
+            if line & 0b10000000 > 0 {
+                if position < CELLS_HIGH {
+                    self.attributes[position * CELLS_WIDE..(position + 1) * CELLS_WIDE].fill(palette);
+                }
+            } else if position < CELLS_WIDE {
+                for y in 0..CELLS_HIGH {
+                    self.attributes[y * CELLS_WIDE + position] = palette;
+                }
+            }
+        }
+    }
+
+    fn border_pixel(&self, x: usize, y: usize) -> Option<u16> {
+        let offset = ((y / 8) * BORDER_MAP_WIDTH + x / 8) * 2;
+        let entry = u16::from_le_bytes([self.border_map[offset], self.border_map[offset + 1]]);
+
+        let tile = (entry & BORDER_TILE_MASK) as usize;
+        let palette = (entry >> BORDER_PALETTE_SHIFT) as usize % BORDER_PALETTE_COUNT;
+
+        let column = if entry & BORDER_X_FLIP_BIT > 0 { 7 - x % 8 } else { x % 8 };
+        let row = if entry & BORDER_Y_FLIP_BIT > 0 { 7 - y % 8 } else { y % 8 };
+
+        // Two pairs of interleaved bitplanes, as the SNES stores them
+        let data = &self.border_tiles[tile * BORDER_TILE_SIZE..(tile + 1) * BORDER_TILE_SIZE];
+        let planes = [data[row * 2], data[row * 2 + 1], data[16 + row * 2], data[16 + row * 2 + 1]];
+
+        let color = planes.iter().enumerate().fold(0usize, |color, (plane, bits)| {
+            color | ((((bits >> (7 - column)) & 0b1) as usize) << plane)
+        });
+
+        match color {
+            0 => None,
+            _ => Some(self.border_palettes[palette][color]),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The last frame as shown on the TV, SGB_SCREEN_WIDTH x
+    /// SGB_SCREEN_HEIGHT RGB555 colors.
+    pub fn framebuffer(&self) -> Vec<u16> {
+        let backdrop = self.palettes[0][0];
+
+        let mut framebuffer = vec![backdrop; SGB_SCREEN_WIDTH * SGB_SCREEN_HEIGHT];
+
+        for y in 0..SCREEN_HEIGHT {
+            for x in 0..SCREEN_WIDTH {
+                let shade = self.screen[y * SCREEN_WIDTH + x] & PALETTE_MASK;
+                let palette = self.attributes[(y / 8) * CELLS_WIDE + x / 8] as usize;
+
+                let color = match self.mask {
+                    SgbMask::Black => 0x0000u16,
+                    SgbMask::Color0 => backdrop,
+                    _ if shade == 0 => backdrop,
+                    _ => self.palettes[palette][shade as usize],
+                };
+
+                framebuffer[(SCREEN_TOP + y) * SGB_SCREEN_WIDTH + SCREEN_LEFT + x] = color;
+            }
+        }
+
+        for y in 0..SGB_SCREEN_HEIGHT {
+            for x in 0..SGB_SCREEN_WIDTH {
+                if let Some(color) = self.border_pixel(x, y) {
+                    framebuffer[y * SGB_SCREEN_WIDTH + x] = color;
+                }
+            }
+        }
+
+        framebuffer
+    }
+
+    /// The PPU frame count as of the last frame received.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn mask(&self) -> SgbMask {
+        self.mask
+    }
+
+    fn next_player(&mut self) {
+        self.player = (self.player + 1) % self.players;
+    }
+
+    pub fn palette(&self, palette: u8) -> [u16; 4] {
+        self.palettes[(palette & PALETTE_MASK) as usize]
+    }
+
+    /// The joypad whose ID P1 reads back, once MLT_REQ has asked for more
+    /// than one.
+    pub fn player(&self) -> Option<u8> {
+        Some(self.player).filter(|_| self.players > 1)
+    }
+
+    /// Takes a finished frame of DMG shades from the PPU, keeping it to show
+    /// unless the screen is frozen, and reading any VRAM transfer out of it.
+    pub fn receive_frame(&mut self, frames: usize, framebuffer: &[u8]) {
+        if self.packet_frame != self.frames {
+            self.bit = None;
+        }
+
+        self.frames = frames;
+
+        if let Some(transfer) = self.transfer.take() {
+            self.receive_transfer(transfer, framebuffer);
+        }
+
+        if self.mask != SgbMask::Freeze {
+            self.screen.copy_from_slice(framebuffer);
+        }
+    }
+
+    fn receive_bit(&mut self, bit: usize, value: bool) {
+        if bit < PACKET_BITS {
+            self.packet[bit / 8] |= (value as u8) << (bit % 8);
+            self.bit = Some(bit + 1);
+
+            return;
+        }
+
+        // A packet is only complete once a 0 stop bit follows it
+        self.bit = None;
+
+        if !value {
+            self.receive_packet();
+        }
+    }
+
+    fn receive_packet(&mut self) {
+        if self.command.is_empty() && self.packet[0] & LENGTH_MASK == 0 {
+            return;
+        }
+
+        self.command.extend_from_slice(&self.packet);
+
+        let length = (self.command[0] & LENGTH_MASK) as usize;
+
+        if self.command.len() < length * PACKET_SIZE {
+            return;
+        }
+
+        let command = std::mem::take(&mut self.command);
+
+        if self.enabled {
+            self.run_command(&command);
+        }
+    }
+
+    fn receive_transfer(&mut self, transfer: VramTransfer, framebuffer: &[u8]) {
+        let mut data = vec![0x00u8; TRANSFER_SIZE];
+
+        // Tiles go left to right, top to bottom, 2 bits a pixel
+        for (tile, bytes) in data.chunks_exact_mut(16).enumerate() {
+            let (left, top) = ((tile % CELLS_WIDE) * 8, (tile / CELLS_WIDE) * 8);
+
+            for row in 0..8 {
+                for column in 0..8 {
+                    let shade = framebuffer[(top + row) * SCREEN_WIDTH + left + column] & PALETTE_MASK;
+
+                    bytes[row * 2] |= (shade & 0b01) << (7 - column);
+                    bytes[row * 2 + 1] |= ((shade & 0b10) >> 1) << (7 - column);
+                }
+            }
+        }
+
+        match transfer {
+            VramTransfer::AttributeFiles => {
+                let length = self.attribute_files.len();
+
+                self.attribute_files.copy_from_slice(&data[..length]);
+            },
+            VramTransfer::BorderMap => {
+                self.border_map.copy_from_slice(&data[..BORDER_MAP_SIZE]);
+
+                for (palette, colors) in self.border_palettes.iter_mut().enumerate() {
+                    let offset = BORDER_MAP_SIZE + palette * 32;
+
+                    for (color, value) in colors.iter_mut().enumerate() {
+                        *value = read_color(&data, offset + color * 2);
+                    }
+                }
+            },
+            VramTransfer::BorderTiles(half) => {
+                let offset = half * TRANSFER_SIZE;
+
+                self.border_tiles[offset..offset + TRANSFER_SIZE].copy_from_slice(&data);
+            },
+            VramTransfer::SystemPalettes => {
+                for (index, color) in self.system_palettes.iter_mut().enumerate() {
+                    *color = read_color(&data, index * 2);
+                }
+            },
+        }
+    }
+
+    fn run_command(&mut self, data: &[u8]) {
+        let Some(command) = SgbCommand::from_u8(data[0] >> COMMAND_SHIFT) else {
+            return;
+        };
+
+        match command {
+            SgbCommand::Pal01 => self.set_palette_pair(0, 1, data),
+            SgbCommand::Pal23 => self.set_palette_pair(2, 3, data),
+            SgbCommand::Pal03 => self.set_palette_pair(0, 3, data),
+            SgbCommand::Pal12 => self.set_palette_pair(1, 2, data),
+            SgbCommand::AttrBlk => self.attribute_block(data),
+            SgbCommand::AttrLin => self.attribute_lines(data),
+            SgbCommand::AttrDiv => self.attribute_division(data),
+            SgbCommand::AttrChr => self.attribute_characters(data),
+            SgbCommand::PalSet => {
+                for (palette, offset) in (1..9).step_by(2).enumerate() {
+                    let index = (u16::from_le_bytes([data[offset], data[offset + 1]]) & SYSTEM_PALETTE_MASK) as usize;
+
+                    self.palettes[palette].copy_from_slice(&self.system_palettes[index * 4..index * 4 + 4]);
+                }
+
+                if data[9] & APPLY_ATTRIBUTE_FILE_BIT > 0 {
+                    self.apply_attribute_file(data[9]);
+                }
+
+                if data[9] & CANCEL_MASK_BIT > 0 {
+                    self.mask = SgbMask::None;
+                }
+            },
+            SgbCommand::PalTrn => self.transfer = Some(VramTransfer::SystemPalettes),
+            SgbCommand::MltReq => {
+                self.players = match data[1] & 0b11 {
+                    0b01 => 2u8,
+                    0b11 => 4u8,
+                    _ => 1u8,
+                };
+                self.player = 0x00u8;
+            },
+            SgbCommand::ChrTrn => self.transfer = Some(VramTransfer::BorderTiles((data[1] & 0b1) as usize)),
+            SgbCommand::PctTrn => self.transfer = Some(VramTransfer::BorderMap),
+            SgbCommand::AttrTrn => self.transfer = Some(VramTransfer::AttributeFiles),
+            SgbCommand::AttrSet => {
+                self.apply_attribute_file(data[1]);
+
+                if data[1] & CANCEL_MASK_BIT > 0 {
+                    self.mask = SgbMask::None;
+                }
+            },
+            SgbCommand::MaskEn => self.mask = SgbMask::from_u8(data[1] & 0b11).unwrap(),
+            _ => {},
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Sets the shared color 0 and colors 1-3 of two palettes, as PAL01,
+    /// PAL23, PAL03 and PAL12 do.
+    fn set_palette_pair(&mut self, first: usize, second: usize, data: &[u8]) {
+        let color0 = read_color(data, 1);
+
+        for palette in self.palettes.iter_mut() {
+            palette[0] = color0;
+        }
+
+        for color in 1..4 {
+            self.palettes[first][color] = read_color(data, 1 + color * 2);
+            self.palettes[second][color] = read_color(data, 7 + color * 2);
+        }
+    }
+
+    /// Follows the select lines as the game writes P1, picking packets out of
+    /// the pulses and moving between joypads in multiplayer mode.
+    pub fn write_joypad(&mut self, value: u8) {
+        let lines = value & SELECT_MASK;
+
+        match lines {
+            0x00u8 => {
+                self.bit = Some(0usize);
+                self.packet = [0x00u8; PACKET_SIZE];
+                self.packet_frame = self.frames;
+            },
+            P14_BIT | P15_BIT if self.lines == SELECT_MASK => {
+                if let Some(bit) = self.bit {
+                    self.receive_bit(bit, lines == P14_BIT);
+                }
+            },
+            // The next joypad is selected as P15 goes back high
+            SELECT_MASK if self.bit.is_none() && self.lines & P15_BIT == 0 && self.players > 1 => self.next_player(),
+            _ => {},
+        }
+
+        self.lines = lines;
+    }
+}
+
+impl Default for SgbComponent {
+    fn default() -> Self {
+        SgbComponent::new()
+    }
+}
+
+impl MemoryComponent for SgbComponent {
+    /// Nothing is mapped; the memory mapping hands over the P1 writes.
+    fn mapped_locations(&self) -> Vec<u16> {
+        vec![]
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        Err(MemoryError::ReadError(location, "invalid state"))
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        Err(MemoryError::WriteError(location, value, "invalid state"))
+    }
+}
+
+fn read_color(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]]) & COLOR_MASK
+}
@@ -0,0 +1,3 @@
+mod timer_component;
+
+pub use timer_component::TimerComponent;
@@ -0,0 +1,173 @@
+use crate::{
+    interrupt::Interrupt,
+    memory_component::{MemoryComponent, MemoryError},
+};
+
+const DIV_ADDRESS: u16 = 0xff04u16;
+const TIMA_ADDRESS: u16 = 0xff05u16;
+const TMA_ADDRESS: u16 = 0xff06u16;
+const TAC_ADDRESS: u16 = 0xff07u16;
+
+const CLOCKS_PER_CYCLE: u16 = 4u16;
+
+const TAC_ENABLE_BIT: u8 = 0b00000100;
+const TAC_CLOCK_SELECT_MASK: u8 = 0b00000011;
+const TAC_UNUSED_BITS: u8 = 0b11111000;
+
+/// The timer registers: DIV (0xFF04), TIMA (0xFF05), TMA (0xFF06) and TAC
+/// (0xFF07).
+///
+/// DIV is the top half of a 16-bit counter running at the CPU clock. TIMA
+/// counts falling edges of the counter bit selected by TAC, so anything that
+/// drops that bit (resetting DIV, or changing TAC) can tick it early. When
+/// TIMA overflows it reads 0 for one cycle before being reloaded from TMA and
+/// requesting the timer interrupt.
+pub struct TimerComponent {
+    divider: u16,
+    overflowed: bool,
+    reloading: bool,
+    signal: bool,
+    tac: u8,
+    tima: u8,
+    tma: u8,
+}
+
+impl TimerComponent {
+    pub fn new() -> Self {
+        TimerComponent {
+            divider: 0x0000u16,
+            overflowed: false,
+            reloading: false,
+            signal: false,
+            tac: 0x00u8,
+            tima: 0x00u8,
+            tma: 0x00u8,
+        }
+    }
+
+    /// The full 16-bit internal counter.
+    pub fn divider(&self) -> u16 {
+        self.divider
+    }
+
+    /// Clears the internal counter, as writing DIV or executing STOP does.
+    pub fn reset_divider(&mut self) {
+        self.divider = 0x0000u16;
+
+        self.update_signal();
+    }
+
+    pub fn set_divider(&mut self, value: u16) {
+        self.divider = value;
+
+        self.signal = self.current_signal();
+    }
+
+    /// The counter bit TIMA follows, per the TAC clock select.
+    fn selected_bit(&self) -> u16 {
+        match self.tac & TAC_CLOCK_SELECT_MASK {
+            0b00 => 9,
+            0b01 => 3,
+            0b10 => 5,
+            _ => 7,
+        }
+    }
+
+    fn current_signal(&self) -> bool {
+        self.tac & TAC_ENABLE_BIT > 0 && (self.divider >> self.selected_bit()) & 0b1 > 0
+    }
+
+    fn increment_tima(&mut self) {
+        let (tima, overflowed) = self.tima.overflowing_add(1);
+
+        self.tima = tima;
+
+        if overflowed {
+            self.overflowed = true;
+        }
+    }
+
+    /// Ticks TIMA on a falling edge of the selected counter bit.
+    fn update_signal(&mut self) {
+        let signal = self.current_signal();
+
+        if self.signal && !signal {
+            self.increment_tima();
+        }
+
+        self.signal = signal;
+    }
+}
+
+impl Default for TimerComponent {
+    fn default() -> Self {
+        TimerComponent::new()
+    }
+}
+
+impl MemoryComponent for TimerComponent {
+    fn mapped_locations(&self) -> Vec<u16> {
+        (DIV_ADDRESS..=TAC_ADDRESS).collect()
+    }
+
+    fn read(&self, location: u16) -> Result<u8, MemoryError> {
+        match location {
+            DIV_ADDRESS => Ok((self.divider >> 8) as u8),
+            TIMA_ADDRESS => Ok(self.tima),
+            TMA_ADDRESS => Ok(self.tma),
+            TAC_ADDRESS => Ok(TAC_UNUSED_BITS | self.tac),
+            _ => Err(MemoryError::ReadError(location, "invalid state")),
+        }
+    }
+
+    fn step(&mut self) -> u8 {
+        let mut requests = 0x00u8;
+
+        self.reloading = false;
+
+        // The reload happens a full cycle after the overflow
+        if self.overflowed {
+            self.overflowed = false;
+            self.reloading = true;
+
+            self.tima = self.tma;
+
+            requests |= Interrupt::Timer as u8;
+        }
+
+        self.divider = self.divider.wrapping_add(CLOCKS_PER_CYCLE);
+
+        self.update_signal();
+
+        requests
+    }
+
+    fn write(&mut self, location: u16, value: u8) -> Result<(), MemoryError> {
+        match location {
+            DIV_ADDRESS => self.reset_divider(),
+            TIMA_ADDRESS => {
+                // Writing during the delay cancels the reload, while writing
+                // on the reload cycle itself is lost
+                if !self.reloading {
+                    self.overflowed = false;
+                    self.tima = value;
+                }
+            },
+            TMA_ADDRESS => {
+                self.tma = value;
+
+                if self.reloading {
+                    self.tima = value;
+                }
+            },
+            TAC_ADDRESS => {
+                self.tac = value & !TAC_UNUSED_BITS;
+
+                self.update_signal();
+            },
+            _ => return Err(MemoryError::WriteError(location, value, "invalid state")),
+        };
+
+        Ok(())
+    }
+}
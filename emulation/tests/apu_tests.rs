@@ -0,0 +1,612 @@
+mod common;
+
+use emulation::{
+    apu::{Envelope, SoundComponent, Sweep},
+    MemoryComponent,
+    Model,
+};
+
+const NR_10_ADDRESS: u16 = 0xff10u16;
+const NR_11_ADDRESS: u16 = 0xff11u16;
+const NR_12_ADDRESS: u16 = 0xff12u16;
+const NR_13_ADDRESS: u16 = 0xff13u16;
+const NR_14_ADDRESS: u16 = 0xff14u16;
+const NR_22_ADDRESS: u16 = 0xff17u16;
+const NR_24_ADDRESS: u16 = 0xff19u16;
+const NR_30_ADDRESS: u16 = 0xff1au16;
+const NR_32_ADDRESS: u16 = 0xff1cu16;
+const NR_34_ADDRESS: u16 = 0xff1eu16;
+const NR_42_ADDRESS: u16 = 0xff21u16;
+const NR_43_ADDRESS: u16 = 0xff22u16;
+const NR_44_ADDRESS: u16 = 0xff23u16;
+const NR_50_ADDRESS: u16 = 0xff24u16;
+const NR_51_ADDRESS: u16 = 0xff25u16;
+const NR_52_ADDRESS: u16 = 0xff26u16;
+const WAVE_PATTERN_RAM_START_ADDRESS: u16 = 0xff30u16;
+
+const READ_MASKS: [u8; 0x20] = [
+    0x80, 0x3f, 0x00, 0xff, 0xbf,
+    0xff, 0x3f, 0x00, 0xff, 0xbf,
+    0x7f, 0xff, 0x9f, 0xff, 0xbf,
+    0xff, 0xff, 0x00, 0x00, 0xbf,
+    0x00, 0x00, 0x70,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+];
+
+const CYCLES_PER_SECOND: usize = 1048576usize;
+const CYCLES_PER_FRAME_SEQUENCER_STEP: usize = 2048usize;
+
+fn step(sound: &mut SoundComponent, cycles: usize) {
+    for _ in 0..cycles {
+        sound.step();
+    }
+}
+
+/// A powered APU with every channel panned to both sides at full volume.
+fn powered_sound() -> SoundComponent {
+    let mut sound = SoundComponent::new();
+
+    sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+    sound.write(NR_50_ADDRESS, 0x77u8).unwrap();
+    sound.write(NR_51_ADDRESS, 0xffu8).unwrap();
+
+    sound
+}
+
+/// Triggers channel 1 at full volume with the given length settings.
+fn trigger_channel1(sound: &mut SoundComponent, nr11: u8, nr14: u8) {
+    sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+    sound.write(NR_11_ADDRESS, nr11).unwrap();
+    sound.write(NR_13_ADDRESS, 0x00u8).unwrap();
+    sound.write(NR_14_ADDRESS, 0x80u8 | nr14).unwrap();
+}
+
+fn channels_enabled(sound: &SoundComponent) -> u8 {
+    sound.read(NR_52_ADDRESS).unwrap() & 0x0fu8
+}
+
+mod registers {
+    use super::*;
+
+    #[test]
+    fn read_masks() {
+        let sound = SoundComponent::new();
+
+        for (i, mask) in READ_MASKS.iter().enumerate() {
+            assert_eq!(sound.read(NR_10_ADDRESS + i as u16).unwrap(), *mask);
+        }
+    }
+
+    #[test]
+    fn read_back() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_10_ADDRESS, 0x7fu8).unwrap();
+        sound.write(NR_11_ADDRESS, 0x81u8).unwrap();
+        sound.write(NR_13_ADDRESS, 0x12u8).unwrap();
+        sound.write(NR_32_ADDRESS, 0x60u8).unwrap();
+
+        assert_eq!(sound.read(NR_10_ADDRESS).unwrap(), 0xffu8);
+        assert_eq!(sound.read(NR_11_ADDRESS).unwrap(), 0xbfu8);
+        assert_eq!(sound.read(NR_13_ADDRESS).unwrap(), 0xffu8);
+        assert_eq!(sound.read(NR_32_ADDRESS).unwrap(), 0xffu8);
+        assert_eq!(sound.read(NR_50_ADDRESS).unwrap(), 0x77u8);
+    }
+
+    #[test]
+    fn channel_status() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x00u8, 0x00u8);
+
+        sound.write(NR_22_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_24_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(sound.read(NR_52_ADDRESS).unwrap(), 0xf3u8);
+
+        // The status bits are read-only
+        sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(sound.read(NR_52_ADDRESS).unwrap(), 0xf3u8);
+    }
+}
+
+mod power {
+    use super::*;
+
+    #[test]
+    fn off_clears_registers() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x80u8, 0x00u8);
+
+        sound.write(NR_52_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(sound.read(NR_52_ADDRESS).unwrap(), 0x70u8);
+        assert_eq!(sound.read(NR_11_ADDRESS).unwrap(), 0x3fu8);
+        assert_eq!(sound.read(NR_12_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(sound.read(NR_50_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(sound.read(NR_51_ADDRESS).unwrap(), 0x00u8);
+
+        sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(sound.read(NR_52_ADDRESS).unwrap(), 0xf0u8);
+    }
+
+    #[test]
+    fn off_ignores_writes() {
+        let mut sound = SoundComponent::new();
+
+        sound.write(NR_50_ADDRESS, 0x77u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_11_ADDRESS, 0xc0u8).unwrap();
+
+        assert_eq!(sound.read(NR_50_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(sound.read(NR_12_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(sound.read(NR_11_ADDRESS).unwrap(), 0x3fu8);
+    }
+
+    #[test]
+    fn off_keeps_wave_ram() {
+        let mut sound = powered_sound();
+
+        sound.write(WAVE_PATTERN_RAM_START_ADDRESS, 0xabu8).unwrap();
+        sound.write(NR_52_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(sound.read(WAVE_PATTERN_RAM_START_ADDRESS).unwrap(), 0xabu8);
+
+        sound.write(WAVE_PATTERN_RAM_START_ADDRESS, 0xcdu8).unwrap();
+
+        assert_eq!(sound.read(WAVE_PATTERN_RAM_START_ADDRESS).unwrap(), 0xcdu8);
+    }
+
+    #[test]
+    fn off_allows_length_writes() {
+        let mut sound = SoundComponent::new();
+
+        // A length of 2
+        sound.write(NR_11_ADDRESS, 0x3eu8).unwrap();
+
+        sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0xc0u8).unwrap();
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 2);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP);
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn off_ignores_length_writes_on_cgb() {
+        let mut sound = SoundComponent::with_model(Model::Cgb);
+
+        // A length of 2, which is ignored, leaving the full 64
+        sound.write(NR_11_ADDRESS, 0x3eu8).unwrap();
+
+        sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0xc0u8).unwrap();
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 4);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+    }
+
+    #[test]
+    fn off_clears_lengths_on_cgb() {
+        for (model, expected) in [(Model::Dmg, 0x00u8), (Model::Cgb, 0x01u8)] {
+            let mut sound = SoundComponent::with_model(model);
+
+            sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+
+            // A length of 2, then power cycled
+            sound.write(NR_11_ADDRESS, 0x3eu8).unwrap();
+            sound.write(NR_52_ADDRESS, 0x00u8).unwrap();
+            sound.write(NR_52_ADDRESS, 0x80u8).unwrap();
+
+            sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+            sound.write(NR_14_ADDRESS, 0xc0u8).unwrap();
+
+            step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 4);
+
+            assert_eq!(channels_enabled(&sound), expected);
+        }
+    }
+}
+
+mod channels {
+    use super::*;
+
+    #[test]
+    fn trigger() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x00u8, 0x00u8);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+    }
+
+    #[test]
+    fn trigger_without_dac() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_12_ADDRESS, 0x00u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn dac_off_disables() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x00u8, 0x00u8);
+
+        sound.write(NR_12_ADDRESS, 0x07u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn wave() {
+        let mut sound = powered_sound();
+
+        sound.write(WAVE_PATTERN_RAM_START_ADDRESS, 0x1fu8).unwrap();
+        sound.write(NR_30_ADDRESS, 0x80u8).unwrap();
+        sound.write(NR_32_ADDRESS, 0x20u8).unwrap();
+        sound.write(NR_34_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(sound.read(WAVE_PATTERN_RAM_START_ADDRESS).unwrap(), 0x1fu8);
+        assert_eq!(channels_enabled(&sound), 0x04u8);
+    }
+
+    #[test]
+    fn noise() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_42_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_43_ADDRESS, 0x08u8).unwrap();
+        sound.write(NR_44_ADDRESS, 0x80u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x08u8);
+
+        step(&mut sound, 1000);
+
+        // The LFSR is running, so the output isn't a constant
+        let samples = sound.take_samples();
+
+        assert!(samples.iter().any(|sample| *sample != samples[0]));
+    }
+}
+
+mod length {
+    use super::*;
+
+    #[test]
+    fn expires() {
+        let mut sound = powered_sound();
+
+        // A length of 2
+        trigger_channel1(&mut sound, 0x3eu8, 0x40u8);
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 2);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP);
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn extra_clock_on_enable() {
+        let mut sound = powered_sound();
+
+        // The next frame sequencer step won't clock lengths
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP);
+
+        trigger_channel1(&mut sound, 0x3fu8, 0x00u8);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+
+        sound.write(NR_14_ADDRESS, 0x40u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn no_extra_clock_before_length_step() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x3fu8, 0x00u8);
+
+        sound.write(NR_14_ADDRESS, 0x40u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut sound = powered_sound();
+
+        trigger_channel1(&mut sound, 0x3fu8, 0x00u8);
+
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 8);
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+    }
+}
+
+mod envelope {
+    use super::*;
+
+    #[test]
+    fn decreases() {
+        let mut envelope = Envelope::new();
+
+        envelope.write(0xf2u8);
+        envelope.trigger();
+
+        envelope.clock();
+
+        assert_eq!(envelope.volume(), 0x0fu8);
+
+        envelope.clock();
+
+        assert_eq!(envelope.volume(), 0x0eu8);
+    }
+
+    #[test]
+    fn increases_to_maximum() {
+        let mut envelope = Envelope::new();
+
+        envelope.write(0xe9u8);
+        envelope.trigger();
+
+        for _ in 0..10 {
+            envelope.clock();
+        }
+
+        assert_eq!(envelope.volume(), 0x0fu8);
+    }
+
+    #[test]
+    fn period_zero_holds() {
+        let mut envelope = Envelope::new();
+
+        envelope.write(0x80u8);
+        envelope.trigger();
+
+        envelope.clock();
+
+        assert_eq!(envelope.volume(), 0x08u8);
+    }
+}
+
+mod sweep {
+    use super::*;
+
+    #[test]
+    fn raises_frequency() {
+        let mut sweep = Sweep::new();
+
+        sweep.write(0x11u8);
+        sweep.trigger(0x0100u16);
+
+        assert_eq!(sweep.clock(), Some(0x0180u16));
+        assert!(!sweep.overflowed());
+    }
+
+    #[test]
+    fn lowers_frequency() {
+        let mut sweep = Sweep::new();
+
+        sweep.write(0x2au8);
+        sweep.trigger(0x0400u16);
+
+        assert_eq!(sweep.clock(), None);
+        assert_eq!(sweep.clock(), Some(0x0300u16));
+    }
+
+    #[test]
+    fn leaving_negate_disables() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_10_ADDRESS, 0x19u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_13_ADDRESS, 0x00u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0x84u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+
+        sound.write(NR_10_ADDRESS, 0x11u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn overflow_on_trigger() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_10_ADDRESS, 0x11u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_13_ADDRESS, 0x00u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0x87u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+
+    #[test]
+    fn overflow_on_clock() {
+        let mut sound = powered_sound();
+
+        sound.write(NR_10_ADDRESS, 0x12u8).unwrap();
+        sound.write(NR_12_ADDRESS, 0xf0u8).unwrap();
+        sound.write(NR_13_ADDRESS, 0x00u8).unwrap();
+        sound.write(NR_14_ADDRESS, 0x86u8).unwrap();
+
+        assert_eq!(channels_enabled(&sound), 0x01u8);
+
+        // Sweeps on the third step of the frame sequencer
+        step(&mut sound, CYCLES_PER_FRAME_SEQUENCER_STEP * 3);
+
+        assert_eq!(channels_enabled(&sound), 0x00u8);
+    }
+}
+
+mod samples {
+    use super::*;
+
+    #[test]
+    fn sample_rate() {
+        let mut sound = SoundComponent::with_sample_rate(48000);
+
+        step(&mut sound, CYCLES_PER_SECOND / 16);
+
+        assert_eq!(sound.sample_rate(), 48000);
+        assert_eq!(sound.take_samples().len(), 3000 * 2);
+        assert!(sound.take_samples().is_empty());
+    }
+
+    #[test]
+    fn buffer_capped_at_half_a_second() {
+        let mut sound = SoundComponent::with_sample_rate(8000);
+
+        step(&mut sound, CYCLES_PER_SECOND);
+
+        assert_eq!(sound.take_samples().len(), 8000);
+    }
+
+    #[test]
+    fn silent_when_powered_off() {
+        let mut sound = SoundComponent::new();
+
+        step(&mut sound, CYCLES_PER_SECOND / 10);
+
+        assert!(sound.take_samples().iter().all(|sample| *sample == 0.0f32));
+    }
+
+    #[test]
+    fn panning() {
+        let mut sound = powered_sound();
+
+        // Channel 1 on the left only
+        sound.write(NR_51_ADDRESS, 0x10u8).unwrap();
+
+        trigger_channel1(&mut sound, 0x80u8, 0x00u8);
+
+        step(&mut sound, CYCLES_PER_SECOND / 10);
+
+        let samples = sound.take_samples();
+        let loudest = |side: usize| samples.iter().skip(side).step_by(2).fold(0.0f32, |loudest, sample| loudest.max(sample.abs()));
+
+        assert!(loudest(0) > 0.1f32);
+        assert_eq!(loudest(1), 0.0f32);
+    }
+
+    #[test]
+    fn master_volume() {
+        let loudest = |nr50: u8| {
+            let mut sound = powered_sound();
+
+            sound.write(NR_50_ADDRESS, nr50).unwrap();
+
+            trigger_channel1(&mut sound, 0x80u8, 0x00u8);
+
+            step(&mut sound, CYCLES_PER_SECOND / 10);
+
+            sound.take_samples().iter().fold(0.0f32, |loudest, sample| loudest.max(sample.abs()))
+        };
+
+        assert!(loudest(0x77u8) > loudest(0x00u8) * 4.0f32);
+    }
+
+    #[test]
+    fn from_emulator() {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.add_memory_component(Box::new(SoundComponent::new()));
+
+        for _ in 0..CYCLES_PER_SECOND / 16 {
+            emulator.tick();
+        }
+
+        // 44100 Hz doesn't divide evenly, so the last part sample is pending
+        assert_eq!(emulator.take_audio_samples().len(), 2756 * 2);
+    }
+}
+
+mod wav {
+    use std::io::Cursor;
+
+    use emulation::{apu::WavRecorder, CYCLES_PER_FRAME};
+
+    use super::*;
+
+    const FRAMES: usize = 10usize;
+
+    /// Plays a tone on channel 1 for a few frames, returning the .wav file.
+    fn record() -> Vec<u8> {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.add_memory_component(Box::new(SoundComponent::new()));
+
+        for (location, value) in [
+            (NR_52_ADDRESS, 0x80u8),
+            (NR_50_ADDRESS, 0x77u8),
+            (NR_51_ADDRESS, 0x11u8),
+            (NR_11_ADDRESS, 0x80u8),
+            (NR_12_ADDRESS, 0xf3u8),
+            (NR_13_ADDRESS, 0x00u8),
+            (NR_14_ADDRESS, 0x87u8),
+        ] {
+            emulator.write(location, value).unwrap();
+        }
+
+        let mut wav = Cursor::new(Vec::new());
+        let mut recorder = WavRecorder::new(&mut wav, 44100).unwrap();
+
+        for _ in 0..FRAMES {
+            for _ in 0..CYCLES_PER_FRAME {
+                emulator.tick();
+            }
+
+            recorder.write_samples(&emulator.take_audio_samples()).unwrap();
+        }
+
+        recorder.finish().unwrap();
+
+        wav.into_inner()
+    }
+
+    #[test]
+    fn header() {
+        let reader = hound::WavReader::new(Cursor::new(record())).unwrap();
+        let spec = reader.spec();
+
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.sample_rate, 44100);
+        assert_eq!(spec.bits_per_sample, 16);
+
+        // About a sixth of a second
+        assert_eq!(reader.duration() as usize, CYCLES_PER_FRAME * FRAMES * 44100 / CYCLES_PER_SECOND);
+    }
+
+    #[test]
+    fn audible() {
+        let mut reader = hound::WavReader::new(Cursor::new(record())).unwrap();
+
+        let loudest = reader.samples::<i16>().map(|sample| sample.unwrap().unsigned_abs()).max();
+
+        assert!(loudest > Some(0x1000u16));
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(record(), record());
+    }
+}
@@ -0,0 +1,171 @@
+use std::{env, fs, path::PathBuf, process, time::Duration};
+
+use emulation::{
+    cartridge::{CartridgeError, CartridgeType},
+    rom::{RamSize, RomBuilder},
+    Cartridge,
+    Emulator,
+    MemoryComponent,
+};
+
+fn build_rom(cartridge_type: CartridgeType) -> Vec<u8> {
+    RomBuilder::new()
+        .cartridge_type(cartridge_type)
+        .ram_size(RamSize::Size64Kilobits)
+        .build()
+}
+
+/// A ROM in a scratch directory unique to the test, removed along with
+/// everything in it once the test is done.
+struct ScratchRom {
+    directory: PathBuf,
+    path: PathBuf,
+}
+
+impl Drop for ScratchRom {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
+
+fn write_rom(name: &str, cartridge_type: CartridgeType) -> ScratchRom {
+    let directory = env::temp_dir().join(format!("gameboy_battery_save_{}_{}", process::id(), name));
+
+    fs::create_dir_all(&directory).unwrap();
+
+    let path = directory.join("game.gb");
+
+    fs::write(&path, build_rom(cartridge_type)).unwrap();
+
+    ScratchRom { directory, path }
+}
+
+fn write_ram(cartridge: &mut Cartridge, value: u8) {
+    cartridge.write(0x0000u16, 0x0au8).unwrap();
+    cartridge.write(0xa000u16, value).unwrap();
+}
+
+mod export_import {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut cartridge = Cartridge::from_bytes(&build_rom(CartridgeType::Mbc1RamBattery)).unwrap();
+
+        write_ram(&mut cartridge, 0x42u8);
+
+        let data = cartridge.export_battery_ram().unwrap();
+
+        let mut restored = Cartridge::from_bytes(&build_rom(CartridgeType::Mbc1RamBattery)).unwrap();
+
+        restored.import_battery_ram(&data).unwrap();
+        restored.write(0x0000u16, 0x0au8).unwrap();
+
+        assert_eq!(data.len(), 0x2000usize);
+        assert_eq!(restored.read(0xa000u16).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn requires_battery() {
+        let mut cartridge = Cartridge::from_bytes(&build_rom(CartridgeType::Mbc1Ram)).unwrap();
+
+        assert!(cartridge.export_battery_ram().is_none());
+
+        match cartridge.import_battery_ram(&[0x00u8]) {
+            Err(CartridgeError::NoBattery) => {},
+            _ => panic!("invalid state"),
+        };
+    }
+}
+
+mod save_file {
+    use super::*;
+
+    #[test]
+    fn autosave_after_interval() {
+        let rom = write_rom("autosave", CartridgeType::Mbc1RamBattery);
+        let path = &rom.path;
+
+        let mut cartridge = Cartridge::from_file(path).unwrap();
+
+        cartridge.set_flush_interval(Duration::ZERO);
+
+        assert_eq!(cartridge.save_path(), Some(path.with_extension("sav").as_path()));
+
+        write_ram(&mut cartridge, 0x11u8);
+
+        assert!(cartridge.autosave().unwrap());
+
+        // Nothing changed, so nothing is written
+        assert!(!cartridge.autosave().unwrap());
+
+        assert_eq!(fs::read(path.with_extension("sav")).unwrap()[0], 0x11u8);
+    }
+
+    #[test]
+    fn autosave_waits_for_interval() {
+        let rom = write_rom("interval", CartridgeType::Mbc1RamBattery);
+        let path = &rom.path;
+
+        let mut cartridge = Cartridge::from_file(path).unwrap();
+
+        cartridge.set_flush_interval(Duration::from_secs(3600));
+
+        write_ram(&mut cartridge, 0x11u8);
+
+        assert!(!cartridge.autosave().unwrap());
+        assert!(!path.with_extension("sav").exists());
+    }
+
+    #[test]
+    fn saved_on_exit_and_restored() {
+        let rom = write_rom("shutdown", CartridgeType::Mbc5RamBattery);
+        let path = &rom.path;
+
+        {
+            let mut emulator = Emulator::default();
+
+            emulator.load_cartridge(Cartridge::from_file(path).unwrap());
+
+            write_ram(emulator.cartridge_mut().unwrap(), 0x22u8);
+
+            emulator.cartridge_mut().unwrap().flush_battery_save().unwrap();
+        }
+
+        let mut cartridge = Cartridge::from_file(path).unwrap();
+
+        cartridge.write(0x0000u16, 0x0au8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x22u8);
+    }
+
+    #[test]
+    fn not_saved_on_drop() {
+        let rom = write_rom("drop", CartridgeType::Mbc1RamBattery);
+        let path = &rom.path;
+
+        {
+            let mut cartridge = Cartridge::from_file(path).unwrap();
+
+            write_ram(&mut cartridge, 0x33u8);
+        }
+
+        assert!(!path.with_extension("sav").exists());
+    }
+
+    #[test]
+    fn not_persisted_without_battery() {
+        let rom = write_rom("no_battery", CartridgeType::Mbc1Ram);
+        let path = &rom.path;
+
+        {
+            let mut cartridge = Cartridge::from_file(path).unwrap();
+
+            write_ram(&mut cartridge, 0x33u8);
+
+            assert!(!cartridge.flush_battery_save().unwrap());
+        }
+
+        assert!(!path.with_extension("sav").exists());
+    }
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use common::{BGP_ADDRESS, LCDC_ADDRESS};
+use emulation::{
+    boot::BootRomComponent,
+    flag::Flag,
+    register::Register,
+    Cartridge,
+    Emulator,
+    Model,
+};
+
+const BOOT_ROM_DISABLE_ADDRESS: u16 = 0xff50u16;
+const DIV_ADDRESS: u16 = 0xff04u16;
+const TAC_ADDRESS: u16 = 0xff07u16;
+const IF_ADDRESS: u16 = 0xff0fu16;
+const NR50_ADDRESS: u16 = 0xff24u16;
+const NR51_ADDRESS: u16 = 0xff25u16;
+const NR52_ADDRESS: u16 = 0xff26u16;
+
+fn build_rom() -> Vec<u8> {
+    common::test_rom().program_data(vec![0x00u8, 0xc3u8, 0x50u8, 0x01u8]).build()
+}
+
+/// A boot ROM of NOPs that unmaps itself in its last two bytes, as the real
+/// ones do, falling through to 0x0100.
+fn build_boot_rom(size: usize) -> Vec<u8> {
+    let mut boot_rom = vec![0xaau8; size];
+
+    boot_rom[..0x0100].fill(0x00u8);
+
+    // LD A, 0x01; LDH (0x50), A
+    boot_rom[0x00fc..0x0100].copy_from_slice(&[0x3eu8, 0x01, 0xe0, 0x50]);
+
+    boot_rom
+}
+
+fn booting_emulator() -> Emulator {
+    let mut emulator = Emulator::default();
+
+    emulator.load_cartridge(Cartridge::from_bytes(&build_rom()).unwrap());
+    emulator.load_boot_rom(BootRomComponent::from_bytes(&build_boot_rom(0x0100)).unwrap());
+
+    emulator
+}
+
+mod boot_rom {
+    use super::*;
+
+    #[test]
+    fn rejects_wrong_size() {
+        assert!(BootRomComponent::from_bytes(&[0x00u8; 0x0200]).is_err());
+    }
+
+    #[test]
+    fn starts_at_zero() {
+        let emulator = booting_emulator();
+
+        assert_eq!(emulator.program_counter(), 0x0000u16);
+    }
+
+    #[test]
+    fn overlays_cartridge() {
+        let emulator = booting_emulator();
+
+        assert_eq!(emulator.memory_location(0x00fcu16), 0x3eu8);
+        assert_eq!(emulator.memory_location(0x0101u16), 0xc3u8);
+    }
+
+    #[test]
+    fn stays_on_top_of_later_cartridge() {
+        let mut emulator = Emulator::default();
+
+        emulator.load_boot_rom(BootRomComponent::from_bytes(&build_boot_rom(0x0100)).unwrap());
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom()).unwrap());
+
+        assert_eq!(emulator.program_counter(), 0x0000u16);
+        assert_eq!(emulator.memory_location(0x00fcu16), 0x3eu8);
+    }
+
+    #[test]
+    fn unmaps_on_write() {
+        let mut emulator = booting_emulator();
+
+        emulator.write(BOOT_ROM_DISABLE_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(emulator.memory_location(0x00fcu16), build_rom()[0x00fc]);
+        assert!(!emulator.memory_component::<BootRomComponent>().unwrap().enabled());
+    }
+
+    #[test]
+    fn ignores_zero_write() {
+        let mut emulator = booting_emulator();
+
+        emulator.write(BOOT_ROM_DISABLE_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(emulator.memory_location(0x00fcu16), 0x3eu8);
+    }
+
+    #[test]
+    fn stays_unmapped() {
+        let mut emulator = booting_emulator();
+
+        emulator.write(BOOT_ROM_DISABLE_ADDRESS, 0x01u8).unwrap();
+        emulator.write(BOOT_ROM_DISABLE_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(emulator.memory_location(0x00fcu16), build_rom()[0x00fc]);
+        assert_eq!(emulator.memory_location(BOOT_ROM_DISABLE_ADDRESS), 0xffu8);
+    }
+
+    #[test]
+    fn hands_over_to_cartridge() {
+        let mut emulator = booting_emulator();
+
+        while emulator.program_counter() != 0x0100u16 {
+            emulator.process_opcode().unwrap();
+        }
+
+        // NOP; JP 0x0150
+        emulator.process_opcode().unwrap();
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.program_counter(), 0x0150u16);
+        assert_eq!(emulator.memory_location(0x0000u16), build_rom()[0x0000]);
+    }
+
+    #[test]
+    fn cgb_leaves_header_visible() {
+        let mut emulator = Emulator::default();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom()).unwrap());
+        emulator.load_boot_rom(BootRomComponent::from_bytes(&build_boot_rom(0x0900)).unwrap());
+
+        assert_eq!(emulator.memory_location(0x0101u16), 0xc3u8);
+        assert_eq!(emulator.memory_location(0x0200u16), 0xaau8);
+        assert_eq!(emulator.memory_location(0x08ffu16), 0xaau8);
+
+        emulator.write(BOOT_ROM_DISABLE_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(emulator.memory_location(0x0200u16), build_rom()[0x0200]);
+    }
+}
+
+mod skip_boot_rom {
+    use super::*;
+
+    fn skipped_emulator(model: Model) -> Emulator {
+        let mut emulator = Emulator::builder().model(model).build();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom()).unwrap());
+        emulator.skip_boot_rom();
+
+        emulator
+    }
+
+    fn registers(emulator: &Emulator) -> [u8; 7] {
+        [Register::A, Register::B, Register::C, Register::D, Register::E, Register::H, Register::L]
+            .map(|register| emulator.register(&register))
+    }
+
+    #[test]
+    fn dmg_registers() {
+        let emulator = skipped_emulator(Model::Dmg);
+
+        assert_eq!(registers(&emulator), [0x01u8, 0x00, 0x13, 0x00, 0xd8, 0x01, 0x4d]);
+        assert!(emulator.flag(Flag::Z));
+        assert!(!emulator.flag(Flag::N));
+        assert!(emulator.flag(Flag::H));
+        assert!(emulator.flag(Flag::CY));
+        assert_eq!(emulator.program_counter(), 0x0100u16);
+        assert_eq!(emulator.stack_pointer(), 0xfffeu16);
+    }
+
+    #[test]
+    fn mgb_registers() {
+        let emulator = skipped_emulator(Model::Mgb);
+
+        assert_eq!(registers(&emulator), [0xffu8, 0x00, 0x13, 0x00, 0xd8, 0x01, 0x4d]);
+    }
+
+    #[test]
+    fn sgb_registers() {
+        let emulator = skipped_emulator(Model::Sgb);
+
+        assert_eq!(registers(&emulator), [0x01u8, 0x00, 0x14, 0x00, 0x00, 0xc0, 0x60]);
+        assert!(!emulator.flag(Flag::Z));
+    }
+
+    #[test]
+    fn cgb_registers() {
+        let emulator = skipped_emulator(Model::Cgb);
+
+        assert_eq!(registers(&emulator), [0x11u8, 0x00, 0x00, 0xff, 0x56, 0x00, 0x0d]);
+        assert!(emulator.flag(Flag::Z));
+        assert!(!emulator.flag(Flag::CY));
+    }
+
+    #[test]
+    fn agb_registers() {
+        let emulator = skipped_emulator(Model::Agb);
+
+        assert_eq!(registers(&emulator), [0x11u8, 0x01, 0x00, 0xff, 0x56, 0x00, 0x0d]);
+        assert!(!emulator.flag(Flag::Z));
+    }
+
+    #[test]
+    fn io_registers() {
+        let emulator = skipped_emulator(Model::Dmg);
+
+        assert_eq!(emulator.memory_location(LCDC_ADDRESS), 0x91u8);
+        assert_eq!(emulator.memory_location(BGP_ADDRESS), 0xfcu8);
+        assert_eq!(emulator.memory_location(IF_ADDRESS), 0xe1u8);
+        assert_eq!(emulator.memory_location(TAC_ADDRESS), 0xf8u8);
+        assert_eq!(emulator.memory_location(NR50_ADDRESS), 0x77u8);
+        assert_eq!(emulator.memory_location(NR51_ADDRESS), 0xf3u8);
+    }
+
+    #[test]
+    fn divider() {
+        let emulator = skipped_emulator(Model::Dmg);
+
+        assert_eq!(emulator.memory_location(DIV_ADDRESS), 0xabu8);
+    }
+
+    #[test]
+    fn boot_chime_left_playing() {
+        assert_eq!(skipped_emulator(Model::Dmg).memory_location(NR52_ADDRESS), 0xf1u8);
+        assert_eq!(skipped_emulator(Model::Cgb).memory_location(NR52_ADDRESS), 0xf1u8);
+        assert_eq!(skipped_emulator(Model::Sgb).memory_location(NR52_ADDRESS), 0xf0u8);
+    }
+}
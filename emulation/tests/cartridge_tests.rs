@@ -0,0 +1,172 @@
+use emulation::{
+    cartridge::{CartridgeError, CartridgeType},
+    rom::{CbgCompatibility, RamSize, RomBuilder, RomSize},
+    Cartridge,
+    Emulator,
+    MemoryComponent,
+};
+
+fn build_rom() -> Vec<u8> {
+    RomBuilder::new()
+        .game_title(String::from("TEST GAME"))
+        .cgb_compatibility(CbgCompatibility::CGBCompatible)
+        .cartridge_type(CartridgeType::RomRam)
+        .rom_size(RomSize::Size256Kilobits)
+        .ram_size(RamSize::Size64Kilobits)
+        .program_data(vec![0x00u8, 0xc3u8, 0x50u8, 0x01u8])
+        .build()
+}
+
+mod header {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        let rom = build_rom();
+
+        let cartridge = Cartridge::from_bytes(&rom).unwrap();
+
+        assert!(cartridge.header().header_checksum_valid(&rom));
+        assert!(cartridge.header().global_checksum_valid(&rom));
+    }
+
+    #[test]
+    fn fields() {
+        let cartridge = Cartridge::from_bytes(&build_rom()).unwrap();
+
+        let header = cartridge.header();
+
+        assert_eq!(header.title(), "TEST GAME");
+        assert_eq!(header.cgb_compatibility(), CbgCompatibility::CGBCompatible);
+        assert_eq!(header.cartridge_type(), CartridgeType::RomRam);
+        assert_eq!(header.rom_size().bytes(), 0x8000usize);
+        assert_eq!(header.ram_size().bytes(), 0x2000usize);
+    }
+
+    #[test]
+    fn new_maker_code() {
+        let mut rom = build_rom();
+
+        rom[0x014b] = 0x33u8;
+        rom[0x0144..0x0146].copy_from_slice(b"A4");
+
+        let cartridge = Cartridge::from_bytes(&rom).unwrap();
+
+        assert_eq!(cartridge.header().maker_code(), "A4");
+        assert_eq!(cartridge.header().maker(), Some("konami"));
+    }
+
+    #[test]
+    fn sgb_support() {
+        let rom = RomBuilder::new().sgb_support(true).build();
+
+        assert!(Cartridge::from_bytes(&rom).unwrap().header().sgb_support());
+    }
+
+    #[test]
+    fn sgb_support_needs_new_maker_code() {
+        let mut rom = RomBuilder::new().sgb_support(true).build();
+
+        rom[0x014b] = 0x01u8;
+
+        assert!(!Cartridge::from_bytes(&rom).unwrap().header().sgb_support());
+    }
+
+    #[test]
+    fn old_maker_code() {
+        let mut rom = build_rom();
+
+        rom[0x014b] = 0x01u8;
+
+        let cartridge = Cartridge::from_bytes(&rom).unwrap();
+
+        assert_eq!(cartridge.header().maker_code(), "01");
+        assert_eq!(cartridge.header().maker(), Some("nintendo"));
+    }
+
+    #[test]
+    fn old_maker_code_uses_old_table() {
+        let maker = |code: u8| {
+            let mut rom = build_rom();
+
+            rom[0x014b] = code;
+
+            Cartridge::from_bytes(&rom).unwrap().header().maker()
+        };
+
+        // These mean something else as new codes
+        assert_eq!(maker(0x38u8), Some("capcom"));
+        assert_eq!(maker(0x30u8), Some("infogrames"));
+    }
+
+    #[test]
+    fn new_maker_code_uses_new_table() {
+        let mut rom = build_rom();
+
+        rom[0x014b] = 0x33u8;
+        rom[0x0144..0x0146].copy_from_slice(b"38");
+
+        assert_eq!(Cartridge::from_bytes(&rom).unwrap().header().maker(), Some("hudson"));
+    }
+
+    #[test]
+    fn invalid_cartridge_type() {
+        let mut rom = build_rom();
+
+        rom[0x0147] = 0x04u8;
+
+        match Cartridge::from_bytes(&rom) {
+            Err(CartridgeError::UnsupportedCartridgeType(t)) => assert_eq!(t, 0x04u8),
+            _ => panic!("invalid state"),
+        };
+    }
+
+    #[test]
+    fn too_short() {
+        match Cartridge::from_bytes(&[0x00u8; 0x0100]) {
+            Err(CartridgeError::HeaderTooShort(l)) => assert_eq!(l, 0x0100usize),
+            _ => panic!("invalid state"),
+        };
+    }
+}
+
+mod memory {
+    use super::*;
+
+    #[test]
+    fn ram() {
+        let mut cartridge = Cartridge::from_bytes(&build_rom()).unwrap();
+
+        cartridge.write(0xa123u16, 0x42u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa123u16).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn rom_is_read_only() {
+        let mut cartridge = Cartridge::from_bytes(&build_rom()).unwrap();
+
+        cartridge.write(0x0101u16, 0xffu8).unwrap();
+
+        assert_eq!(cartridge.read(0x0101u16).unwrap(), 0xc3u8);
+    }
+}
+
+mod load_cartridge {
+    use super::*;
+
+    #[test]
+    fn boots_at_entry_point() {
+        let mut emulator = Emulator::default();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom()).unwrap());
+
+        assert_eq!(emulator.program_counter(), 0x0100u16);
+
+        // NOP; JP 0x0150
+        emulator.process_opcode().unwrap();
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.program_counter(), 0x0150u16);
+    }
+}
@@ -0,0 +1,423 @@
+mod common;
+
+use common::LCDC_ADDRESS;
+use emulation::{
+    ppu::{PpuComponent, SCREEN_WIDTH},
+    rom::CbgCompatibility,
+    Cartridge,
+    Emulator,
+    MemoryComponent,
+    Model,
+    CYCLES_PER_FRAME,
+};
+
+const VBK_ADDRESS: u16 = 0xff4fu16;
+const BCPS_ADDRESS: u16 = 0xff68u16;
+const BCPD_ADDRESS: u16 = 0xff69u16;
+const OCPS_ADDRESS: u16 = 0xff6au16;
+const OCPD_ADDRESS: u16 = 0xff6bu16;
+const SVBK_ADDRESS: u16 = 0xff70u16;
+
+const TILE_MAP_ADDRESS: u16 = 0x9800u16;
+
+// LCD on, 0x8000 tile data, BG on, with and without objects
+const LCDC_BACKGROUND: u8 = 0x91u8;
+const LCDC_OBJECTS: u8 = 0x93u8;
+const LCDC_OBJECTS_ON_TOP: u8 = 0x92u8;
+
+fn emulator(model: Model, cgb_compatibility: CbgCompatibility) -> Emulator {
+    let mut emulator = Emulator::builder().model(model).build();
+    let rom = common::test_rom().cgb_compatibility(cgb_compatibility).build();
+
+    emulator.load_cartridge(Cartridge::from_bytes(&rom).unwrap());
+
+    emulator
+}
+
+fn cgb_ppu() -> PpuComponent {
+    let mut ppu = PpuComponent::new();
+
+    ppu.set_cgb_mode(true);
+
+    ppu
+}
+
+/// Fills a tile with a single color, in the selected VRAM bank.
+fn solid_tile(ppu: &mut PpuComponent, tile: u8, color: u8) {
+    let low = if color & 0b01 > 0 { 0xffu8 } else { 0x00u8 };
+    let high = if color & 0b10 > 0 { 0xffu8 } else { 0x00u8 };
+
+    let address = 0x8000u16 + tile as u16 * 16;
+
+    for row in 0..8u16 {
+        ppu.write(address + row * 2, low).unwrap();
+        ppu.write(address + row * 2 + 1, high).unwrap();
+    }
+}
+
+/// Sets the first tile map entry's tile and attributes.
+fn map_tile(ppu: &mut PpuComponent, tile: u8, attributes: u8) {
+    ppu.write(TILE_MAP_ADDRESS, tile).unwrap();
+    ppu.write(VBK_ADDRESS, 0x01u8).unwrap();
+    ppu.write(TILE_MAP_ADDRESS, attributes).unwrap();
+    ppu.write(VBK_ADDRESS, 0x00u8).unwrap();
+}
+
+fn place_sprite(ppu: &mut PpuComponent, index: u16, y: u8, x: u8, tile: u8, attributes: u8) {
+    let address = 0xfe00u16 + index * 4;
+
+    ppu.write(address, y).unwrap();
+    ppu.write(address + 1, x).unwrap();
+    ppu.write(address + 2, tile).unwrap();
+    ppu.write(address + 3, attributes).unwrap();
+}
+
+fn set_color(ppu: &mut PpuComponent, spec_address: u16, palette: u8, color: u8, value: u16) {
+    ppu.write(spec_address, 0x80u8 | (palette * 8 + color * 2)).unwrap();
+
+    for byte in value.to_le_bytes() {
+        ppu.write(spec_address + 1, byte).unwrap();
+    }
+}
+
+fn render(ppu: &mut PpuComponent, lcdc: u8) {
+    ppu.write(LCDC_ADDRESS, lcdc).unwrap();
+
+    for _ in 0..CYCLES_PER_FRAME {
+        ppu.step();
+    }
+}
+
+fn pixel(ppu: &PpuComponent, x: usize, y: usize) -> u8 {
+    ppu.framebuffer()[y * SCREEN_WIDTH + x]
+}
+
+fn color(ppu: &PpuComponent, x: usize, y: usize) -> u16 {
+    ppu.color_framebuffer()[y * SCREEN_WIDTH + x]
+}
+
+mod mode_selection {
+    use super::*;
+
+    #[test]
+    fn cgb_game_on_cgb() {
+        assert!(emulator(Model::Cgb, CbgCompatibility::CGBCompatible).cgb_mode());
+        assert!(emulator(Model::Agb, CbgCompatibility::CGBExclusive).cgb_mode());
+    }
+
+    #[test]
+    fn dmg_game_on_cgb() {
+        let emulator = emulator(Model::Cgb, CbgCompatibility::CGBIncompatible);
+
+        assert!(!emulator.cgb_mode());
+        assert!(emulator.color_framebuffer().is_none());
+    }
+
+    #[test]
+    fn cgb_game_on_dmg() {
+        let emulator = emulator(Model::Dmg, CbgCompatibility::CGBCompatible);
+
+        assert!(!emulator.cgb_mode());
+        assert_eq!(emulator.memory_location(SVBK_ADDRESS), 0xffu8);
+        assert_eq!(emulator.memory_location(VBK_ADDRESS), 0xffu8);
+    }
+}
+
+mod work_ram {
+    use super::*;
+
+    #[test]
+    fn switches_banks() {
+        let mut emulator = emulator(Model::Cgb, CbgCompatibility::CGBCompatible);
+
+        for bank in 1..8u8 {
+            emulator.write(SVBK_ADDRESS, bank).unwrap();
+            emulator.write(0xd000u16, bank * 0x11).unwrap();
+        }
+
+        for bank in 1..8u8 {
+            emulator.write(SVBK_ADDRESS, bank).unwrap();
+
+            assert_eq!(emulator.read(0xd000u16).unwrap(), bank * 0x11);
+        }
+    }
+
+    #[test]
+    fn bank_zero_selects_bank_one() {
+        let mut emulator = emulator(Model::Cgb, CbgCompatibility::CGBCompatible);
+
+        emulator.write(SVBK_ADDRESS, 0x01u8).unwrap();
+        emulator.write(0xd000u16, 0x42u8).unwrap();
+        emulator.write(SVBK_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(emulator.read(0xd000u16).unwrap(), 0x42u8);
+        assert_eq!(emulator.read(SVBK_ADDRESS).unwrap(), 0xf8u8);
+    }
+
+    #[test]
+    fn bank_zero_is_fixed() {
+        let mut emulator = emulator(Model::Cgb, CbgCompatibility::CGBCompatible);
+
+        emulator.write(0xc000u16, 0x42u8).unwrap();
+        emulator.write(SVBK_ADDRESS, 0x05u8).unwrap();
+
+        assert_eq!(emulator.read(0xc000u16).unwrap(), 0x42u8);
+        assert_eq!(emulator.read(SVBK_ADDRESS).unwrap(), 0xfdu8);
+    }
+
+    #[test]
+    fn echo_follows_bank() {
+        let mut emulator = emulator(Model::Cgb, CbgCompatibility::CGBCompatible);
+
+        emulator.write(SVBK_ADDRESS, 0x03u8).unwrap();
+        emulator.write(0xd123u16, 0x42u8).unwrap();
+
+        assert_eq!(emulator.read(0xf123u16).unwrap(), 0x42u8);
+
+        emulator.write(0xe010u16, 0x24u8).unwrap();
+
+        assert_eq!(emulator.read(0xc010u16).unwrap(), 0x24u8);
+    }
+
+    #[test]
+    fn echo_mirrors_work_ram_on_dmg() {
+        let mut emulator = emulator(Model::Dmg, CbgCompatibility::CGBIncompatible);
+
+        emulator.write(0xe000u16, 0x42u8).unwrap();
+        emulator.write(0xfdffu16, 0x24u8).unwrap();
+
+        assert_eq!(emulator.read(0xc000u16).unwrap(), 0x42u8);
+        assert_eq!(emulator.read(0xddffu16).unwrap(), 0x24u8);
+    }
+
+    #[test]
+    fn ignores_svbk_outside_cgb_mode() {
+        let mut emulator = emulator(Model::Cgb, CbgCompatibility::CGBIncompatible);
+
+        emulator.write(0xd000u16, 0x42u8).unwrap();
+        emulator.write(SVBK_ADDRESS, 0x02u8).unwrap();
+
+        assert_eq!(emulator.read(0xd000u16).unwrap(), 0x42u8);
+        assert_eq!(emulator.read(SVBK_ADDRESS).unwrap(), 0xffu8);
+    }
+}
+
+mod vram {
+    use super::*;
+
+    #[test]
+    fn switches_banks() {
+        let mut ppu = cgb_ppu();
+
+        ppu.write(0x8000u16, 0x11u8).unwrap();
+        ppu.write(VBK_ADDRESS, 0x01u8).unwrap();
+        ppu.write(0x8000u16, 0x22u8).unwrap();
+
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0x22u8);
+        assert_eq!(ppu.read(VBK_ADDRESS).unwrap(), 0xffu8);
+
+        ppu.write(VBK_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0x11u8);
+        assert_eq!(ppu.read(VBK_ADDRESS).unwrap(), 0xfeu8);
+    }
+
+    #[test]
+    fn ignores_vbk_outside_cgb_mode() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(0x8000u16, 0x11u8).unwrap();
+        ppu.write(VBK_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0x11u8);
+        assert_eq!(ppu.read(VBK_ADDRESS).unwrap(), 0xffu8);
+    }
+}
+
+mod palette_ram {
+    use super::*;
+
+    #[test]
+    fn auto_increments() {
+        let mut ppu = cgb_ppu();
+
+        ppu.write(BCPS_ADDRESS, 0x80u8).unwrap();
+
+        for byte in [0x11u8, 0x22, 0x33] {
+            ppu.write(BCPD_ADDRESS, byte).unwrap();
+        }
+
+        assert_eq!(ppu.read(BCPS_ADDRESS).unwrap(), 0xc3u8);
+
+        ppu.write(BCPS_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(ppu.read(BCPD_ADDRESS).unwrap(), 0x22u8);
+        assert_eq!(ppu.read(BCPS_ADDRESS).unwrap(), 0x41u8);
+    }
+
+    #[test]
+    fn wraps_around() {
+        let mut ppu = cgb_ppu();
+
+        ppu.write(OCPS_ADDRESS, 0xbfu8).unwrap();
+        ppu.write(OCPD_ADDRESS, 0x42u8).unwrap();
+
+        assert_eq!(ppu.read(OCPS_ADDRESS).unwrap(), 0xc0u8);
+
+        ppu.write(OCPS_ADDRESS, 0x3fu8).unwrap();
+
+        assert_eq!(ppu.read(OCPD_ADDRESS).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn blocked_while_drawing() {
+        let mut ppu = cgb_ppu();
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        // Into drawing
+        for _ in 0..21 {
+            ppu.step();
+        }
+
+        ppu.write(BCPS_ADDRESS, 0x80u8).unwrap();
+        ppu.write(BCPD_ADDRESS, 0x42u8).unwrap();
+
+        assert_eq!(ppu.read(BCPD_ADDRESS).unwrap(), 0xffu8);
+
+        // The index still moved on
+        assert_eq!(ppu.read(BCPS_ADDRESS).unwrap(), 0xc1u8);
+    }
+}
+
+mod background {
+    use super::*;
+
+    #[test]
+    fn palette_from_attributes() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 3);
+        map_tile(&mut ppu, 1, 0x02u8);
+        set_color(&mut ppu, BCPS_ADDRESS, 2, 3, 0x7c1fu16);
+
+        render(&mut ppu, LCDC_BACKGROUND);
+
+        assert_eq!(pixel(&ppu, 0, 0), 3u8);
+        assert_eq!(color(&ppu, 0, 0), 0x7c1fu16);
+
+        // Palette RAM starts out white
+        assert_eq!(color(&ppu, 8, 0), 0x7fffu16);
+    }
+
+    #[test]
+    fn tile_bank_from_attributes() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 1);
+        ppu.write(VBK_ADDRESS, 0x01u8).unwrap();
+        solid_tile(&mut ppu, 1, 2);
+        ppu.write(VBK_ADDRESS, 0x00u8).unwrap();
+
+        map_tile(&mut ppu, 1, 0x08u8);
+
+        render(&mut ppu, LCDC_BACKGROUND);
+
+        assert_eq!(pixel(&ppu, 0, 0), 2u8);
+    }
+
+    #[test]
+    fn flips_from_attributes() {
+        let mut ppu = cgb_ppu();
+
+        // Only the top left pixel is set
+        ppu.write(0x8010u16, 0x80u8).unwrap();
+
+        map_tile(&mut ppu, 1, 0x60u8);
+
+        render(&mut ppu, LCDC_BACKGROUND);
+
+        assert_eq!(pixel(&ppu, 0, 0), 0u8);
+        assert_eq!(pixel(&ppu, 7, 7), 1u8);
+    }
+
+    #[test]
+    fn drawn_with_enable_bit_clear() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 2);
+        map_tile(&mut ppu, 1, 0x00u8);
+
+        render(&mut ppu, LCDC_OBJECTS_ON_TOP);
+
+        assert_eq!(pixel(&ppu, 0, 0), 2u8);
+    }
+}
+
+mod sprites {
+    use super::*;
+
+    #[test]
+    fn priority_by_oam_order() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 2);
+
+        place_sprite(&mut ppu, 0, 16, 8 + 4, 1, 0x00u8);
+        place_sprite(&mut ppu, 1, 16, 8, 2, 0x00u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 5, 0), 1u8);
+        assert_eq!(pixel(&ppu, 2, 0), 2u8);
+    }
+
+    #[test]
+    fn palette_and_bank() {
+        let mut ppu = cgb_ppu();
+
+        ppu.write(VBK_ADDRESS, 0x01u8).unwrap();
+        solid_tile(&mut ppu, 2, 3);
+        ppu.write(VBK_ADDRESS, 0x00u8).unwrap();
+
+        set_color(&mut ppu, OCPS_ADDRESS, 5, 3, 0x03e0u16);
+        place_sprite(&mut ppu, 0, 16, 8, 2, 0x0du8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 0, 0), 3u8);
+        assert_eq!(color(&ppu, 0, 0), 0x03e0u16);
+    }
+
+    #[test]
+    fn background_priority_attribute() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 3);
+        map_tile(&mut ppu, 1, 0x80u8);
+
+        place_sprite(&mut ppu, 0, 16, 8, 2, 0x00u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 0, 0), 1u8);
+    }
+
+    #[test]
+    fn on_top_with_enable_bit_clear() {
+        let mut ppu = cgb_ppu();
+
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 3);
+        map_tile(&mut ppu, 1, 0x80u8);
+
+        place_sprite(&mut ppu, 0, 16, 8, 2, 0x80u8);
+
+        render(&mut ppu, LCDC_OBJECTS_ON_TOP);
+
+        assert_eq!(pixel(&ppu, 0, 0), 3u8);
+        assert_eq!(pixel(&ppu, 8, 0), 0u8);
+    }
+}
@@ -1,6 +1,15 @@
 use std::collections::HashMap;
 
-use emulation::{Emulator, MemoryComponent, MemoryError, addresses::PROGRAM_COUNTER_START, register::Register, instruction::general_instructions::PREFIX, opcode::OpcodePattern};
+use emulation::{Emulator, MemoryComponent, MemoryError, addresses::PROGRAM_COUNTER_START, interrupt::InterruptComponent, register::Register, instruction::general_instructions::PREFIX, opcode::OpcodePattern};
+use emulation::{cartridge::CartridgeType, rom::{RamSize, RomBuilder, RomSize}};
+
+#[allow(dead_code)]
+pub const STACK_TOP: u16 = 0xe000u16;
+
+#[allow(dead_code)]
+pub const LCDC_ADDRESS: u16 = 0xff40u16;
+#[allow(dead_code)]
+pub const BGP_ADDRESS: u16 = 0xff47u16;
 
 pub struct TestComponent {
     memory_state: HashMap<u16, u8>,
@@ -57,6 +66,49 @@ pub fn complex_emulator(memory_state: HashMap<u16, u8>) -> Emulator {
     emulator
 }
 
+/// Builds an emulator running `program` from 0x0000, with NOPs everywhere
+/// else in the first page, some stack space and an interrupt controller.
+#[allow(dead_code)]
+pub fn program_emulator(program: &[u8]) -> Emulator {
+    let mut memory_state = HashMap::new();
+
+    for location in 0x0000u16..0x0100u16 {
+        memory_state.insert(location, 0x00u8);
+    }
+
+    for location in (STACK_TOP - 0x10)..STACK_TOP {
+        memory_state.insert(location, 0x00u8);
+    }
+
+    for (i, opcode) in program.iter().enumerate() {
+        memory_state.insert(i as u16, *opcode);
+    }
+
+    let mut emulator = complex_emulator(memory_state);
+
+    emulator.add_memory_component(Box::new(InterruptComponent::new()));
+
+    emulator.set_stack_pointer(STACK_TOP);
+
+    emulator
+}
+
+/// A ROM-only "TEST GAME" cartridge with a NOP at the entry point, left open
+/// for whatever header fields or program a test cares about.
+#[allow(dead_code)]
+pub fn test_rom() -> RomBuilder {
+    let mut rom_builder = RomBuilder::new();
+
+    rom_builder
+        .game_title(String::from("TEST GAME"))
+        .cartridge_type(CartridgeType::RomOnly)
+        .rom_size(RomSize::Size256Kilobits)
+        .ram_size(RamSize::None)
+        .program_data(vec![0x00u8]);
+
+    rom_builder
+}
+
 #[allow(dead_code)]
 pub fn simple_emulator(opcode: u8) -> Emulator {
     let memory_state = build_memory(opcode, false);
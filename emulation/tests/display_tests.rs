@@ -0,0 +1,96 @@
+use emulation::{
+    display::{DmgPalette, RgbConverter},
+    ppu::{SCREEN_HEIGHT, SCREEN_WIDTH},
+    Emulator,
+};
+
+mod palettes {
+    use super::*;
+
+    #[test]
+    fn green_by_default() {
+        let converter = RgbConverter::new();
+
+        assert_eq!(converter.palette(), DmgPalette::Green);
+        assert_eq!(converter.shade_rgb(0), [0xe0u8, 0xf8, 0xd0]);
+        assert_eq!(converter.shade_rgb(3), [0x08u8, 0x18, 0x20]);
+    }
+
+    #[test]
+    fn greyscale() {
+        let converter = RgbConverter::with_palette(DmgPalette::Greyscale);
+
+        assert_eq!(converter.shade_rgb(0), [0xffu8; 3]);
+        assert_eq!(converter.shade_rgb(1), [0xaau8; 3]);
+        assert_eq!(converter.shade_rgb(2), [0x55u8; 3]);
+        assert_eq!(converter.shade_rgb(3), [0x00u8; 3]);
+    }
+
+    #[test]
+    fn custom() {
+        let shades = [[0x01u8, 0x02, 0x03], [0x04, 0x05, 0x06], [0x07, 0x08, 0x09], [0x0a, 0x0b, 0x0c]];
+
+        let mut converter = RgbConverter::new();
+
+        converter.set_palette(DmgPalette::Custom(shades));
+
+        for (shade, rgb) in shades.iter().enumerate() {
+            assert_eq!(converter.shade_rgb(shade as u8), *rgb);
+        }
+    }
+}
+
+mod colors {
+    use super::*;
+
+    #[test]
+    fn widens_channels() {
+        let converter = RgbConverter::new();
+
+        assert_eq!(converter.color_rgb(0x7fffu16), [0xffu8; 3]);
+        assert_eq!(converter.color_rgb(0x0000u16), [0x00u8; 3]);
+        assert_eq!(converter.color_rgb(0x001fu16), [0xffu8, 0x00, 0x00]);
+        assert_eq!(converter.color_rgb(0x03e0u16), [0x00u8, 0xff, 0x00]);
+        assert_eq!(converter.color_rgb(0x7c00u16), [0x00u8, 0x00, 0xff]);
+        assert_eq!(converter.color_rgb(0x0010u16), [0x84u8, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn corrected_white_is_dimmed() {
+        let mut converter = RgbConverter::new();
+
+        converter.set_color_correction(true);
+
+        assert_eq!(converter.color_rgb(0x7fffu16), [0xf0u8; 3]);
+        assert_eq!(converter.color_rgb(0x0000u16), [0x00u8; 3]);
+    }
+
+    #[test]
+    fn corrected_channels_bleed() {
+        let mut converter = RgbConverter::new();
+
+        converter.set_color_correction(true);
+
+        // Pure red picks up some blue, and loses some of its own strength
+        assert_eq!(converter.color_rgb(0x001fu16), [0xc9u8, 0x00, 0x2e]);
+    }
+}
+
+mod frames {
+    use super::*;
+
+    #[test]
+    fn converts_dmg_frame() {
+        let emulator = Emulator::default();
+
+        let frame = RgbConverter::with_palette(DmgPalette::Pocket).frame_rgb(&emulator).unwrap();
+
+        assert_eq!(frame.len(), SCREEN_WIDTH * SCREEN_HEIGHT * 3);
+        assert_eq!(frame[..3], DmgPalette::Pocket.shades()[0]);
+    }
+
+    #[test]
+    fn nothing_without_ppu() {
+        assert!(RgbConverter::new().frame_rgb(&Emulator::new()).is_none());
+    }
+}
@@ -0,0 +1,429 @@
+mod common;
+
+use common::LCDC_ADDRESS;
+use emulation::{
+    rom::CbgCompatibility,
+    Cartridge,
+    Emulator,
+    Model,
+};
+
+const DMA_ADDRESS: u16 = 0xff46u16;
+const OAM_START_ADDRESS: u16 = 0xfe00u16;
+const WORK_RAM_START_ADDRESS: u16 = 0xc000u16;
+const HRAM_START_ADDRESS: u16 = 0xff80u16;
+
+const OAM_SIZE: u16 = 0xa0u16;
+
+/// An emulator with the LCD off, so OAM is always reachable, and work RAM
+/// filled with a pattern.
+fn dma_emulator() -> Emulator {
+    let mut emulator = Emulator::default();
+
+    for i in 0..OAM_SIZE {
+        emulator.write(WORK_RAM_START_ADDRESS + i, pattern(i)).unwrap();
+    }
+
+    emulator
+}
+
+fn pattern(i: u16) -> u8 {
+    (i as u8).wrapping_mul(3).wrapping_add(1)
+}
+
+fn oam(emulator: &Emulator, i: u16) -> u8 {
+    emulator.memory_location(OAM_START_ADDRESS + i)
+}
+
+fn tick(emulator: &mut Emulator, cycles: usize) {
+    for _ in 0..cycles {
+        emulator.tick();
+    }
+}
+
+mod oam_dma {
+    use super::*;
+
+    #[test]
+    fn copies_a_page_into_oam() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 161);
+
+        for i in 0..OAM_SIZE {
+            assert_eq!(oam(&emulator, i), pattern(i));
+        }
+    }
+
+    #[test]
+    fn starts_after_a_setup_cycle() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 1);
+
+        assert_eq!(oam(&emulator, 0), 0x00u8);
+
+        tick(&mut emulator, 1);
+
+        assert_eq!(oam(&emulator, 0), pattern(0));
+        assert_eq!(oam(&emulator, 1), 0x00u8);
+    }
+
+    #[test]
+    fn copies_one_byte_per_cycle() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 80);
+
+        assert_eq!(oam(&emulator, 78), pattern(78));
+        assert_eq!(oam(&emulator, 79), 0x00u8);
+    }
+
+    #[test]
+    fn reads_register_back() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc1u8).unwrap();
+
+        assert_eq!(emulator.read(DMA_ADDRESS).unwrap(), 0xc1u8);
+    }
+
+    #[test]
+    fn reads_echo_ram_as_work_ram() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xe0u8).unwrap();
+
+        tick(&mut emulator, 161);
+
+        assert_eq!(oam(&emulator, 0x10), pattern(0x10));
+    }
+
+    #[test]
+    fn ignores_oam_mode_restrictions() {
+        let mut emulator = dma_emulator();
+
+        // LCD on, so OAM is locked during OAM scan and drawing
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 161);
+
+        // Wait for H-Blank to look
+        tick(&mut emulator, 114 - 161 % 114 + 70);
+
+        assert_eq!(oam(&emulator, 0x20), pattern(0x20));
+    }
+
+    #[test]
+    fn restarts_when_written_again() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 11);
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+        emulator.write(WORK_RAM_START_ADDRESS, 0x00u8).unwrap();
+
+        // The write to work RAM was blocked, so the new transfer copies the
+        // same first byte again
+        tick(&mut emulator, 161);
+
+        assert_eq!(oam(&emulator, 0), pattern(0));
+        assert_eq!(oam(&emulator, 0x9f), pattern(0x9f));
+    }
+}
+
+mod bus_conflicts {
+    use super::*;
+
+    #[test]
+    fn blocks_reads_outside_hram() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 10);
+
+        // The CPU sees the byte being copied this cycle
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS + 0x50).unwrap(), pattern(9));
+    }
+
+    #[test]
+    fn blocks_writes_outside_hram() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 1);
+
+        emulator.write(WORK_RAM_START_ADDRESS + 0x50, 0xaau8).unwrap();
+
+        tick(&mut emulator, 159);
+
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS + 0x50).unwrap(), pattern(0x50));
+    }
+
+    #[test]
+    fn leaves_hram_reachable() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 10);
+
+        emulator.write(HRAM_START_ADDRESS, 0x42u8).unwrap();
+
+        assert_eq!(emulator.read(HRAM_START_ADDRESS).unwrap(), 0x42u8);
+        assert_eq!(emulator.read(DMA_ADDRESS).unwrap(), 0xc0u8);
+    }
+
+    #[test]
+    fn does_not_block_during_setup() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS + 0x50).unwrap(), pattern(0x50));
+    }
+
+    #[test]
+    fn lifts_after_the_last_byte() {
+        let mut emulator = dma_emulator();
+
+        emulator.write(DMA_ADDRESS, 0xc0u8).unwrap();
+
+        tick(&mut emulator, 160);
+
+        // The last byte is copied during this read
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS + 0x50).unwrap(), pattern(0x9f));
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS + 0x50).unwrap(), pattern(0x50));
+    }
+
+    #[test]
+    fn runs_routine_from_hram() {
+        let mut emulator = dma_emulator();
+
+        // LD A, 0xC0; LDH (0x46), A; LD A, 64; loop: DEC A; JR NZ, loop; NOP
+        let routine = [0x3eu8, 0xc0, 0xe0, 0x46, 0x3e, 0x40, 0x3d, 0x20, 0xfd, 0x00];
+
+        for (i, byte) in routine.iter().enumerate() {
+            emulator.write(HRAM_START_ADDRESS + i as u16, *byte).unwrap();
+        }
+
+        emulator.set_program_counter(HRAM_START_ADDRESS);
+
+        while emulator.program_counter() != HRAM_START_ADDRESS + routine.len() as u16 - 1 {
+            emulator.process_opcode().unwrap();
+        }
+
+        for i in 0..OAM_SIZE {
+            assert_eq!(oam(&emulator, i), pattern(i));
+        }
+
+        assert_eq!(emulator.read(WORK_RAM_START_ADDRESS).unwrap(), pattern(0));
+    }
+}
+
+mod vram_dma {
+    use super::*;
+
+    const STAT_ADDRESS: u16 = 0xff41u16;
+    const VBK_ADDRESS: u16 = 0xff4fu16;
+    const HDMA1_ADDRESS: u16 = 0xff51u16;
+    const HDMA2_ADDRESS: u16 = 0xff52u16;
+    const HDMA3_ADDRESS: u16 = 0xff53u16;
+    const HDMA4_ADDRESS: u16 = 0xff54u16;
+    const HDMA5_ADDRESS: u16 = 0xff55u16;
+
+    const VRAM_DESTINATION: u16 = 0x8800u16;
+
+    const HBLANK_MODE: u8 = 0x00u8;
+
+    fn build_rom(cgb_compatibility: CbgCompatibility) -> Vec<u8> {
+        common::test_rom().cgb_compatibility(cgb_compatibility).build()
+    }
+
+    /// A CGB with work RAM filled with a pattern and the transfer pointed
+    /// from there to 0x8800.
+    fn vram_dma_emulator() -> Emulator {
+        let mut emulator = Emulator::builder().model(Model::Cgb).build();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom(CbgCompatibility::CGBCompatible)).unwrap());
+
+        for i in 0..0x0100u16 {
+            emulator.write(WORK_RAM_START_ADDRESS + i, pattern(i)).unwrap();
+        }
+
+        emulator.write(HDMA1_ADDRESS, 0xc0u8).unwrap();
+        emulator.write(HDMA2_ADDRESS, 0x00u8).unwrap();
+        emulator.write(HDMA3_ADDRESS, 0x08u8).unwrap();
+        emulator.write(HDMA4_ADDRESS, 0x00u8).unwrap();
+
+        emulator
+    }
+
+    fn copied(emulator: &Emulator) -> usize {
+        (0..0x0100u16).take_while(|i| emulator.memory_location(VRAM_DESTINATION + i) == pattern(*i)).count()
+    }
+
+    fn wait_for_hblank(emulator: &mut Emulator) {
+        while emulator.memory_location(STAT_ADDRESS) & 0b11 == HBLANK_MODE {
+            emulator.tick();
+        }
+
+        while emulator.memory_location(STAT_ADDRESS) & 0b11 != HBLANK_MODE {
+            emulator.tick();
+        }
+    }
+
+    #[test]
+    fn general_purpose_copies_blocks() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(HDMA5_ADDRESS, 0x03u8).unwrap();
+        emulator.tick();
+
+        assert_eq!(copied(&emulator), 0x40usize);
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn general_purpose_halts_cpu() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(HDMA5_ADDRESS, 0x01u8).unwrap();
+
+        let start = emulator.cycles();
+
+        emulator.tick();
+
+        // Eight cycles a block, then the CPU's own cycle
+        assert_eq!(emulator.cycles() - start, 2 * 8 + 1);
+    }
+
+    #[test]
+    fn general_purpose_keeps_real_time_at_double_speed() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(0xff4du16, 0x01u8).unwrap();
+        emulator.switch_speed();
+
+        emulator.write(HDMA5_ADDRESS, 0x01u8).unwrap();
+
+        let start = emulator.cycles();
+
+        emulator.tick();
+
+        assert_eq!(emulator.cycles() - start, 2 * 16 + 1);
+        assert_eq!(copied(&emulator), 0x20usize);
+    }
+
+    #[test]
+    fn writes_selected_bank() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(VBK_ADDRESS, 0x01u8).unwrap();
+        emulator.write(HDMA5_ADDRESS, 0x00u8).unwrap();
+        emulator.tick();
+
+        assert_eq!(copied(&emulator), 0x10usize);
+
+        emulator.write(VBK_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(copied(&emulator), 0usize);
+    }
+
+    #[test]
+    fn hblank_copies_block_per_hblank() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+        emulator.write(HDMA5_ADDRESS, 0x81u8).unwrap();
+
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0x01u8);
+
+        wait_for_hblank(&mut emulator);
+        tick(&mut emulator, 1);
+
+        assert_eq!(copied(&emulator), 0x10usize);
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0x00u8);
+
+        wait_for_hblank(&mut emulator);
+        tick(&mut emulator, 1);
+
+        assert_eq!(copied(&emulator), 0x20usize);
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn hblank_halts_cpu_for_a_block() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+        emulator.write(HDMA5_ADDRESS, 0x81u8).unwrap();
+
+        // Up to the cycle that enters H-Blank
+        while emulator.memory_location(STAT_ADDRESS) & 0b11 != HBLANK_MODE {
+            emulator.tick();
+        }
+
+        let start = emulator.cycles();
+
+        emulator.tick();
+
+        // The block started copying in the cycle that entered H-Blank
+        assert_eq!(emulator.cycles() - start, 7 + 1);
+    }
+
+    #[test]
+    fn hblank_starts_straight_away_with_lcd_off() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(HDMA5_ADDRESS, 0x82u8).unwrap();
+        emulator.tick();
+
+        assert_eq!(copied(&emulator), 0x10usize);
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0x01u8);
+    }
+
+    #[test]
+    fn hblank_cancels() {
+        let mut emulator = vram_dma_emulator();
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+        emulator.write(HDMA5_ADDRESS, 0x83u8).unwrap();
+
+        wait_for_hblank(&mut emulator);
+        tick(&mut emulator, 1);
+
+        emulator.write(HDMA5_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0x82u8);
+
+        wait_for_hblank(&mut emulator);
+        tick(&mut emulator, 1);
+
+        assert_eq!(copied(&emulator), 0x10usize);
+    }
+
+    #[test]
+    fn ignored_outside_cgb_mode() {
+        let mut emulator = Emulator::builder().model(Model::Cgb).build();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&build_rom(CbgCompatibility::CGBIncompatible)).unwrap());
+
+        emulator.write(HDMA5_ADDRESS, 0x00u8).unwrap();
+        emulator.tick();
+
+        assert_eq!(emulator.read(HDMA5_ADDRESS).unwrap(), 0xffu8);
+        assert_eq!(emulator.memory_location(VRAM_DESTINATION), 0x00u8);
+    }
+}
@@ -39,4 +39,95 @@ fn test_instructions() {
     }).collect::<Vec<String>>().join(", "));
 
     assert_eq!(matched.len(), 512usize);
-}
\ No newline at end of file
+}
+
+mod builder {
+    use emulation::{dma::VramDmaComponent, Emulator, Model, SpeedSwitchComponent};
+
+    #[test]
+    fn defaults_to_dmg() {
+        assert_eq!(Emulator::builder().build().model(), Model::Dmg);
+        assert_eq!(Emulator::default().model(), Model::Dmg);
+    }
+
+    #[test]
+    fn keeps_model() {
+        for model in [Model::Dmg, Model::Mgb, Model::Sgb, Model::Cgb, Model::Agb] {
+            assert_eq!(Emulator::builder().model(model).build().model(), model);
+        }
+    }
+
+    #[test]
+    fn adds_instructions() {
+        let emulator = Emulator::builder().model(Model::Cgb).build();
+
+        assert!(emulator.instruction_name((false, 0x00u8)).is_some());
+    }
+
+    #[test]
+    fn adds_speed_switch_for_cgb() {
+        assert!(Emulator::builder().model(Model::Cgb).build().memory_component::<SpeedSwitchComponent>().is_some());
+        assert!(Emulator::builder().model(Model::Agb).build().memory_component::<SpeedSwitchComponent>().is_some());
+    }
+
+    #[test]
+    fn leaves_out_speed_switch_for_dmg() {
+        for model in [Model::Dmg, Model::Mgb, Model::Sgb] {
+            assert!(Emulator::builder().model(model).build().memory_component::<SpeedSwitchComponent>().is_none());
+        }
+    }
+
+    #[test]
+    fn adds_vram_dma_for_cgb() {
+        assert!(Emulator::builder().model(Model::Cgb).build().memory_component::<VramDmaComponent>().is_some());
+        assert!(Emulator::builder().model(Model::Agb).build().memory_component::<VramDmaComponent>().is_some());
+    }
+
+    #[test]
+    fn leaves_out_vram_dma_for_dmg() {
+        for model in [Model::Dmg, Model::Mgb, Model::Sgb] {
+            assert!(Emulator::builder().model(model).build().memory_component::<VramDmaComponent>().is_none());
+        }
+    }
+}
+
+mod unused_io {
+    use emulation::{Cartridge, Emulator};
+
+    use super::common;
+
+    const UNUSED_IO_ADDRESSES: [u16; 5] = [0xff03u16, 0xff08u16, 0xff4cu16, 0xff50u16, 0xff7fu16];
+
+    fn booted_emulator(program: Vec<u8>) -> Emulator {
+        let mut emulator = Emulator::default();
+
+        emulator.load_cartridge(Cartridge::from_bytes(&common::test_rom().program_data(program).build()).unwrap());
+        emulator.skip_boot_rom();
+
+        emulator
+    }
+
+    #[test]
+    fn reads_open_bus() {
+        let mut emulator = booted_emulator(vec![0x00u8]);
+
+        for location in UNUSED_IO_ADDRESSES {
+            emulator.write(location, 0x00u8).unwrap();
+
+            assert_eq!(emulator.read(location).unwrap(), 0xffu8);
+        }
+    }
+
+    #[test]
+    fn game_writing_ff7f_runs() {
+        // JP 0x0150, past the header, then LD A, 0x00; LDH (0x7F), A; JR -2
+        let mut program = vec![0x00u8; 0x0056];
+
+        program[0x0000..0x0003].copy_from_slice(&[0xc3u8, 0x50u8, 0x01u8]);
+        program[0x0050..0x0056].copy_from_slice(&[0x3eu8, 0x00u8, 0xe0u8, 0x7fu8, 0x18u8, 0xfeu8]);
+
+        let mut emulator = booted_emulator(program);
+
+        emulator.run_frame().unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use emulation::{
+    interrupt::Interrupt,
+    joypad::{Button, JoypadComponent},
+    ppu::PpuComponent,
+    EmulationState,
+    Emulator,
+    SpeedSwitchComponent,
+};
+
+const INTERRUPT_ENABLE_ADDRESS: u16 = 0xffffu16;
+const P1_ADDRESS: u16 = 0xff00u16;
+const KEY1_ADDRESS: u16 = 0xff4du16;
+const LCDC_ADDRESS: u16 = 0xff40u16;
+const LY_ADDRESS: u16 = 0xff44u16;
+
+const HALT_OPCODE: u8 = 0x76u8;
+const INC_A_OPCODE: u8 = 0x3cu8;
+const STOP_OPCODE: u8 = 0x10u8;
+
+fn run(emulator: &mut Emulator, steps: usize) {
+    for _ in 0..steps {
+        emulator.process_opcode().unwrap();
+    }
+}
+
+mod halt {
+    use super::*;
+
+    #[test]
+    fn idles() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE, INC_A_OPCODE]);
+
+        run(&mut emulator, 1);
+
+        let cycles = emulator.cycles();
+
+        run(&mut emulator, 10);
+
+        assert_eq!(emulator.state(), EmulationState::Halt);
+        assert_eq!(emulator.program_counter(), 0x0001u16);
+        assert_eq!(emulator.cycles(), cycles + 10);
+        assert_eq!(emulator.a(), 0x00u8);
+    }
+
+    #[test]
+    fn wakes_without_ime() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE, INC_A_OPCODE]);
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, Interrupt::Timer as u8).unwrap();
+
+        run(&mut emulator, 5);
+
+        emulator.request_interrupt(Interrupt::Timer);
+
+        run(&mut emulator, 1);
+
+        // Carries on after HALT without servicing the interrupt
+        assert_eq!(emulator.state(), EmulationState::Run);
+        assert_eq!(emulator.program_counter(), 0x0002u16);
+        assert_eq!(emulator.a(), 0x01u8);
+    }
+
+    #[test]
+    fn wakes_into_interrupt() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE, INC_A_OPCODE]);
+
+        emulator.set_interrupt_master_enable(true);
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, Interrupt::Serial as u8).unwrap();
+
+        run(&mut emulator, 5);
+
+        emulator.request_interrupt(Interrupt::Serial);
+
+        run(&mut emulator, 1);
+
+        assert_eq!(emulator.program_counter(), 0x0058u16);
+        assert_eq!(emulator.memory_location(common::STACK_TOP - 2), 0x01u8);
+    }
+
+    #[test]
+    fn ignores_disabled_requests() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE]);
+
+        run(&mut emulator, 1);
+
+        emulator.request_interrupt(Interrupt::Joypad);
+
+        run(&mut emulator, 5);
+
+        assert_eq!(emulator.state(), EmulationState::Halt);
+    }
+
+    #[test]
+    fn halt_bug() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE, INC_A_OPCODE]);
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, Interrupt::VBlank as u8).unwrap();
+        emulator.request_interrupt(Interrupt::VBlank);
+
+        run(&mut emulator, 3);
+
+        // The INC A after HALT runs twice
+        assert_eq!(emulator.state(), EmulationState::Run);
+        assert_eq!(emulator.a(), 0x02u8);
+        assert_eq!(emulator.program_counter(), 0x0002u16);
+    }
+
+    #[test]
+    fn peripherals_keep_running() {
+        let mut emulator = common::program_emulator(&[HALT_OPCODE, INC_A_OPCODE]);
+
+        emulator.add_memory_component(Box::new(PpuComponent::new()));
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, Interrupt::VBlank as u8).unwrap();
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+
+        run(&mut emulator, 1);
+
+        while emulator.state() == EmulationState::Halt {
+            run(&mut emulator, 1);
+        }
+
+        // Woken by the PPU's V-Blank request
+        assert_eq!(emulator.memory_location(LY_ADDRESS), 144u8);
+        assert_eq!(emulator.a(), 0x01u8);
+    }
+}
+
+mod stop {
+    use super::*;
+
+    #[test]
+    fn stops_everything() {
+        let mut emulator = common::program_emulator(&[STOP_OPCODE, 0x00u8, INC_A_OPCODE]);
+
+        emulator.add_memory_component(Box::new(PpuComponent::new()));
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+
+        run(&mut emulator, 1);
+
+        let ly = emulator.memory_location(LY_ADDRESS);
+
+        run(&mut emulator, 1000);
+
+        assert_eq!(emulator.state(), EmulationState::Stop);
+        assert_eq!(emulator.memory_location(LY_ADDRESS), ly);
+        assert_eq!(emulator.program_counter(), 0x0002u16);
+    }
+
+    #[test]
+    fn joypad_wakes() {
+        let mut emulator = common::program_emulator(&[STOP_OPCODE, 0x00u8, INC_A_OPCODE]);
+
+        emulator.add_memory_component(Box::new(JoypadComponent::new()));
+
+        // Only the action buttons are selected
+        emulator.write(P1_ADDRESS, 0x10u8).unwrap();
+
+        run(&mut emulator, 10);
+
+        emulator.press_button(Button::Down);
+
+        run(&mut emulator, 10);
+
+        assert_eq!(emulator.state(), EmulationState::Stop);
+
+        emulator.press_button(Button::Start);
+
+        run(&mut emulator, 1);
+
+        assert_eq!(emulator.state(), EmulationState::Run);
+        assert_eq!(emulator.a(), 0x01u8);
+    }
+
+    #[test]
+    fn run_frame_returns() {
+        let mut emulator = common::program_emulator(&[STOP_OPCODE, 0x00u8]);
+
+        emulator.add_memory_component(Box::new(PpuComponent::new()));
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+
+        emulator.run_frame().unwrap();
+
+        assert_eq!(emulator.state(), EmulationState::Stop);
+    }
+
+    #[test]
+    fn speed_switch() {
+        let mut emulator = common::program_emulator(&[STOP_OPCODE, 0x00u8, INC_A_OPCODE]);
+
+        emulator.add_memory_component(Box::new(SpeedSwitchComponent::new()));
+
+        emulator.write(KEY1_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(emulator.memory_location(KEY1_ADDRESS), 0x7fu8);
+
+        run(&mut emulator, 2);
+
+        assert_eq!(emulator.state(), EmulationState::Run);
+        assert_eq!(emulator.memory_location(KEY1_ADDRESS), 0xfeu8);
+        assert_eq!(emulator.a(), 0x01u8);
+    }
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use emulation::{
+    interrupt::{Interrupt, InterruptComponent},
+    ppu::PpuComponent,
+    Emulator,
+    MemoryComponent,
+};
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+const INTERRUPT_ENABLE_ADDRESS: u16 = 0xffffu16;
+
+const DI_OPCODE: u8 = 0xf3u8;
+const EI_OPCODE: u8 = 0xfbu8;
+const RETI_OPCODE: u8 = 0xd9u8;
+
+fn interrupt_flag(emulator: &Emulator) -> u8 {
+    emulator.memory_location(INTERRUPT_FLAG_ADDRESS) & 0x1fu8
+}
+
+mod registers {
+    use super::*;
+
+    #[test]
+    fn unused_flag_bits() {
+        let mut interrupts = InterruptComponent::new();
+
+        assert_eq!(interrupts.read(INTERRUPT_FLAG_ADDRESS).unwrap(), 0xe0u8);
+
+        interrupts.write(INTERRUPT_FLAG_ADDRESS, 0xffu8).unwrap();
+
+        assert_eq!(interrupts.read(INTERRUPT_FLAG_ADDRESS).unwrap(), 0xffu8);
+        assert_eq!(interrupts.requested(), 0x1fu8);
+    }
+
+    #[test]
+    fn enable_register() {
+        let mut interrupts = InterruptComponent::new();
+
+        interrupts.write(INTERRUPT_ENABLE_ADDRESS, 0x05u8).unwrap();
+        interrupts.request(Interrupt::Timer as u8 | Interrupt::LcdStat as u8);
+
+        assert_eq!(interrupts.read(INTERRUPT_ENABLE_ADDRESS).unwrap(), 0x05u8);
+        assert_eq!(interrupts.pending(), Interrupt::Timer as u8);
+    }
+}
+
+mod dispatch {
+    use super::*;
+
+    fn run(enabled: u8, requested: u8) -> Emulator {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.set_program_counter(0x0012u16);
+        emulator.set_interrupt_master_enable(true);
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, enabled).unwrap();
+        emulator.write(INTERRUPT_FLAG_ADDRESS, requested).unwrap();
+
+        emulator.process_cycles();
+
+        emulator.process_opcode().unwrap();
+
+        emulator
+    }
+
+    #[test]
+    fn jumps_to_vector() {
+        let emulator = run(0x1fu8, Interrupt::Timer as u8);
+
+        assert_eq!(emulator.program_counter(), 0x0050u16);
+        assert_eq!(emulator.stack_pointer(), common::STACK_TOP - 2);
+        assert_eq!(emulator.memory_location(common::STACK_TOP - 1), 0x00u8);
+        assert_eq!(emulator.memory_location(common::STACK_TOP - 2), 0x12u8);
+        assert_eq!(interrupt_flag(&emulator), 0x00u8);
+        assert!(!emulator.interrupt_master_enable());
+    }
+
+    #[test]
+    fn cycles() {
+        let emulator = run(0x1fu8, Interrupt::Joypad as u8);
+
+        assert_eq!(emulator.cycles(), 5);
+    }
+
+    #[test]
+    fn priority() {
+        let emulator = run(0x1fu8, Interrupt::Serial as u8 | Interrupt::LcdStat as u8);
+
+        assert_eq!(emulator.program_counter(), 0x0048u16);
+        assert_eq!(interrupt_flag(&emulator), Interrupt::Serial as u8);
+    }
+
+    #[test]
+    fn disabled_in_ie() {
+        let emulator = run(Interrupt::VBlank as u8, Interrupt::Timer as u8);
+
+        // Just the NOP
+        assert_eq!(emulator.program_counter(), 0x0013u16);
+        assert_eq!(interrupt_flag(&emulator), Interrupt::Timer as u8);
+    }
+
+    #[test]
+    fn disabled_by_ime() {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, 0x1fu8).unwrap();
+        emulator.request_interrupt(Interrupt::VBlank);
+
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.program_counter(), 0x0001u16);
+        assert_eq!(interrupt_flag(&emulator), Interrupt::VBlank as u8);
+    }
+}
+
+mod interrupt_master_enable {
+    use super::*;
+
+    fn pending_emulator(program: &[u8]) -> Emulator {
+        let mut emulator = common::program_emulator(program);
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, 0x1fu8).unwrap();
+        emulator.request_interrupt(Interrupt::VBlank);
+
+        emulator
+    }
+
+    #[test]
+    fn ei_is_delayed() {
+        let mut emulator = pending_emulator(&[EI_OPCODE]);
+
+        emulator.process_opcode().unwrap();
+
+        assert!(!emulator.interrupt_master_enable());
+
+        // The instruction after EI still runs
+        emulator.process_opcode().unwrap();
+
+        assert!(emulator.interrupt_master_enable());
+        assert_eq!(emulator.program_counter(), 0x0002u16);
+
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.program_counter(), 0x0040u16);
+        assert_eq!(emulator.memory_location(common::STACK_TOP - 2), 0x02u8);
+    }
+
+    #[test]
+    fn di_cancels_ei() {
+        let mut emulator = pending_emulator(&[EI_OPCODE, DI_OPCODE]);
+
+        emulator.process_opcode().unwrap();
+        emulator.process_opcode().unwrap();
+        emulator.process_opcode().unwrap();
+
+        assert!(!emulator.interrupt_master_enable());
+        assert_eq!(emulator.program_counter(), 0x0003u16);
+    }
+
+    #[test]
+    fn reti_is_immediate() {
+        let mut emulator = pending_emulator(&[RETI_OPCODE]);
+
+        emulator.write(common::STACK_TOP - 2, 0x20u8).unwrap();
+        emulator.set_stack_pointer(common::STACK_TOP - 2);
+
+        emulator.process_opcode().unwrap();
+
+        assert!(emulator.interrupt_master_enable());
+        assert_eq!(emulator.program_counter(), 0x0020u16);
+
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.program_counter(), 0x0040u16);
+    }
+}
+
+mod requests {
+    use super::*;
+
+    #[test]
+    fn ppu_vblank() {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.add_memory_component(Box::new(PpuComponent::new()));
+
+        emulator.write(0xff40u16, 0x80u8).unwrap();
+
+        // V-Blank starts after 144 lines of 114 cycles
+        for _ in 0..(144 * 114 - 1) {
+            emulator.tick();
+        }
+
+        assert_eq!(interrupt_flag(&emulator), 0x00u8);
+
+        emulator.tick();
+
+        assert_eq!(interrupt_flag(&emulator), Interrupt::VBlank as u8);
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use emulation::{
+    interrupt::Interrupt,
+    joypad::{Button, JoypadComponent},
+    MemoryComponent,
+};
+
+const P1_ADDRESS: u16 = 0xff00u16;
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+
+const SELECT_NONE: u8 = 0x30u8;
+const SELECT_DIRECTIONS: u8 = 0x20u8;
+const SELECT_ACTIONS: u8 = 0x10u8;
+const SELECT_BOTH: u8 = 0x00u8;
+
+fn read_with_select(joypad: &mut JoypadComponent, select: u8) -> u8 {
+    joypad.write(P1_ADDRESS, select).unwrap();
+
+    joypad.read(P1_ADDRESS).unwrap()
+}
+
+mod matrix {
+    use super::*;
+
+    #[test]
+    fn nothing_pressed() {
+        let mut joypad = JoypadComponent::new();
+
+        assert_eq!(read_with_select(&mut joypad, SELECT_NONE), 0xffu8);
+        assert_eq!(read_with_select(&mut joypad, SELECT_DIRECTIONS), 0xefu8);
+        assert_eq!(read_with_select(&mut joypad, SELECT_ACTIONS), 0xdfu8);
+    }
+
+    #[test]
+    fn rows() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.press(Button::Left);
+        joypad.press(Button::Start);
+
+        assert_eq!(read_with_select(&mut joypad, SELECT_NONE) & 0x0fu8, 0x0fu8);
+        assert_eq!(read_with_select(&mut joypad, SELECT_DIRECTIONS) & 0x0fu8, 0x0du8);
+        assert_eq!(read_with_select(&mut joypad, SELECT_ACTIONS) & 0x0fu8, 0x07u8);
+
+        // Both rows selected: the lines are shared
+        assert_eq!(read_with_select(&mut joypad, SELECT_BOTH) & 0x0fu8, 0x05u8);
+    }
+
+    #[test]
+    fn release() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.press(Button::A);
+        joypad.press(Button::B);
+        joypad.release(Button::A);
+
+        assert!(!joypad.is_pressed(Button::A));
+        assert!(joypad.is_pressed(Button::B));
+        assert_eq!(read_with_select(&mut joypad, SELECT_ACTIONS) & 0x0fu8, 0x0du8);
+    }
+
+    #[test]
+    fn lines_map_to_buttons() {
+        for (i, button) in Button::ALL.iter().enumerate() {
+            let mut joypad = JoypadComponent::new();
+
+            joypad.press(*button);
+
+            let select = if i < 4 { SELECT_DIRECTIONS } else { SELECT_ACTIONS };
+
+            assert_eq!(read_with_select(&mut joypad, select) & 0x0fu8, 0x0fu8 & !(1u8 << (i % 4)));
+        }
+    }
+}
+
+mod interrupts {
+    use super::*;
+
+    #[test]
+    fn press_on_selected_row() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.write(P1_ADDRESS, SELECT_DIRECTIONS).unwrap();
+
+        joypad.press(Button::Up);
+
+        assert_eq!(joypad.step(), Interrupt::Joypad as u8);
+        assert_eq!(joypad.step(), 0x00u8);
+
+        // Releasing is a rising edge
+        joypad.release(Button::Up);
+
+        assert_eq!(joypad.step(), 0x00u8);
+    }
+
+    #[test]
+    fn press_on_other_row() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.write(P1_ADDRESS, SELECT_DIRECTIONS).unwrap();
+
+        joypad.press(Button::A);
+
+        assert_eq!(joypad.step(), 0x00u8);
+    }
+
+    #[test]
+    fn selecting_held_button() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.press(Button::B);
+
+        assert_eq!(joypad.step(), 0x00u8);
+
+        joypad.write(P1_ADDRESS, SELECT_ACTIONS).unwrap();
+
+        assert_eq!(joypad.step(), Interrupt::Joypad as u8);
+    }
+
+    #[test]
+    fn line_already_low() {
+        let mut joypad = JoypadComponent::new();
+
+        joypad.write(P1_ADDRESS, SELECT_BOTH).unwrap();
+
+        joypad.press(Button::Right);
+        joypad.step();
+
+        // Shares a line with Right, so nothing falls
+        joypad.press(Button::A);
+
+        assert_eq!(joypad.step(), 0x00u8);
+    }
+
+    #[test]
+    fn through_emulator() {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.add_memory_component(Box::new(JoypadComponent::new()));
+
+        emulator.write(P1_ADDRESS, SELECT_ACTIONS).unwrap();
+
+        emulator.press_button(Button::Select);
+        emulator.tick();
+
+        assert_eq!(emulator.memory_location(INTERRUPT_FLAG_ADDRESS) & 0x1fu8, Interrupt::Joypad as u8);
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0fu8, 0x0bu8);
+
+        emulator.release_button(Button::Select);
+
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0fu8, 0x0fu8);
+    }
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use emulation::{
+    interrupt::Interrupt,
+    serial::{LinkedEmulators, SerialTransferComponent},
+    Emulator,
+};
+
+const SB_ADDRESS: u16 = 0xff01u16;
+const SC_ADDRESS: u16 = 0xff02u16;
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+
+const START_INTERNAL: u8 = 0x81u8;
+const START_EXTERNAL: u8 = 0x80u8;
+
+const CYCLES_PER_TRANSFER: usize = 1024usize;
+const RUN_CYCLES: usize = 8192usize;
+
+/// LD A, 0x08; LDH (IE), A
+const ENABLE_SERIAL: [u8; 4] = [0x3eu8, 0x08u8, 0xe0u8, 0xffu8];
+
+/// Two runs of LD B, n; DEC B; JR NZ, -3, taking about 2048 cycles.
+const DELAY: [u8; 8] = [0x06u8, 0x00u8, 0x05u8, 0x20u8, 0xfdu8, 0x05u8, 0x20u8, 0xfdu8];
+
+/// JR -2
+const SPIN: [u8; 2] = [0x18u8, 0xfeu8];
+
+/// LD A, n; LDH (SB), A; LD A, control; LDH (SC), A; HALT until the serial
+/// interrupt, then clear IF.
+fn transfer(value: u8, control: u8) -> Vec<u8> {
+    vec![0x3eu8, value, 0xe0u8, 0x01u8, 0x3eu8, control, 0xe0u8, 0x02u8, 0x76u8, 0x3eu8, 0x00u8, 0xe0u8, 0x0fu8]
+}
+
+fn serial_emulator(program: &[u8]) -> Emulator {
+    let mut emulator = common::program_emulator(program);
+
+    emulator.add_memory_component(Box::new(SerialTransferComponent::new()));
+
+    emulator
+}
+
+/// Runs the pair, returning the cycles each side's transfers finished on.
+fn run(linked: &mut LinkedEmulators) -> (Vec<usize>, Vec<usize>) {
+    let mut finished = (Vec::new(), Vec::new());
+    let mut transferring = (false, false);
+
+    while linked.cycles() < RUN_CYCLES {
+        linked.step().unwrap();
+
+        let now = (
+            linked.first().memory_location(SC_ADDRESS) & 0x80u8 > 0,
+            linked.second().memory_location(SC_ADDRESS) & 0x80u8 > 0,
+        );
+
+        if transferring.0 && !now.0 {
+            finished.0.push(linked.first().cycles());
+        }
+
+        if transferring.1 && !now.1 {
+            finished.1.push(linked.second().cycles());
+        }
+
+        transferring = now;
+    }
+
+    finished
+}
+
+fn linked(first: &[u8], second: &[u8]) -> LinkedEmulators {
+    LinkedEmulators::new(serial_emulator(first), serial_emulator(second))
+}
+
+fn program(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+mod exchange {
+    use super::*;
+
+    #[test]
+    fn swaps_bytes() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &SPIN]),
+            &program(&[&[0x3eu8, 0x99u8, 0xe0u8, 0x01u8, 0x3eu8, START_EXTERNAL, 0xe0u8, 0x02u8], &SPIN]),
+        );
+
+        let (first, second) = run(&mut linked);
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_eq!(linked.first().memory_location(SB_ADDRESS), 0x99u8);
+        assert_eq!(linked.second().memory_location(SB_ADDRESS), 0x42u8);
+        assert_eq!(linked.second().memory_location(INTERRUPT_FLAG_ADDRESS) & 0x1fu8, Interrupt::Serial as u8);
+    }
+
+    #[test]
+    fn same_cycle() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &SPIN]),
+            &program(&[&ENABLE_SERIAL, &transfer(0x99u8, START_EXTERNAL), &SPIN]),
+        );
+
+        let (first, second) = run(&mut linked);
+
+        // SC is written on the 15th cycle
+        assert_eq!(first, vec![15 + CYCLES_PER_TRANSFER]);
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn either_side_clocks() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x99u8, START_EXTERNAL), &SPIN]),
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &SPIN]),
+        );
+
+        let (first, second) = run(&mut linked);
+
+        assert_eq!(first, second);
+        assert_eq!(linked.first().memory_location(SB_ADDRESS), 0x42u8);
+        assert_eq!(linked.second().memory_location(SB_ADDRESS), 0x99u8);
+    }
+
+    #[test]
+    fn deterministic() {
+        let result = || {
+            let mut linked = linked(
+                &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &transfer(0x43u8, START_INTERNAL), &SPIN]),
+                &program(&[&ENABLE_SERIAL, &transfer(0x99u8, START_EXTERNAL), &transfer(0x98u8, START_EXTERNAL), &SPIN]),
+            );
+
+            let finished = run(&mut linked);
+
+            (finished, linked.first().memory_location(SB_ADDRESS), linked.second().memory_location(SB_ADDRESS))
+        };
+
+        let first = result();
+
+        assert_eq!(first.1, 0x98u8);
+        assert_eq!(first.2, 0x43u8);
+
+        for _ in 0..3 {
+            assert_eq!(result(), first);
+        }
+    }
+}
+
+mod no_partner {
+    use super::*;
+
+    #[test]
+    fn late_offer_waits_for_next_transfer() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &DELAY, &transfer(0x43u8, START_INTERNAL), &SPIN]),
+            &program(&[&ENABLE_SERIAL, &DELAY, &transfer(0x99u8, START_EXTERNAL), &SPIN]),
+        );
+
+        let (first, second) = run(&mut linked);
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second, vec![first[1]]);
+
+        // Nobody was listening for the first byte
+        assert_eq!(linked.first().memory_location(SB_ADDRESS), 0x99u8);
+        assert_eq!(linked.second().memory_location(SB_ADDRESS), 0x43u8);
+    }
+
+    #[test]
+    fn both_internal() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_INTERNAL), &SPIN]),
+            &program(&[&ENABLE_SERIAL, &transfer(0x99u8, START_INTERNAL), &SPIN]),
+        );
+
+        run(&mut linked);
+
+        assert_eq!(linked.first().memory_location(SB_ADDRESS), 0xffu8);
+        assert_eq!(linked.second().memory_location(SB_ADDRESS), 0xffu8);
+    }
+
+    #[test]
+    fn both_external() {
+        let mut linked = linked(
+            &program(&[&ENABLE_SERIAL, &transfer(0x42u8, START_EXTERNAL), &SPIN]),
+            &program(&[&ENABLE_SERIAL, &transfer(0x99u8, START_EXTERNAL), &SPIN]),
+        );
+
+        let (first, second) = run(&mut linked);
+
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+    }
+}
+
+#[test]
+fn into_inner() {
+    let mut linked = linked(&SPIN, &SPIN);
+
+    linked.run_cycles(100).unwrap();
+
+    let (first, second) = linked.into_inner();
+
+    assert!(first.cycles() >= 100);
+    assert!(second.cycles() >= 100);
+    assert!((first.cycles() as isize - second.cycles() as isize).abs() <= 3);
+}
@@ -0,0 +1,168 @@
+use emulation::{
+    cartridge::{CartridgeType, Mbc1Component},
+    rom::{RamSize, RomBuilder, RomSize, LOGO_END_ADDRESS, LOGO_START_ADDRESS, NINTENDO_LOGO, ROM_BANK_SIZE},
+    Cartridge,
+    MemoryComponent,
+};
+
+/// Builds a ROM where the first byte of each bank holds its bank number.
+fn build_rom(rom_size: RomSize, ram_size: RamSize) -> Vec<u8> {
+    let mut rom = RomBuilder::new()
+        .cartridge_type(CartridgeType::Mbc1RamBattery)
+        .rom_size(rom_size)
+        .ram_size(ram_size)
+        .build();
+
+    for bank in 1..rom_size.banks() {
+        rom[bank * ROM_BANK_SIZE] = bank as u8;
+    }
+
+    rom
+}
+
+fn build_cartridge(rom_size: RomSize, ram_size: RamSize) -> Cartridge {
+    Cartridge::from_bytes(&build_rom(rom_size, ram_size)).unwrap()
+}
+
+mod rom_banking {
+    use super::*;
+
+    #[test]
+    fn bank_zero_selects_bank_one() {
+        let mut cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::None);
+
+        cartridge.write(0x2000u16, 0x00u8).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x01u8);
+    }
+
+    #[test]
+    fn defaults_to_bank_one() {
+        let cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::None);
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x01u8);
+    }
+
+    #[test]
+    fn high_banks_with_bank_2() {
+        let mut cartridge = build_cartridge(RomSize::Size16Megabits, RamSize::None);
+
+        cartridge.write(0x2000u16, 0x03u8).unwrap();
+        cartridge.write(0x4000u16, 0x02u8).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x43u8);
+
+        // Mode 0 keeps bank 0 fixed
+        assert_eq!(cartridge.read(0x0000u16).unwrap(), 0x00u8);
+
+        // Mode 1 also applies BANK2 to 0x0000-0x3FFF
+        cartridge.write(0x6000u16, 0x01u8).unwrap();
+
+        assert_eq!(cartridge.read(0x0000u16).unwrap(), 0x40u8);
+    }
+
+    #[test]
+    fn masked_to_rom_size() {
+        let mut cartridge = build_cartridge(RomSize::Size1Megabits, RamSize::None);
+
+        cartridge.write(0x2000u16, 0x0bu8).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x03u8);
+    }
+
+    #[test]
+    fn switch() {
+        let mut cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::None);
+
+        cartridge.write(0x2000u16, 0x05u8).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x05u8);
+    }
+}
+
+mod ram_banking {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let mut cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::Size64Kilobits);
+
+        cartridge.write(0xa000u16, 0x42u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn enabled() {
+        let mut cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::Size64Kilobits);
+
+        cartridge.write(0x0000u16, 0x0au8).unwrap();
+        cartridge.write(0xa000u16, 0x42u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x42u8);
+
+        cartridge.write(0x0000u16, 0x00u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn switch_in_mode_1() {
+        let mut cartridge = build_cartridge(RomSize::Size4Megabits, RamSize::Size256Kilobits);
+
+        cartridge.write(0x0000u16, 0x0au8).unwrap();
+        cartridge.write(0x6000u16, 0x01u8).unwrap();
+
+        cartridge.write(0x4000u16, 0x02u8).unwrap();
+        cartridge.write(0xa000u16, 0x22u8).unwrap();
+
+        cartridge.write(0x4000u16, 0x01u8).unwrap();
+        cartridge.write(0xa000u16, 0x11u8).unwrap();
+
+        cartridge.write(0x4000u16, 0x02u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x22u8);
+
+        // Mode 0 always uses RAM bank 0
+        cartridge.write(0x6000u16, 0x00u8).unwrap();
+        cartridge.write(0xa000u16, 0x00u8).unwrap();
+        cartridge.write(0x6000u16, 0x01u8).unwrap();
+        cartridge.write(0x4000u16, 0x01u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x11u8);
+    }
+}
+
+mod multicart {
+    use super::*;
+
+    fn build_multicart() -> Vec<u8> {
+        let mut rom = build_rom(RomSize::Size8Megabits, RamSize::None);
+
+        let second_game = 0x10 * ROM_BANK_SIZE;
+
+        rom[second_game + LOGO_START_ADDRESS as usize..=second_game + LOGO_END_ADDRESS as usize].copy_from_slice(&NINTENDO_LOGO);
+
+        rom
+    }
+
+    #[test]
+    fn detected() {
+        assert!(Mbc1Component::is_multicart(&build_multicart()));
+        assert!(!Mbc1Component::is_multicart(&build_rom(RomSize::Size8Megabits, RamSize::None)));
+    }
+
+    #[test]
+    fn four_bit_bank_1() {
+        let mut cartridge = Cartridge::from_bytes(&build_multicart()).unwrap();
+
+        cartridge.write(0x2000u16, 0x12u8).unwrap();
+        cartridge.write(0x4000u16, 0x01u8).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x12u8);
+
+        cartridge.write(0x6000u16, 0x01u8).unwrap();
+
+        assert_eq!(cartridge.read(0x0000u16).unwrap(), 0x10u8);
+    }
+}
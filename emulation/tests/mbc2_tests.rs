@@ -0,0 +1,68 @@
+use emulation::{
+    cartridge::CartridgeType,
+    rom::{RomBuilder, RomSize, ROM_BANK_SIZE},
+    Cartridge,
+    MemoryComponent,
+};
+
+fn build_cartridge() -> Cartridge {
+    let mut rom = RomBuilder::new()
+        .cartridge_type(CartridgeType::Mbc2Battery)
+        .rom_size(RomSize::Size2Megabits)
+        .build();
+
+    for bank in 1..RomSize::Size2Megabits.banks() {
+        rom[bank * ROM_BANK_SIZE] = bank as u8;
+    }
+
+    Cartridge::from_bytes(&rom).unwrap()
+}
+
+#[test]
+fn address_bit_8_selects_register() {
+    let mut cartridge = build_cartridge();
+
+    // Bit 8 clear: RAM enable, so the bank doesn't change
+    cartridge.write(0x2000u16, 0x05u8).unwrap();
+
+    assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x01u8);
+
+    // Bit 8 set: ROM bank
+    cartridge.write(0x2100u16, 0x05u8).unwrap();
+
+    assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x05u8);
+
+    cartridge.write(0x0100u16, 0x00u8).unwrap();
+
+    assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x01u8);
+}
+
+#[test]
+fn half_byte_ram() {
+    let mut cartridge = build_cartridge();
+
+    cartridge.write(0x0000u16, 0x0au8).unwrap();
+    cartridge.write(0xa000u16, 0xabu8).unwrap();
+
+    assert_eq!(cartridge.read(0xa000u16).unwrap(), 0xfbu8);
+}
+
+#[test]
+fn ram_echoes() {
+    let mut cartridge = build_cartridge();
+
+    cartridge.write(0x0000u16, 0x0au8).unwrap();
+    cartridge.write(0xa1ffu16, 0x07u8).unwrap();
+
+    assert_eq!(cartridge.read(0xa3ffu16).unwrap(), 0xf7u8);
+    assert_eq!(cartridge.read(0xbfffu16).unwrap(), 0xf7u8);
+}
+
+#[test]
+fn ram_disabled() {
+    let mut cartridge = build_cartridge();
+
+    cartridge.write(0xa000u16, 0x01u8).unwrap();
+
+    assert_eq!(cartridge.read(0xa000u16).unwrap(), 0xffu8);
+}
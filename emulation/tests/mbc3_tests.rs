@@ -0,0 +1,255 @@
+use emulation::{
+    cartridge::{CartridgeType, ManualClockSource, Mbc3Component, MemoryBankController, RTC_TRAILER_SIZE},
+    rom::{RamSize, RomBuilder, RomSize, ROM_BANK_SIZE},
+    Cartridge,
+    MemoryComponent,
+};
+
+const START_TIME: u64 = 1_600_000_000u64;
+
+fn build_rom() -> Vec<u8> {
+    let mut rom = RomBuilder::new()
+        .cartridge_type(CartridgeType::Mbc3TimerRamBattery)
+        .rom_size(RomSize::Size16Megabits)
+        .ram_size(RamSize::Size256Kilobits)
+        .build();
+
+    for bank in 1..RomSize::Size16Megabits.banks() {
+        rom[bank * ROM_BANK_SIZE] = bank as u8;
+    }
+
+    rom
+}
+
+fn build_mbc3(clock_source: &ManualClockSource) -> Mbc3Component {
+    Mbc3Component::new(build_rom(), RamSize::Size256Kilobits.bytes(), Some(Box::new(clock_source.clone())))
+}
+
+fn latch(mbc3: &mut Mbc3Component) {
+    mbc3.write(0x6000u16, 0x00u8).unwrap();
+    mbc3.write(0x6000u16, 0x01u8).unwrap();
+}
+
+fn read_clock_register(mbc3: &mut Mbc3Component, register: u8) -> u8 {
+    mbc3.write(0x4000u16, register).unwrap();
+
+    mbc3.read(0xa000u16).unwrap()
+}
+
+mod banking {
+    use super::*;
+
+    #[test]
+    fn rom_bank() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x2000u16, 0x7fu8).unwrap();
+
+        assert_eq!(mbc3.read(0x4000u16).unwrap(), 0x7fu8);
+
+        mbc3.write(0x2000u16, 0x00u8).unwrap();
+
+        assert_eq!(mbc3.read(0x4000u16).unwrap(), 0x01u8);
+    }
+
+    #[test]
+    fn ram_bank() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        mbc3.write(0x4000u16, 0x03u8).unwrap();
+        mbc3.write(0xa000u16, 0x33u8).unwrap();
+
+        mbc3.write(0x4000u16, 0x00u8).unwrap();
+
+        assert_eq!(mbc3.read(0xa000u16).unwrap(), 0x00u8);
+
+        mbc3.write(0x4000u16, 0x03u8).unwrap();
+
+        assert_eq!(mbc3.read(0xa000u16).unwrap(), 0x33u8);
+    }
+
+    #[test]
+    fn selected_from_header() {
+        let cartridge = Cartridge::from_bytes(&build_rom()).unwrap();
+
+        assert_eq!(cartridge.read(0x4000u16).unwrap(), 0x01u8);
+    }
+}
+
+mod real_time_clock {
+    use super::*;
+
+    #[test]
+    fn latched_until_relatched() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        clock_source.advance(5);
+        latch(&mut mbc3);
+        clock_source.advance(10);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x08u8), 5u8);
+
+        latch(&mut mbc3);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x08u8), 15u8);
+    }
+
+    #[test]
+    fn counts_days() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        clock_source.advance(300 * 24 * 60 * 60 + 2 * 60 * 60 + 3 * 60 + 4);
+        latch(&mut mbc3);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x08u8), 4u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x09u8), 3u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0au8), 2u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0bu8), (300 & 0xff) as u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0cu8), 0x01u8);
+    }
+
+    #[test]
+    fn day_carry() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        clock_source.advance(513 * 24 * 60 * 60);
+        latch(&mut mbc3);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x0bu8), 0x01u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0cu8), 0x80u8);
+    }
+
+    #[test]
+    fn halt() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        mbc3.write(0x4000u16, 0x0cu8).unwrap();
+        mbc3.write(0xa000u16, 0x40u8).unwrap();
+
+        clock_source.advance(100);
+        latch(&mut mbc3);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x08u8), 0u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0cu8), 0x40u8);
+    }
+
+    #[test]
+    fn write_registers() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        mbc3.write(0x4000u16, 0x09u8).unwrap();
+        mbc3.write(0xa000u16, 59u8).unwrap();
+
+        clock_source.advance(60);
+        latch(&mut mbc3);
+
+        assert_eq!(read_clock_register(&mut mbc3, 0x09u8), 0u8);
+        assert_eq!(read_clock_register(&mut mbc3, 0x0au8), 1u8);
+    }
+}
+
+mod save_data {
+    use super::*;
+
+    #[test]
+    fn trailer_layout() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+        mbc3.write(0xa000u16, 0x42u8).unwrap();
+
+        clock_source.advance(7);
+
+        let data = mbc3.save_data();
+        let ram_size = RamSize::Size256Kilobits.bytes();
+
+        assert_eq!(data.len(), ram_size + RTC_TRAILER_SIZE);
+        assert_eq!(data[0], 0x42u8);
+        assert_eq!(&data[ram_size..ram_size + 4], &7u32.to_le_bytes());
+        assert_eq!(&data[ram_size + 40..], &(START_TIME + 7).to_le_bytes());
+    }
+
+    #[test]
+    fn restores_and_catches_up() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mbc3 = build_mbc3(&clock_source);
+
+        clock_source.advance(30);
+
+        let data = mbc3.save_data();
+
+        // The emulator is closed for an hour
+        clock_source.advance(60 * 60);
+
+        let mut restored = build_mbc3(&clock_source);
+
+        restored.load_save_data(&data);
+        restored.write(0x0000u16, 0x0au8).unwrap();
+        latch(&mut restored);
+
+        assert_eq!(read_clock_register(&mut restored, 0x08u8), 30u8);
+        assert_eq!(read_clock_register(&mut restored, 0x0au8), 1u8);
+    }
+
+    #[test]
+    fn running_clock_still_matches() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mbc3 = build_mbc3(&clock_source);
+
+        let data = mbc3.save_data();
+
+        clock_source.advance(60 * 60);
+
+        // Only the timestamp moved on, so there's nothing new to write
+        assert_ne!(mbc3.save_data(), data);
+        assert!(mbc3.save_data_matches(&data));
+    }
+
+    #[test]
+    fn changes_do_not_match() {
+        let clock_source = ManualClockSource::new(START_TIME);
+        let mut mbc3 = build_mbc3(&clock_source);
+
+        mbc3.write(0x0000u16, 0x0au8).unwrap();
+
+        let data = mbc3.save_data();
+
+        mbc3.write(0xa000u16, 0x42u8).unwrap();
+
+        assert!(!mbc3.save_data_matches(&data));
+
+        let data = mbc3.save_data();
+
+        mbc3.write(0x4000u16, 0x0au8).unwrap();
+        mbc3.write(0xa000u16, 5u8).unwrap();
+
+        assert!(!mbc3.save_data_matches(&data));
+
+        let data = mbc3.save_data();
+
+        clock_source.advance(7);
+        latch(&mut mbc3);
+
+        assert!(!mbc3.save_data_matches(&data));
+    }
+}
@@ -0,0 +1,115 @@
+use emulation::{
+    cartridge::{CartridgeType, RumbleEvent},
+    rom::{RamSize, RomBuilder, RomSize, ROM_BANK_SIZE},
+    Cartridge,
+    MemoryComponent,
+};
+
+/// Builds a ROM where the first two bytes of each bank hold its bank number.
+fn build_cartridge(cartridge_type: CartridgeType) -> Cartridge {
+    let mut rom = RomBuilder::new()
+        .cartridge_type(cartridge_type)
+        .rom_size(RomSize::Size64Megabits)
+        .ram_size(RamSize::Size1Megabits)
+        .build();
+
+    for bank in 1..RomSize::Size64Megabits.banks() {
+        let [low, high] = (bank as u16).to_le_bytes();
+
+        rom[bank * ROM_BANK_SIZE] = low;
+        rom[bank * ROM_BANK_SIZE + 1] = high;
+    }
+
+    Cartridge::from_bytes(&rom).unwrap()
+}
+
+fn read_bank_number(cartridge: &Cartridge) -> u16 {
+    u16::from_le_bytes([cartridge.read(0x4000u16).unwrap(), cartridge.read(0x4001u16).unwrap()])
+}
+
+mod banking {
+    use super::*;
+
+    #[test]
+    fn bank_zero_is_selectable() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5);
+
+        cartridge.write(0x2000u16, 0x00u8).unwrap();
+
+        assert_eq!(read_bank_number(&cartridge), 0x0000u16);
+    }
+
+    #[test]
+    fn nine_bit_rom_bank() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5);
+
+        cartridge.write(0x2000u16, 0xffu8).unwrap();
+        cartridge.write(0x3000u16, 0x01u8).unwrap();
+
+        assert_eq!(read_bank_number(&cartridge), 0x01ffu16);
+
+        cartridge.write(0x2000u16, 0x02u8).unwrap();
+
+        assert_eq!(read_bank_number(&cartridge), 0x0102u16);
+    }
+
+    #[test]
+    fn sixteen_ram_banks() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5RamBattery);
+
+        cartridge.write(0x0000u16, 0x0au8).unwrap();
+
+        for bank in 0u8..16u8 {
+            cartridge.write(0x4000u16, bank).unwrap();
+            cartridge.write(0xa000u16, bank).unwrap();
+        }
+
+        cartridge.write(0x4000u16, 0x0fu8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x0fu8);
+
+        cartridge.write(0x4000u16, 0x07u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x07u8);
+    }
+}
+
+mod rumble {
+    use super::*;
+
+    #[test]
+    fn events_on_change() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5RumbleRam);
+
+        let events = cartridge.subscribe_rumble();
+
+        cartridge.write(0x4000u16, 0x08u8).unwrap();
+        cartridge.write(0x4000u16, 0x09u8).unwrap();
+        cartridge.write(0x4000u16, 0x01u8).unwrap();
+
+        assert_eq!(events.try_iter().collect::<Vec<RumbleEvent>>(), vec![RumbleEvent::Started, RumbleEvent::Stopped]);
+    }
+
+    #[test]
+    fn motor_bit_is_not_a_bank_bit() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5RumbleRam);
+
+        cartridge.write(0x0000u16, 0x0au8).unwrap();
+        cartridge.write(0x4000u16, 0x01u8).unwrap();
+        cartridge.write(0xa000u16, 0x42u8).unwrap();
+        cartridge.write(0x4000u16, 0x09u8).unwrap();
+
+        assert_eq!(cartridge.read(0xa000u16).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn no_events_without_motor() {
+        let mut cartridge = build_cartridge(CartridgeType::Mbc5Ram);
+
+        let events = cartridge.subscribe_rumble();
+
+        cartridge.write(0x4000u16, 0x08u8).unwrap();
+
+        assert!(events.try_recv().is_err());
+    }
+}
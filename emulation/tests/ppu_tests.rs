@@ -0,0 +1,457 @@
+use emulation::{
+    ppu::{PpuComponent, PpuMode, SCREEN_WIDTH},
+    MemoryComponent,
+};
+
+// Machine cycles
+const CYCLES_PER_LINE: usize = 114usize;
+const CYCLES_PER_FRAME: usize = 17556usize;
+
+const LCDC_ADDRESS: u16 = 0xff40u16;
+const STAT_ADDRESS: u16 = 0xff41u16;
+const SCY_ADDRESS: u16 = 0xff42u16;
+const SCX_ADDRESS: u16 = 0xff43u16;
+const LY_ADDRESS: u16 = 0xff44u16;
+const LYC_ADDRESS: u16 = 0xff45u16;
+const BGP_ADDRESS: u16 = 0xff47u16;
+const OBP0_ADDRESS: u16 = 0xff48u16;
+const OBP1_ADDRESS: u16 = 0xff49u16;
+const WY_ADDRESS: u16 = 0xff4au16;
+const WX_ADDRESS: u16 = 0xff4bu16;
+
+// LCD on, 0x8000 tile data, BG on
+const LCDC_BACKGROUND: u8 = 0x91u8;
+const LCDC_OBJECTS: u8 = 0x93u8;
+const LCDC_TALL_OBJECTS: u8 = 0x97u8;
+
+// Identity palette, so shades equal color numbers
+const IDENTITY_PALETTE: u8 = 0xe4u8;
+
+fn step(ppu: &mut PpuComponent, cycles: usize) {
+    for _ in 0..cycles {
+        ppu.step();
+    }
+}
+
+/// Fills a tile with a single color.
+fn solid_tile(ppu: &mut PpuComponent, tile: u8, color: u8) {
+    let low = if color & 0b01 > 0 { 0xffu8 } else { 0x00u8 };
+    let high = if color & 0b10 > 0 { 0xffu8 } else { 0x00u8 };
+
+    let address = 0x8000u16 + tile as u16 * 16;
+
+    for row in 0..8u16 {
+        ppu.write(address + row * 2, low).unwrap();
+        ppu.write(address + row * 2 + 1, high).unwrap();
+    }
+}
+
+fn place_sprite(ppu: &mut PpuComponent, index: u16, y: u8, x: u8, tile: u8, attributes: u8) {
+    let address = 0xfe00u16 + index * 4;
+
+    ppu.write(address, y).unwrap();
+    ppu.write(address + 1, x).unwrap();
+    ppu.write(address + 2, tile).unwrap();
+    ppu.write(address + 3, attributes).unwrap();
+}
+
+/// Sets everything up with the LCD off, then renders one full frame.
+fn render(ppu: &mut PpuComponent, lcdc: u8) {
+    ppu.write(BGP_ADDRESS, IDENTITY_PALETTE).unwrap();
+    ppu.write(OBP0_ADDRESS, IDENTITY_PALETTE).unwrap();
+    ppu.write(LCDC_ADDRESS, lcdc).unwrap();
+
+    step(ppu, CYCLES_PER_FRAME);
+}
+
+fn pixel(ppu: &PpuComponent, x: usize, y: usize) -> u8 {
+    ppu.framebuffer()[y * SCREEN_WIDTH + x]
+}
+
+mod timing {
+    use super::*;
+
+    #[test]
+    fn mode_sequence() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(ppu.mode(), PpuMode::OamScan);
+
+        step(&mut ppu, 20);
+
+        assert_eq!(ppu.mode(), PpuMode::Drawing);
+        assert_eq!(ppu.read(STAT_ADDRESS).unwrap() & 0b11, 3u8);
+
+        step(&mut ppu, 43);
+
+        assert_eq!(ppu.mode(), PpuMode::HBlank);
+
+        step(&mut ppu, 51);
+
+        assert_eq!(ppu.mode(), PpuMode::OamScan);
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 1u8);
+    }
+
+    #[test]
+    fn vblank_and_wrap() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        step(&mut ppu, CYCLES_PER_LINE * 144);
+
+        assert_eq!(ppu.mode(), PpuMode::VBlank);
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 144u8);
+        assert_eq!(ppu.frames(), 1);
+
+        step(&mut ppu, CYCLES_PER_LINE * 10);
+
+        assert_eq!(ppu.mode(), PpuMode::OamScan);
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 0u8);
+        assert_eq!(ppu.frames(), 1);
+    }
+
+    #[test]
+    fn lcd_off_resets() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        step(&mut ppu, CYCLES_PER_LINE * 5 + 30);
+
+        ppu.write(LCDC_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 0u8);
+        assert_eq!(ppu.mode(), PpuMode::HBlank);
+
+        step(&mut ppu, CYCLES_PER_FRAME);
+
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 0u8);
+        assert_eq!(ppu.frames(), 0);
+    }
+
+    #[test]
+    fn coincidence_flag() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LYC_ADDRESS, 0x02u8).unwrap();
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(ppu.read(STAT_ADDRESS).unwrap() & 0x04u8, 0x00u8);
+
+        step(&mut ppu, CYCLES_PER_LINE * 2);
+
+        assert_eq!(ppu.read(STAT_ADDRESS).unwrap() & 0x04u8, 0x04u8);
+
+        // LY is read-only
+        ppu.write(LY_ADDRESS, 0x10u8).unwrap();
+
+        assert_eq!(ppu.read(LY_ADDRESS).unwrap(), 2u8);
+    }
+}
+
+mod interrupts {
+    use super::*;
+
+    use emulation::interrupt::Interrupt;
+
+    /// Steps until the PPU requests something, returning the cycle count.
+    fn step_until_request(ppu: &mut PpuComponent) -> (usize, u8) {
+        for cycle in 1..=CYCLES_PER_FRAME {
+            let requests = ppu.step();
+
+            if requests > 0 {
+                return (cycle, requests);
+            }
+        }
+
+        panic!("invalid state");
+    }
+
+    #[test]
+    fn vblank() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(step_until_request(&mut ppu), (CYCLES_PER_LINE * 144, Interrupt::VBlank as u8));
+    }
+
+    #[test]
+    fn stat_hblank() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(STAT_ADDRESS, 0x08u8).unwrap();
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(step_until_request(&mut ppu), (63, Interrupt::LcdStat as u8));
+        assert_eq!(step_until_request(&mut ppu), (CYCLES_PER_LINE, Interrupt::LcdStat as u8));
+    }
+
+    #[test]
+    fn stat_coincidence() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(LYC_ADDRESS, 0x03u8).unwrap();
+        ppu.write(STAT_ADDRESS, 0x40u8).unwrap();
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(step_until_request(&mut ppu), (CYCLES_PER_LINE * 3, Interrupt::LcdStat as u8));
+    }
+
+    #[test]
+    fn stat_line_blocks() {
+        let mut ppu = PpuComponent::new();
+
+        // H-Blank on line 0 runs straight into LY=LYC on line 1, so line 1 has
+        // no rising edge of its own
+        ppu.write(LYC_ADDRESS, 0x01u8).unwrap();
+        ppu.write(STAT_ADDRESS, 0x48u8).unwrap();
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        assert_eq!(step_until_request(&mut ppu), (63, Interrupt::LcdStat as u8));
+        assert_eq!(step_until_request(&mut ppu), (CYCLES_PER_LINE * 2, Interrupt::LcdStat as u8));
+    }
+}
+
+mod access {
+    use super::*;
+
+    #[test]
+    fn vram_blocked_while_drawing() {
+        let mut ppu = PpuComponent::new();
+
+        ppu.write(0x8000u16, 0x12u8).unwrap();
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+
+        // OAM scan: VRAM open, OAM closed
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0x12u8);
+        assert_eq!(ppu.read(0xfe00u16).unwrap(), 0xffu8);
+
+        step(&mut ppu, 20);
+
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0xffu8);
+
+        ppu.write(0x8000u16, 0x34u8).unwrap();
+
+        step(&mut ppu, 43);
+
+        // H-Blank: everything open again
+        assert_eq!(ppu.read(0x8000u16).unwrap(), 0x12u8);
+        assert_eq!(ppu.read(0xfe00u16).unwrap(), 0x00u8);
+    }
+}
+
+mod background {
+    use super::*;
+
+    #[test]
+    fn scroll() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 1, 3);
+
+        // Second tile of the second map row
+        ppu.write(0x9800u16 + 32 + 1, 0x01u8).unwrap();
+
+        ppu.write(SCX_ADDRESS, 4u8).unwrap();
+        ppu.write(SCY_ADDRESS, 2u8).unwrap();
+
+        render(&mut ppu, LCDC_BACKGROUND);
+
+        assert_eq!(pixel(&ppu, 3, 6), 0u8);
+        assert_eq!(pixel(&ppu, 4, 6), 3u8);
+        assert_eq!(pixel(&ppu, 11, 13), 3u8);
+        assert_eq!(pixel(&ppu, 12, 13), 0u8);
+        assert_eq!(pixel(&ppu, 4, 14), 0u8);
+    }
+
+    #[test]
+    fn signed_tile_data() {
+        let mut ppu = PpuComponent::new();
+
+        // Tile 0 in 0x8800 addressing lives at 0x9000
+        for row in 0..8u16 {
+            ppu.write(0x9000u16 + row * 2, 0xffu8).unwrap();
+        }
+
+        render(&mut ppu, LCDC_BACKGROUND & !0x10u8);
+
+        assert_eq!(pixel(&ppu, 0, 0), 1u8);
+    }
+
+    #[test]
+    fn palette() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 0, 1);
+
+        ppu.write(LCDC_ADDRESS, LCDC_BACKGROUND).unwrap();
+        ppu.write(BGP_ADDRESS, 0b00001000u8).unwrap();
+
+        step(&mut ppu, CYCLES_PER_FRAME);
+
+        assert_eq!(pixel(&ppu, 0, 0), 2u8);
+    }
+
+    #[test]
+    fn window() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 1, 2);
+
+        // The window uses the 0x9C00 map
+        for i in 0..0x400u16 {
+            ppu.write(0x9c00u16 + i, 0x01u8).unwrap();
+        }
+
+        ppu.write(WY_ADDRESS, 10u8).unwrap();
+        ppu.write(WX_ADDRESS, 27u8).unwrap();
+
+        render(&mut ppu, LCDC_BACKGROUND | 0x60u8);
+
+        assert_eq!(pixel(&ppu, 30, 9), 0u8);
+        assert_eq!(pixel(&ppu, 19, 10), 0u8);
+        assert_eq!(pixel(&ppu, 20, 10), 2u8);
+        assert_eq!(pixel(&ppu, 159, 143), 2u8);
+    }
+}
+
+mod sprites {
+    use super::*;
+
+    #[test]
+    fn position_and_transparency() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 0, 1);
+
+        // Left column opaque, the rest transparent
+        for row in 0..8u16 {
+            ppu.write(0x8010u16 + row * 2, 0x80u8).unwrap();
+            ppu.write(0x8011u16 + row * 2, 0x80u8).unwrap();
+        }
+
+        place_sprite(&mut ppu, 0, 16 + 20, 8 + 30, 1, 0x00u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 30, 20), 3u8);
+        assert_eq!(pixel(&ppu, 31, 20), 1u8);
+        assert_eq!(pixel(&ppu, 30, 27), 3u8);
+        assert_eq!(pixel(&ppu, 30, 28), 1u8);
+    }
+
+    #[test]
+    fn flips() {
+        let mut ppu = PpuComponent::new();
+
+        // Only the top-left pixel is set
+        ppu.write(0x8010u16, 0x80u8).unwrap();
+        ppu.write(0x8011u16, 0x80u8).unwrap();
+
+        place_sprite(&mut ppu, 0, 16, 8, 1, 0x00u8);
+        place_sprite(&mut ppu, 1, 16, 8 + 10, 1, 0x20u8);
+        place_sprite(&mut ppu, 2, 16 + 10, 8, 1, 0x40u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 0, 0), 3u8);
+        assert_eq!(pixel(&ppu, 17, 0), 3u8);
+        assert_eq!(pixel(&ppu, 10, 0), 0u8);
+        assert_eq!(pixel(&ppu, 0, 17), 3u8);
+        assert_eq!(pixel(&ppu, 0, 10), 0u8);
+    }
+
+    #[test]
+    fn priority() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 1, 1);
+        solid_tile(&mut ppu, 2, 2);
+
+        // Overlapping: the leftmost object wins, then the earliest in OAM
+        place_sprite(&mut ppu, 0, 16, 8 + 4, 1, 0x00u8);
+        place_sprite(&mut ppu, 1, 16, 8, 2, 0x00u8);
+        place_sprite(&mut ppu, 2, 16 + 20, 8, 1, 0x00u8);
+        place_sprite(&mut ppu, 3, 16 + 20, 8, 2, 0x00u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 5, 0), 2u8);
+        assert_eq!(pixel(&ppu, 9, 0), 1u8);
+        assert_eq!(pixel(&ppu, 0, 20), 1u8);
+    }
+
+    #[test]
+    fn behind_background() {
+        let mut ppu = PpuComponent::new();
+
+        // Background is color 0 except the first tile
+        solid_tile(&mut ppu, 1, 2);
+        solid_tile(&mut ppu, 2, 3);
+
+        ppu.write(0x9800u16, 0x01u8).unwrap();
+
+        place_sprite(&mut ppu, 0, 16, 8 + 4, 2, 0x80u8);
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 4, 0), 2u8);
+        assert_eq!(pixel(&ppu, 8, 0), 3u8);
+    }
+
+    #[test]
+    fn second_palette() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 1, 1);
+
+        place_sprite(&mut ppu, 0, 16, 8, 1, 0x10u8);
+
+        ppu.write(OBP1_ADDRESS, 0b00001100u8).unwrap();
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 0, 0), 3u8);
+    }
+
+    #[test]
+    fn ten_per_line() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 1, 3);
+
+        for i in 0..12u16 {
+            place_sprite(&mut ppu, i, 16, 8 + i as u8 * 8, 1, 0x00u8);
+        }
+
+        render(&mut ppu, LCDC_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 79, 0), 3u8);
+        assert_eq!(pixel(&ppu, 80, 0), 0u8);
+        assert_eq!(pixel(&ppu, 88, 0), 0u8);
+    }
+
+    #[test]
+    fn tall_objects() {
+        let mut ppu = PpuComponent::new();
+
+        solid_tile(&mut ppu, 2, 1);
+        solid_tile(&mut ppu, 3, 2);
+
+        // Bit 0 of the tile number is ignored
+        place_sprite(&mut ppu, 0, 16, 8, 3, 0x00u8);
+        place_sprite(&mut ppu, 1, 16, 8 + 8, 3, 0x40u8);
+
+        render(&mut ppu, LCDC_TALL_OBJECTS);
+
+        assert_eq!(pixel(&ppu, 0, 0), 1u8);
+        assert_eq!(pixel(&ppu, 0, 15), 2u8);
+        assert_eq!(pixel(&ppu, 0, 16), 0u8);
+        assert_eq!(pixel(&ppu, 8, 0), 2u8);
+        assert_eq!(pixel(&ppu, 8, 15), 1u8);
+    }
+}
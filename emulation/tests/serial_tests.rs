@@ -0,0 +1,209 @@
+mod common;
+
+use emulation::{
+    interrupt::Interrupt,
+    serial::{CaptureLinkCable, LinkCable, LoopbackLinkCable, SerialTransferComponent},
+    MemoryComponent,
+};
+
+const SB_ADDRESS: u16 = 0xff01u16;
+const SC_ADDRESS: u16 = 0xff02u16;
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+
+const CYCLES_PER_TRANSFER: usize = 1024usize;
+
+const START_INTERNAL: u8 = 0x81u8;
+const START_EXTERNAL: u8 = 0x80u8;
+
+/// The far end of a cable, clocking a transfer after a few polls.
+struct ExternalClock {
+    incoming: u8,
+    outgoing: u8,
+    polls: usize,
+}
+
+impl LinkCable for ExternalClock {
+    fn transfer(&mut self, _: u8) -> u8 {
+        0xffu8
+    }
+
+    fn receive(&mut self, outgoing: u8) -> Option<u8> {
+        assert_eq!(outgoing, self.outgoing);
+
+        self.polls -= 1;
+
+        if self.polls == 0 {
+            Some(self.incoming)
+        } else {
+            None
+        }
+    }
+}
+
+/// Steps until the port requests something, returning the cycle count.
+fn step_until_request(serial: &mut SerialTransferComponent, limit: usize) -> Option<(usize, u8)> {
+    for cycle in 1..=limit {
+        let requests = serial.step();
+
+        if requests > 0 {
+            return Some((cycle, requests));
+        }
+    }
+
+    None
+}
+
+fn start_transfer(serial: &mut SerialTransferComponent, value: u8, control: u8) {
+    serial.write(SB_ADDRESS, value).unwrap();
+    serial.write(SC_ADDRESS, control).unwrap();
+}
+
+mod registers {
+    use super::*;
+
+    #[test]
+    fn unused_bits() {
+        let mut serial = SerialTransferComponent::new();
+
+        assert_eq!(serial.read(SC_ADDRESS).unwrap(), 0x7eu8);
+
+        serial.write(SC_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(serial.read(SC_ADDRESS).unwrap(), 0x7fu8);
+        assert!(!serial.transferring());
+    }
+
+    #[test]
+    fn data() {
+        let mut serial = SerialTransferComponent::new();
+
+        serial.write(SB_ADDRESS, 0x5au8).unwrap();
+
+        assert_eq!(serial.read(SB_ADDRESS).unwrap(), 0x5au8);
+    }
+}
+
+mod internal_clock {
+    use super::*;
+
+    #[test]
+    fn timing() {
+        let mut serial = SerialTransferComponent::new();
+
+        start_transfer(&mut serial, 0x42u8, START_INTERNAL);
+
+        assert!(serial.transferring());
+        assert_eq!(step_until_request(&mut serial, 10000), Some((CYCLES_PER_TRANSFER, Interrupt::Serial as u8)));
+        assert!(!serial.transferring());
+        assert_eq!(serial.read(SC_ADDRESS).unwrap(), 0x7fu8);
+    }
+
+    #[test]
+    fn disconnected() {
+        let mut serial = SerialTransferComponent::new();
+
+        start_transfer(&mut serial, 0x42u8, START_INTERNAL);
+
+        step_until_request(&mut serial, 10000);
+
+        assert_eq!(serial.read(SB_ADDRESS).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn capture() {
+        let mut serial = SerialTransferComponent::new();
+        let cable = CaptureLinkCable::new();
+
+        serial.connect(Box::new(cable.clone()));
+
+        for byte in b"ok" {
+            start_transfer(&mut serial, *byte, START_INTERNAL);
+
+            step_until_request(&mut serial, 10000);
+        }
+
+        assert_eq!(cable.captured_string(), "ok");
+        assert_eq!(serial.read(SB_ADDRESS).unwrap(), 0xffu8);
+    }
+
+    #[test]
+    fn loopback() {
+        let mut serial = SerialTransferComponent::new();
+
+        serial.connect(Box::new(LoopbackLinkCable::new()));
+
+        start_transfer(&mut serial, 0x42u8, START_INTERNAL);
+
+        step_until_request(&mut serial, 10000);
+
+        assert_eq!(serial.read(SB_ADDRESS).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn restart() {
+        let mut serial = SerialTransferComponent::new();
+
+        start_transfer(&mut serial, 0x42u8, START_INTERNAL);
+
+        step_until_request(&mut serial, 500);
+
+        // Starting again begins a fresh 8 bits
+        serial.write(SC_ADDRESS, START_INTERNAL).unwrap();
+
+        assert_eq!(step_until_request(&mut serial, 10000), Some((CYCLES_PER_TRANSFER, Interrupt::Serial as u8)));
+    }
+}
+
+mod external_clock {
+    use super::*;
+
+    #[test]
+    fn waits_forever_unplugged() {
+        let mut serial = SerialTransferComponent::new();
+
+        start_transfer(&mut serial, 0x42u8, START_EXTERNAL);
+
+        assert_eq!(step_until_request(&mut serial, 100000), None);
+        assert!(serial.transferring());
+    }
+
+    #[test]
+    fn driven_by_other_end() {
+        let mut serial = SerialTransferComponent::new();
+
+        serial.connect(Box::new(ExternalClock {
+            incoming: 0x99u8,
+            outgoing: 0x42u8,
+            polls: 3,
+        }));
+
+        start_transfer(&mut serial, 0x42u8, START_EXTERNAL);
+
+        assert_eq!(step_until_request(&mut serial, 100), Some((3, Interrupt::Serial as u8)));
+        assert_eq!(serial.read(SB_ADDRESS).unwrap(), 0x99u8);
+    }
+}
+
+mod emulator {
+    use super::*;
+
+    #[test]
+    fn requests_interrupt() {
+        let mut emulator = common::program_emulator(&[]);
+        let cable = CaptureLinkCable::new();
+
+        emulator.add_memory_component(Box::new(SerialTransferComponent::new()));
+        emulator.connect_link_cable(Box::new(cable.clone()));
+
+        emulator.write(SB_ADDRESS, 0x21u8).unwrap();
+        emulator.write(SC_ADDRESS, START_INTERNAL).unwrap();
+
+        for _ in 0..CYCLES_PER_TRANSFER {
+            emulator.tick();
+        }
+
+        assert_eq!(emulator.memory_location(INTERRUPT_FLAG_ADDRESS) & 0x1fu8, Interrupt::Serial as u8);
+        assert_eq!(cable.captured(), vec![0x21u8]);
+    }
+}
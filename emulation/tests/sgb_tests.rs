@@ -0,0 +1,472 @@
+mod common;
+
+use common::{BGP_ADDRESS, LCDC_ADDRESS};
+use emulation::{
+    display::RgbConverter,
+    joypad::P1_ADDRESS,
+    ppu::{SCREEN_HEIGHT, SCREEN_WIDTH},
+    sgb::{SgbComponent, SgbMask, SGB_SCREEN_HEIGHT, SGB_SCREEN_WIDTH},
+    Cartridge,
+    Emulator,
+    Model,
+    CYCLES_PER_FRAME,
+};
+
+const DEFAULT_PALETTE: [u16; 4] = [0x67bfu16, 0x265bu16, 0x10b5u16, 0x2866u16];
+
+// PAL01 setting color 0 to 0x0011
+const PAL01_PACKET: [u8; 16] = [
+    0x01, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+fn sgb_emulator(sgb_support: bool) -> Emulator {
+    let mut emulator = Emulator::builder().model(Model::Sgb).build();
+    let rom = common::test_rom().sgb_support(sgb_support).build();
+
+    emulator.load_cartridge(Cartridge::from_bytes(&rom).unwrap());
+
+    emulator
+}
+
+fn enabled_sgb() -> SgbComponent {
+    let mut sgb = SgbComponent::new();
+
+    sgb.set_enabled(true);
+
+    sgb
+}
+
+/// The P1 writes that send a packet: a reset pulse, then a pulse on P15 for
+/// each 1 and on P14 for each 0, lowest bit first, then a 0 stop bit.
+fn packet_writes(packet: &[u8]) -> Vec<u8> {
+    let mut writes = vec![0x00u8, 0x30u8];
+
+    for bit in 0..128 {
+        let one = packet[bit / 8] & (1 << (bit % 8)) > 0;
+
+        writes.push(if one { 0x10u8 } else { 0x20u8 });
+        writes.push(0x30u8);
+    }
+
+    writes.extend([0x20u8, 0x30u8]);
+
+    writes
+}
+
+/// Sends a command, padded out to whole packets.
+fn send_command(sgb: &mut SgbComponent, command: &[u8]) {
+    for packet in command.chunks(16) {
+        let mut padded = [0x00u8; 16];
+
+        padded[..packet.len()].copy_from_slice(packet);
+
+        for value in packet_writes(&padded) {
+            sgb.write_joypad(value);
+        }
+    }
+}
+
+fn send_emulator_command(emulator: &mut Emulator, command: &[u8]) {
+    let mut packet = [0x00u8; 16];
+
+    packet[..command.len()].copy_from_slice(command);
+
+    for value in packet_writes(&packet) {
+        emulator.write(P1_ADDRESS, value).unwrap();
+    }
+}
+
+/// Gives palette N color N + 1 for shade 1, so a cell's color shows which
+/// palette it uses.
+fn distinct_palettes(sgb: &mut SgbComponent) {
+    send_command(sgb, &[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00]);
+    send_command(sgb, &[0x09, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00]);
+}
+
+fn solid_frame(shade: u8) -> Vec<u8> {
+    vec![shade; SCREEN_WIDTH * SCREEN_HEIGHT]
+}
+
+/// A frame showing `data` as its first 256 tiles, the way a game sends a
+/// VRAM transfer.
+fn transfer_frame(data: &[u8]) -> Vec<u8> {
+    let mut frame = solid_frame(0);
+
+    for (tile, bytes) in data.chunks(16).enumerate() {
+        let (left, top) = ((tile % 20) * 8, (tile / 20) * 8);
+
+        for row in 0..8 {
+            for column in 0..8 {
+                let low = (bytes[row * 2] >> (7 - column)) & 0b1;
+                let high = (bytes[row * 2 + 1] >> (7 - column)) & 0b1;
+
+                frame[(top + row) * SCREEN_WIDTH + left + column] = low | (high << 1);
+            }
+        }
+    }
+
+    frame
+}
+
+/// A pixel of the game screen, within the border.
+fn game_pixel(framebuffer: &[u16], x: usize, y: usize) -> u16 {
+    framebuffer[(40 + y) * SGB_SCREEN_WIDTH + 48 + x]
+}
+
+/// The palette a cell is drawn with, with `distinct_palettes` and a screen of
+/// shade 1.
+fn cell_palette(sgb: &SgbComponent, x: usize, y: usize) -> u16 {
+    game_pixel(&sgb.framebuffer(), x * 8, y * 8) - 1
+}
+
+mod packets {
+    use super::*;
+
+    #[test]
+    fn sets_palettes() {
+        let mut sgb = enabled_sgb();
+
+        let colors = [0x11u8, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00, 0x05, 0x00, 0x06, 0x00];
+
+        send_command(&mut sgb, &[&[0x01u8], &colors[..]].concat());
+
+        assert_eq!(sgb.palette(0), [0x0011u16, 0x0001, 0x0002, 0x0003]);
+        assert_eq!(sgb.palette(1), [0x0011u16, 0x0004, 0x0005, 0x0006]);
+        assert_eq!(sgb.palette(2), [0x0011u16, DEFAULT_PALETTE[1], DEFAULT_PALETTE[2], DEFAULT_PALETTE[3]]);
+    }
+
+    #[test]
+    fn waits_for_every_packet() {
+        let mut sgb = enabled_sgb();
+
+        // ATTR_BLK in two packets, with the only set in the second
+        let mut command = [0x00u8; 32];
+
+        command[..2].copy_from_slice(&[0x22, 0x04]);
+        command[20..26].copy_from_slice(&[0x04, 0x10, 0x00, 0x00, 0x00, 0x00]);
+
+        distinct_palettes(&mut sgb);
+        sgb.receive_frame(1, &solid_frame(1));
+
+        send_command(&mut sgb, &command[..16]);
+
+        assert_eq!(cell_palette(&sgb, 10, 10), 0);
+
+        send_command(&mut sgb, &command[16..]);
+
+        assert_eq!(cell_palette(&sgb, 10, 10), 1);
+    }
+
+    #[test]
+    fn needs_stop_bit() {
+        let mut sgb = enabled_sgb();
+
+        let mut writes = packet_writes(&PAL01_PACKET);
+
+        let length = writes.len();
+
+        writes[length - 2] = 0x10u8;
+
+        for value in writes {
+            sgb.write_joypad(value);
+        }
+
+        assert_eq!(sgb.palette(0), DEFAULT_PALETTE);
+    }
+
+    #[test]
+    fn drops_packet_spanning_frames() {
+        let mut sgb = enabled_sgb();
+
+        let writes = packet_writes(&PAL01_PACKET);
+
+        for (index, value) in writes.into_iter().enumerate() {
+            if index == 100 {
+                sgb.receive_frame(1, &solid_frame(0));
+                sgb.receive_frame(2, &solid_frame(0));
+            }
+
+            sgb.write_joypad(value);
+        }
+
+        assert_eq!(sgb.palette(0), DEFAULT_PALETTE);
+    }
+
+    #[test]
+    fn ignored_without_sgb_support() {
+        let mut emulator = sgb_emulator(false);
+
+        send_emulator_command(&mut emulator, &[0x01, 0x11, 0x00]);
+
+        assert_eq!(emulator.memory_component::<SgbComponent>().unwrap().palette(0), DEFAULT_PALETTE);
+    }
+
+    #[test]
+    fn through_joypad_register() {
+        let mut emulator = sgb_emulator(true);
+
+        send_emulator_command(&mut emulator, &[0x01, 0x11, 0x00]);
+
+        assert_eq!(emulator.memory_component::<SgbComponent>().unwrap().palette(0)[0], 0x0011u16);
+    }
+}
+
+mod attributes {
+    use super::*;
+
+    fn sgb() -> SgbComponent {
+        let mut sgb = enabled_sgb();
+
+        distinct_palettes(&mut sgb);
+        sgb.receive_frame(1, &solid_frame(1));
+
+        sgb
+    }
+
+    #[test]
+    fn block() {
+        let mut sgb = sgb();
+
+        // Inside only, which takes the line with it
+        send_command(&mut sgb, &[0x21, 0x01, 0x01, 0x01, 0x01, 0x01, 0x03, 0x03]);
+
+        assert_eq!(cell_palette(&sgb, 2, 2), 1);
+        assert_eq!(cell_palette(&sgb, 1, 1), 1);
+        assert_eq!(cell_palette(&sgb, 3, 2), 1);
+        assert_eq!(cell_palette(&sgb, 0, 0), 0);
+        assert_eq!(cell_palette(&sgb, 4, 2), 0);
+    }
+
+    #[test]
+    fn block_with_line_and_outside() {
+        let mut sgb = sgb();
+
+        send_command(&mut sgb, &[0x21, 0x01, 0x07, 0x39, 0x01, 0x01, 0x03, 0x03]);
+
+        assert_eq!(cell_palette(&sgb, 2, 2), 1);
+        assert_eq!(cell_palette(&sgb, 1, 3), 2);
+        assert_eq!(cell_palette(&sgb, 5, 5), 3);
+    }
+
+    #[test]
+    fn lines() {
+        let mut sgb = sgb();
+
+        // Row 3 with palette 2, then column 5 with palette 1
+        send_command(&mut sgb, &[0x29, 0x02, 0xc3, 0x25]);
+
+        assert_eq!(cell_palette(&sgb, 0, 3), 2);
+        assert_eq!(cell_palette(&sgb, 19, 3), 2);
+        assert_eq!(cell_palette(&sgb, 5, 3), 1);
+        assert_eq!(cell_palette(&sgb, 5, 17), 1);
+        assert_eq!(cell_palette(&sgb, 0, 0), 0);
+    }
+
+    #[test]
+    fn division() {
+        let mut sgb = sgb();
+
+        // Split at row 5: palette 1 above, 3 on the line, 2 below
+        send_command(&mut sgb, &[0x31, 0x76, 0x05]);
+
+        assert_eq!(cell_palette(&sgb, 0, 4), 1);
+        assert_eq!(cell_palette(&sgb, 0, 5), 3);
+        assert_eq!(cell_palette(&sgb, 0, 6), 2);
+    }
+
+    #[test]
+    fn characters() {
+        let mut sgb = sgb();
+
+        // Three cells from (18, 0), wrapping onto the next row
+        send_command(&mut sgb, &[0x39, 0x12, 0x00, 0x03, 0x00, 0x00, 0x6c]);
+
+        assert_eq!(cell_palette(&sgb, 18, 0), 1);
+        assert_eq!(cell_palette(&sgb, 19, 0), 2);
+        assert_eq!(cell_palette(&sgb, 0, 1), 3);
+        assert_eq!(cell_palette(&sgb, 1, 1), 0);
+    }
+
+    #[test]
+    fn attribute_files() {
+        let mut sgb = sgb();
+
+        // File 1 has every cell on palette 3
+        let mut data = vec![0x00u8; 4096];
+
+        data[90..180].fill(0xff);
+
+        send_command(&mut sgb, &[0xa9]);
+        sgb.receive_frame(2, &transfer_frame(&data));
+
+        send_command(&mut sgb, &[0xb1, 0x01]);
+        sgb.receive_frame(3, &solid_frame(1));
+
+        assert_eq!(cell_palette(&sgb, 7, 7), 3);
+    }
+}
+
+mod transfers {
+    use super::*;
+
+    #[test]
+    fn system_palettes() {
+        let mut sgb = enabled_sgb();
+
+        let colors = [0x0102u16, 0x0304, 0x0506, 0x0708];
+
+        let mut data = vec![0x00u8; 4096];
+
+        for (color, value) in colors.iter().enumerate() {
+            data[3 * 8 + color * 2..3 * 8 + color * 2 + 2].copy_from_slice(&value.to_le_bytes());
+        }
+
+        send_command(&mut sgb, &[0x59]);
+        sgb.receive_frame(1, &transfer_frame(&data));
+
+        // PAL_SET with system palette 3 for every palette
+        send_command(&mut sgb, &[0x51, 0x03, 0x00, 0x03, 0x00, 0x03, 0x00, 0x03, 0x00, 0x00]);
+
+        for palette in 0..4 {
+            assert_eq!(sgb.palette(palette), colors);
+        }
+    }
+
+    #[test]
+    fn border() {
+        let mut sgb = enabled_sgb();
+
+        // Tile 1 is color 1 all over
+        let mut tiles = vec![0x00u8; 4096];
+
+        for row in 0..8 {
+            tiles[32 + row * 2] = 0xff;
+        }
+
+        send_command(&mut sgb, &[0x99, 0x00]);
+        sgb.receive_frame(1, &transfer_frame(&tiles));
+
+        // The top left corner is tile 1 with palette 4, whose color 1 is red
+        let mut map = vec![0x00u8; 4096];
+
+        map[..2].copy_from_slice(&0x1001u16.to_le_bytes());
+        map[0x802..0x804].copy_from_slice(&0x001fu16.to_le_bytes());
+
+        send_command(&mut sgb, &[0xa1]);
+        sgb.receive_frame(2, &transfer_frame(&map));
+
+        let framebuffer = sgb.framebuffer();
+
+        assert_eq!(framebuffer.len(), SGB_SCREEN_WIDTH * SGB_SCREEN_HEIGHT);
+        assert_eq!(framebuffer[0], 0x001fu16);
+        assert_eq!(framebuffer[7 * SGB_SCREEN_WIDTH + 7], 0x001fu16);
+
+        // Color 0 is transparent
+        assert_eq!(framebuffer[8], DEFAULT_PALETTE[0]);
+    }
+}
+
+mod masks {
+    use super::*;
+
+    #[test]
+    fn black() {
+        let mut sgb = enabled_sgb();
+
+        sgb.receive_frame(1, &solid_frame(3));
+
+        send_command(&mut sgb, &[0xb9, 0x02]);
+
+        assert_eq!(sgb.mask(), SgbMask::Black);
+        assert_eq!(game_pixel(&sgb.framebuffer(), 0, 0), 0x0000u16);
+    }
+
+    #[test]
+    fn freeze() {
+        let mut sgb = enabled_sgb();
+
+        sgb.receive_frame(1, &solid_frame(3));
+
+        send_command(&mut sgb, &[0xb9, 0x01]);
+
+        sgb.receive_frame(2, &solid_frame(2));
+
+        assert_eq!(game_pixel(&sgb.framebuffer(), 0, 0), DEFAULT_PALETTE[3]);
+
+        send_command(&mut sgb, &[0xb9, 0x00]);
+
+        sgb.receive_frame(3, &solid_frame(2));
+
+        assert_eq!(game_pixel(&sgb.framebuffer(), 0, 0), DEFAULT_PALETTE[2]);
+    }
+}
+
+mod multiplayer {
+    use super::*;
+
+    #[test]
+    fn reads_joypad_ids() {
+        let mut emulator = sgb_emulator(true);
+
+        send_emulator_command(&mut emulator, &[0x89, 0x01]);
+
+        emulator.write(P1_ADDRESS, 0x30u8).unwrap();
+
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0f, 0x0fu8);
+
+        emulator.write(P1_ADDRESS, 0x10u8).unwrap();
+        emulator.write(P1_ADDRESS, 0x30u8).unwrap();
+
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0f, 0x0eu8);
+
+        emulator.write(P1_ADDRESS, 0x10u8).unwrap();
+        emulator.write(P1_ADDRESS, 0x30u8).unwrap();
+
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0f, 0x0fu8);
+    }
+
+    #[test]
+    fn single_player_reads_no_id() {
+        let mut emulator = sgb_emulator(true);
+
+        emulator.write(P1_ADDRESS, 0x10u8).unwrap();
+        emulator.write(P1_ADDRESS, 0x30u8).unwrap();
+
+        assert_eq!(emulator.memory_location(P1_ADDRESS) & 0x0f, 0x0fu8);
+        assert!(emulator.memory_component::<SgbComponent>().unwrap().player().is_none());
+    }
+}
+
+mod frames {
+    use super::*;
+
+    #[test]
+    fn composites_ppu_frames() {
+        let mut emulator = sgb_emulator(true);
+
+        emulator.write(BGP_ADDRESS, 0xffu8).unwrap();
+        emulator.write(LCDC_ADDRESS, 0x91u8).unwrap();
+
+        for _ in 0..CYCLES_PER_FRAME * 2 {
+            emulator.tick();
+        }
+
+        let framebuffer = emulator.sgb_framebuffer().unwrap();
+
+        assert_eq!(game_pixel(&framebuffer, 0, 0), DEFAULT_PALETTE[3]);
+        assert_eq!(game_pixel(&framebuffer, 159, 143), DEFAULT_PALETTE[3]);
+        assert_eq!(framebuffer[0], DEFAULT_PALETTE[0]);
+    }
+
+    #[test]
+    fn converts_to_rgb() {
+        let frame = RgbConverter::new().frame_rgb(&sgb_emulator(true)).unwrap();
+
+        assert_eq!(frame.len(), SGB_SCREEN_WIDTH * SGB_SCREEN_HEIGHT * 3);
+    }
+
+    #[test]
+    fn only_on_sgb() {
+        assert!(Emulator::default().sgb_framebuffer().is_none());
+    }
+}
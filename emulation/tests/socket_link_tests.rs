@@ -0,0 +1,167 @@
+mod common;
+
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::UnixStream,
+    time::{Duration, Instant},
+};
+
+use emulation::serial::{SerialTransferComponent, SocketLinkCable};
+
+#[cfg(unix)]
+use emulation::serial::LinkCable;
+
+const SB_ADDRESS: u16 = 0xff01u16;
+const SC_ADDRESS: u16 = 0xff02u16;
+
+const RUN_CYCLES: usize = 8192usize;
+const SYNC_INTERVAL: usize = 256usize;
+
+/// LD A, n; LD (SB), A; LD A, control; LD (SC), A; then spin.
+fn transfer_program(value: u8, control: u8) -> Vec<u8> {
+    vec![0x3eu8, value, 0xe0u8, 0x01u8, 0x3eu8, control, 0xe0u8, 0x02u8, 0x18u8, 0xfeu8]
+}
+
+/// Runs one side of the link, returning the byte it received and the cycle
+/// its transfer finished on.
+fn run_side<S: Read + Write + 'static>(stream: S, value: u8, control: u8) -> (u8, Option<usize>) {
+    let mut emulator = common::program_emulator(&transfer_program(value, control));
+
+    emulator.add_memory_component(Box::new(SerialTransferComponent::new()));
+    emulator.connect_link_cable(Box::new(SocketLinkCable::new(stream).with_sync_interval(SYNC_INTERVAL)));
+
+    let mut started = false;
+    let mut finished = None;
+
+    while emulator.cycles() < RUN_CYCLES {
+        emulator.process_opcode().unwrap();
+
+        let transferring = emulator.memory_location(SC_ADDRESS) & 0x80u8 > 0;
+
+        if transferring {
+            started = true;
+        } else if started && finished.is_none() {
+            finished = Some(emulator.cycles());
+        }
+    }
+
+    // Keep syncing until the other side is done too
+    while emulator.cycles() < RUN_CYCLES * 2 {
+        emulator.tick();
+    }
+
+    (emulator.memory_location(SB_ADDRESS), finished)
+}
+
+fn run_pair<S: Read + Write + Send + 'static>(master: S, slave: S) -> ((u8, Option<usize>), (u8, Option<usize>)) {
+    let master = thread::spawn(move || run_side(master, 0x42u8, 0x81u8));
+    let slave = thread::spawn(move || run_side(slave, 0x99u8, 0x80u8));
+
+    (master.join().unwrap(), slave.join().unwrap())
+}
+
+#[cfg(unix)]
+fn unix_pair() -> ((u8, Option<usize>), (u8, Option<usize>)) {
+    let (master, slave) = UnixStream::pair().unwrap();
+
+    run_pair(master, slave)
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_exchange() {
+    let ((master_byte, master_finished), (slave_byte, slave_finished)) = unix_pair();
+
+    assert_eq!(master_byte, 0x99u8);
+    assert_eq!(slave_byte, 0x42u8);
+
+    match (master_finished, slave_finished) {
+        (Some(master_finished), Some(slave_finished)) => assert!(slave_finished >= master_finished),
+        _ => panic!("invalid state"),
+    };
+}
+
+#[cfg(unix)]
+#[test]
+fn deterministic() {
+    let first = unix_pair();
+
+    for _ in 0..3 {
+        assert_eq!(unix_pair(), first);
+    }
+}
+
+#[test]
+fn tcp_exchange() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let slave = TcpStream::connect(address).unwrap();
+    let (master, _) = listener.accept().unwrap();
+
+    let ((master_byte, _), (slave_byte, _)) = run_pair(master, slave);
+
+    assert_eq!(master_byte, 0x99u8);
+    assert_eq!(slave_byte, 0x42u8);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_listen_and_connect() {
+    let path = std::env::temp_dir().join(format!("gameboy_rust_link_{}.sock", std::process::id()));
+
+    let _ = std::fs::remove_file(&path);
+
+    let listen_path = path.clone();
+    let listener = thread::spawn(move || SocketLinkCable::listen_unix(listen_path).unwrap().connected());
+
+    // Wait for the socket to appear
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while SocketLinkCable::connect_unix(&path).is_err() {
+        assert!(Instant::now() < deadline, "listener never appeared");
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(listener.join().unwrap());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(unix)]
+#[test]
+fn peer_hangs_up() {
+    let (master, slave) = UnixStream::pair().unwrap();
+
+    drop(slave);
+
+    let (byte, finished) = run_side(master, 0x42u8, 0x81u8);
+
+    // Unplugged: the transfer still completes, reading 0xFF
+    assert_eq!(byte, 0xffu8);
+    assert!(finished.is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn hang_up_error_kept() {
+    let (master, slave) = UnixStream::pair().unwrap();
+
+    drop(slave);
+
+    let mut link_cable = SocketLinkCable::new(master).with_sync_interval(1);
+
+    assert!(link_cable.last_error().is_none());
+
+    link_cable.step();
+
+    assert!(!link_cable.connected());
+    assert!(link_cable.last_error().is_some());
+}
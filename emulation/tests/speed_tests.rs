@@ -0,0 +1,163 @@
+use emulation::{Emulator, Model};
+
+const SC_ADDRESS: u16 = 0xff02u16;
+const DIV_ADDRESS: u16 = 0xff04u16;
+const NR11_ADDRESS: u16 = 0xff11u16;
+const NR12_ADDRESS: u16 = 0xff12u16;
+const NR14_ADDRESS: u16 = 0xff14u16;
+const NR52_ADDRESS: u16 = 0xff26u16;
+const LCDC_ADDRESS: u16 = 0xff40u16;
+const LY_ADDRESS: u16 = 0xff44u16;
+const KEY1_ADDRESS: u16 = 0xff4du16;
+
+const WORK_RAM_START_ADDRESS: u16 = 0xc000u16;
+
+const CYCLES_PER_LINE: usize = 114usize;
+
+const STOP_OPCODE: u8 = 0x10u8;
+
+fn cgb_emulator() -> Emulator {
+    Emulator::builder().model(Model::Cgb).build()
+}
+
+/// A CGB that has already gone through a speed switch.
+fn double_speed_emulator() -> Emulator {
+    let mut emulator = cgb_emulator();
+
+    emulator.write(KEY1_ADDRESS, 0x01u8).unwrap();
+    emulator.switch_speed();
+
+    emulator
+}
+
+fn tick(emulator: &mut Emulator, cycles: usize) {
+    for _ in 0..cycles {
+        emulator.tick();
+    }
+}
+
+/// Ticks until `done`, returning how many cycles that took.
+fn cycles_until(emulator: &mut Emulator, done: impl Fn(&Emulator) -> bool) -> usize {
+    let mut cycles = 0usize;
+
+    while !done(emulator) {
+        emulator.tick();
+
+        cycles += 1;
+    }
+
+    cycles
+}
+
+mod switching {
+    use super::*;
+
+    #[test]
+    fn starts_at_normal_speed() {
+        assert!(!cgb_emulator().double_speed());
+        assert!(!Emulator::default().double_speed());
+    }
+
+    #[test]
+    fn stop_switches_when_armed() {
+        let mut emulator = cgb_emulator();
+
+        emulator.write(WORK_RAM_START_ADDRESS, STOP_OPCODE).unwrap();
+        emulator.write(KEY1_ADDRESS, 0x01u8).unwrap();
+        emulator.set_program_counter(WORK_RAM_START_ADDRESS);
+
+        emulator.process_opcode().unwrap();
+
+        assert!(emulator.double_speed());
+        assert_eq!(emulator.memory_location(KEY1_ADDRESS), 0xfeu8);
+    }
+
+    #[test]
+    fn switches_back() {
+        let mut emulator = double_speed_emulator();
+
+        emulator.write(KEY1_ADDRESS, 0x01u8).unwrap();
+        emulator.switch_speed();
+
+        assert!(!emulator.double_speed());
+        assert_eq!(emulator.memory_location(KEY1_ADDRESS), 0x7eu8);
+    }
+}
+
+mod rates {
+    use super::*;
+
+    #[test]
+    fn ppu_keeps_real_time() {
+        let mut emulator = double_speed_emulator();
+
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+
+        tick(&mut emulator, CYCLES_PER_LINE);
+
+        assert_eq!(emulator.memory_location(LY_ADDRESS), 0x00u8);
+
+        tick(&mut emulator, CYCLES_PER_LINE);
+
+        assert_eq!(emulator.memory_location(LY_ADDRESS), 0x01u8);
+    }
+
+    #[test]
+    fn timer_follows_cpu() {
+        let mut emulator = double_speed_emulator();
+
+        // Writing resets DIV
+        emulator.write(DIV_ADDRESS, 0x00u8).unwrap();
+
+        tick(&mut emulator, 64);
+
+        assert_eq!(emulator.memory_location(DIV_ADDRESS), 0x01u8);
+    }
+
+    #[test]
+    fn serial_internal_clock_keeps_real_time() {
+        let mut emulator = double_speed_emulator();
+
+        emulator.write(SC_ADDRESS, 0x81u8).unwrap();
+
+        let cycles = cycles_until(&mut emulator, |emulator| emulator.memory_location(SC_ADDRESS) & 0x80u8 == 0);
+
+        assert_eq!(cycles, 8 * 128 * 2);
+    }
+
+    #[test]
+    fn apu_keeps_real_time() {
+        // A length of 1 runs out on the first length clock
+        fn length_expiry(emulator: &mut Emulator) -> usize {
+            emulator.write(NR52_ADDRESS, 0x80u8).unwrap();
+            emulator.write(NR11_ADDRESS, 0x3fu8).unwrap();
+            emulator.write(NR12_ADDRESS, 0xf0u8).unwrap();
+            emulator.write(NR14_ADDRESS, 0xc0u8).unwrap();
+
+            cycles_until(emulator, |emulator| emulator.memory_location(NR52_ADDRESS) & 0x01u8 == 0)
+        }
+
+        let normal = length_expiry(&mut cgb_emulator());
+        let double = length_expiry(&mut double_speed_emulator());
+
+        assert!(double.abs_diff(normal * 2) <= 2, "{} cycles at double speed, {} at normal", double, normal);
+    }
+
+    #[test]
+    fn frames_take_twice_the_cycles() {
+        let mut emulator = double_speed_emulator();
+
+        emulator.write(WORK_RAM_START_ADDRESS, 0x18u8).unwrap();
+        emulator.write(WORK_RAM_START_ADDRESS + 1, 0xfeu8).unwrap();
+        emulator.set_program_counter(WORK_RAM_START_ADDRESS);
+        emulator.write(LCDC_ADDRESS, 0x80u8).unwrap();
+
+        emulator.run_frame().unwrap();
+
+        let start = emulator.cycles();
+
+        emulator.run_frame().unwrap();
+
+        assert!(emulator.cycles() - start >= emulation::CYCLES_PER_FRAME * 2 - 4);
+    }
+}
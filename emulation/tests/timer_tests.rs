@@ -0,0 +1,217 @@
+mod common;
+
+use emulation::{
+    interrupt::Interrupt,
+    timer::TimerComponent,
+    MemoryComponent,
+};
+
+const DIV_ADDRESS: u16 = 0xff04u16;
+const TIMA_ADDRESS: u16 = 0xff05u16;
+const TMA_ADDRESS: u16 = 0xff06u16;
+const TAC_ADDRESS: u16 = 0xff07u16;
+
+const INTERRUPT_FLAG_ADDRESS: u16 = 0xff0fu16;
+const INTERRUPT_ENABLE_ADDRESS: u16 = 0xffffu16;
+
+const STOP_OPCODE: u8 = 0x10u8;
+
+/// Steps the timer, returning the interrupts it requested along the way.
+fn step(timer: &mut TimerComponent, cycles: usize) -> u8 {
+    (0..cycles).fold(0x00u8, |requests, _| requests | timer.step())
+}
+
+/// A timer with TIMA about to overflow, counting every 4 cycles.
+fn overflowing_timer() -> TimerComponent {
+    let mut timer = TimerComponent::new();
+
+    timer.write(TIMA_ADDRESS, 0xffu8).unwrap();
+    timer.write(TMA_ADDRESS, 0x42u8).unwrap();
+    timer.write(TAC_ADDRESS, 0x05u8).unwrap();
+
+    // TIMA overflows on this cycle and reads 0 until the next one
+    step(&mut timer, 3);
+
+    assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0xffu8);
+    assert_eq!(step(&mut timer, 1), 0x00u8);
+    assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x00u8);
+
+    timer
+}
+
+mod divider {
+    use super::*;
+
+    #[test]
+    fn increments() {
+        let mut timer = TimerComponent::new();
+
+        step(&mut timer, 63);
+
+        assert_eq!(timer.read(DIV_ADDRESS).unwrap(), 0x00u8);
+
+        step(&mut timer, 1);
+
+        assert_eq!(timer.read(DIV_ADDRESS).unwrap(), 0x01u8);
+
+        step(&mut timer, 64 * 0xff);
+
+        assert_eq!(timer.read(DIV_ADDRESS).unwrap(), 0x00u8);
+    }
+
+    #[test]
+    fn write_resets() {
+        let mut timer = TimerComponent::new();
+
+        step(&mut timer, 1000);
+
+        timer.write(DIV_ADDRESS, 0xabu8).unwrap();
+
+        assert_eq!(timer.read(DIV_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(timer.divider(), 0x0000u16);
+    }
+
+    #[test]
+    fn stop_resets() {
+        let mut emulator = common::program_emulator(&[STOP_OPCODE, 0x00u8]);
+
+        emulator.add_memory_component(Box::new(TimerComponent::new()));
+
+        for _ in 0..1000 {
+            emulator.tick();
+        }
+
+        emulator.process_opcode().unwrap();
+
+        assert_eq!(emulator.memory_location(DIV_ADDRESS), 0x00u8);
+    }
+}
+
+mod counter {
+    use super::*;
+
+    fn rate(tac: u8) -> usize {
+        let mut timer = TimerComponent::new();
+
+        timer.write(TAC_ADDRESS, tac).unwrap();
+
+        let mut cycles = 0usize;
+
+        while timer.read(TIMA_ADDRESS).unwrap() == 0x00u8 {
+            step(&mut timer, 1);
+
+            cycles += 1;
+        }
+
+        cycles
+    }
+
+    #[test]
+    fn rates() {
+        assert_eq!(rate(0x04u8), 256);
+        assert_eq!(rate(0x05u8), 4);
+        assert_eq!(rate(0x06u8), 16);
+        assert_eq!(rate(0x07u8), 64);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut timer = TimerComponent::new();
+
+        timer.write(TAC_ADDRESS, 0x01u8).unwrap();
+
+        step(&mut timer, 1000);
+
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x00u8);
+        assert_eq!(timer.read(TAC_ADDRESS).unwrap(), 0xf9u8);
+    }
+
+    #[test]
+    fn div_write_glitch() {
+        let mut timer = TimerComponent::new();
+
+        timer.write(TAC_ADDRESS, 0x05u8).unwrap();
+
+        // Bit 3 of the counter is now set
+        step(&mut timer, 2);
+
+        timer.write(DIV_ADDRESS, 0x00u8).unwrap();
+
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x01u8);
+    }
+
+    #[test]
+    fn tac_disable_glitch() {
+        let mut timer = TimerComponent::new();
+
+        timer.write(TAC_ADDRESS, 0x05u8).unwrap();
+
+        step(&mut timer, 2);
+
+        timer.write(TAC_ADDRESS, 0x01u8).unwrap();
+
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x01u8);
+    }
+}
+
+mod overflow {
+    use super::*;
+
+    #[test]
+    fn reloads_after_delay() {
+        let mut timer = overflowing_timer();
+
+        assert_eq!(step(&mut timer, 1), Interrupt::Timer as u8);
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn tima_write_cancels_reload() {
+        let mut timer = overflowing_timer();
+
+        timer.write(TIMA_ADDRESS, 0x10u8).unwrap();
+
+        assert_eq!(step(&mut timer, 1), 0x00u8);
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x10u8);
+    }
+
+    #[test]
+    fn tima_write_on_reload_ignored() {
+        let mut timer = overflowing_timer();
+
+        step(&mut timer, 1);
+
+        timer.write(TIMA_ADDRESS, 0x10u8).unwrap();
+
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x42u8);
+    }
+
+    #[test]
+    fn tma_write_on_reload() {
+        let mut timer = overflowing_timer();
+
+        step(&mut timer, 1);
+
+        timer.write(TMA_ADDRESS, 0x99u8).unwrap();
+
+        assert_eq!(timer.read(TIMA_ADDRESS).unwrap(), 0x99u8);
+    }
+
+    #[test]
+    fn requests_interrupt() {
+        let mut emulator = common::program_emulator(&[]);
+
+        emulator.add_memory_component(Box::new(TimerComponent::new()));
+
+        emulator.write(INTERRUPT_ENABLE_ADDRESS, Interrupt::Timer as u8).unwrap();
+        emulator.write(TIMA_ADDRESS, 0xfeu8).unwrap();
+        emulator.write(TAC_ADDRESS, 0x05u8).unwrap();
+
+        for _ in 0..16 {
+            emulator.tick();
+        }
+
+        assert_eq!(emulator.memory_location(INTERRUPT_FLAG_ADDRESS) & 0x1fu8, Interrupt::Timer as u8);
+        assert_eq!(emulator.pending_interrupts(), Interrupt::Timer as u8);
+    }
+}